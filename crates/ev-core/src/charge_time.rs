@@ -0,0 +1,333 @@
+//! Charging time estimates derived from a vehicle's charging data.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ChargingAc, DcChargingTimeEntry, Vehicle};
+use crate::error::ValidationError;
+use crate::validation::{into_result, validate_percent, validate_positive};
+
+/// SOC step used to integrate the DC charge curve, in percentage points.
+const CURVE_STEP_PERCENT: f64 = 0.1;
+
+/// Vehicle data an estimate was derived from, most accurate first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateBasis {
+    /// `charging.dc_charge_curve` integrated over the SOC window.
+    ChargeCurve,
+    /// Average power of the closest `charging.charging_time.dc` entry.
+    ChargingTime,
+    /// Constant power at `charging.dc.max_power_kw`.
+    MaxPower,
+}
+
+/// Result of a charging time estimate.
+///
+/// `energy_kwh` is the energy added to the battery; charger-side losses are not modelled.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChargeEstimate {
+    pub duration_min: f64,
+    pub energy_kwh: f64,
+    pub average_power_kw: f64,
+    pub basis: EstimateBasis,
+}
+
+impl ChargeEstimate {
+    fn new(energy_kwh: f64, duration_min: f64, basis: EstimateBasis) -> Self {
+        Self {
+            duration_min,
+            energy_kwh,
+            average_power_kw: energy_kwh / (duration_min / 60.0),
+            basis,
+        }
+    }
+}
+
+/// Estimates a DC charging session from `from_soc_percent` to `to_soc_percent` on a
+/// charger limited to `charger_power_kw`.
+///
+/// The charge curve is integrated against `Battery::usable_capacity_kwh`, with the power
+/// at each SOC capped by the charger and `charging.dc.max_power_kw`. Without a curve the
+/// closest `charging_time.dc` entry provides the average power, and without either the
+/// session is assumed to run at the lower of the two power limits.
+pub fn estimate_dc_charge(
+    vehicle: &Vehicle,
+    charger_power_kw: f64,
+    from_soc_percent: f64,
+    to_soc_percent: f64,
+) -> Result<ChargeEstimate, ValidationError> {
+    validate_session(charger_power_kw, from_soc_percent, to_soc_percent)?;
+
+    let capacity_kwh = vehicle
+        .battery
+        .usable_capacity_kwh()
+        .ok_or(ValidationError::MissingBatteryCapacity)?;
+    let dc = vehicle
+        .charging
+        .dc
+        .as_ref()
+        .ok_or_else(|| ValidationError::missing_field("charging.dc"))?;
+
+    let limit_kw = charger_power_kw.min(dc.max_power_kw);
+    validate_positive("charging.dc.max_power_kw", limit_kw)?;
+
+    let energy_kwh = capacity_kwh * (to_soc_percent - from_soc_percent) / 100.0;
+
+    if let Some(duration_min) = vehicle.charging.dc_charge_curve.as_ref().and_then(|curve| {
+        integrate(from_soc_percent, to_soc_percent, |soc| {
+            curve.power_at(soc).map(|power| power.min(limit_kw))
+        })
+        .map(|hours| hours * capacity_kwh / 100.0 * 60.0)
+    }) {
+        return Ok(ChargeEstimate::new(
+            energy_kwh,
+            duration_min,
+            EstimateBasis::ChargeCurve,
+        ));
+    }
+
+    let entries = vehicle
+        .charging
+        .charging_time
+        .as_ref()
+        .and_then(|time| time.dc.as_deref())
+        .unwrap_or_default();
+    if let Some(entry) = closest_dc_entry(entries, charger_power_kw) {
+        let entry_energy_kwh =
+            capacity_kwh * (entry.to_soc_percent - entry.from_soc_percent) / 100.0;
+        let power_kw = (entry_energy_kwh / (entry.time_min / 60.0)).min(limit_kw);
+        return Ok(ChargeEstimate::new(
+            energy_kwh,
+            energy_kwh / power_kw * 60.0,
+            EstimateBasis::ChargingTime,
+        ));
+    }
+
+    Ok(ChargeEstimate::new(
+        energy_kwh,
+        energy_kwh / limit_kw * 60.0,
+        EstimateBasis::MaxPower,
+    ))
+}
+
+fn validate_session(
+    charger_power_kw: f64,
+    from_soc_percent: f64,
+    to_soc_percent: f64,
+) -> Result<(), ValidationError> {
+    let mut errors: Vec<ValidationError> = validate_positive("charger_power_kw", charger_power_kw)
+        .err()
+        .into_iter()
+        .collect();
+    soc_window_errors(&mut errors, from_soc_percent, to_soc_percent);
+    into_result(errors)
+}
+
+fn soc_window_errors(
+    errors: &mut Vec<ValidationError>,
+    from_soc_percent: f64,
+    to_soc_percent: f64,
+) {
+    errors.extend(
+        [
+            validate_percent("from_soc_percent", from_soc_percent),
+            validate_percent("to_soc_percent", to_soc_percent),
+        ]
+        .into_iter()
+        .filter_map(Result::err),
+    );
+
+    if from_soc_percent >= to_soc_percent {
+        errors.push(ValidationError::InvalidSocInterval {
+            field: "soc".to_string(),
+            from: from_soc_percent.to_string(),
+            to: to_soc_percent.to_string(),
+        });
+    }
+}
+
+/// Integrates `1 / power(soc)` over the SOC window with the midpoint rule.
+///
+/// The result is in percent-hours per kW: multiplied by the capacity per percent it
+/// gives the duration in hours. `None` when the power is unknown or not positive
+/// somewhere in the window.
+fn integrate(from: f64, to: f64, power_at: impl Fn(f64) -> Option<f64>) -> Option<f64> {
+    let steps = ((to - from) / CURVE_STEP_PERCENT).ceil().max(1.0) as usize;
+    let step = (to - from) / steps as f64;
+
+    (0..steps).try_fold(0.0, |hours, index| {
+        let power = power_at(from + step * (index as f64 + 0.5))?;
+        (power > 0.0).then(|| hours + step / power)
+    })
+}
+
+/// Picks the entry measured on the charger closest in power to the requested one.
+fn closest_dc_entry(
+    entries: &[DcChargingTimeEntry],
+    charger_power_kw: f64,
+) -> Option<&DcChargingTimeEntry> {
+    entries
+        .iter()
+        .filter(|entry| entry.time_min > 0.0 && entry.to_soc_percent > entry.from_soc_percent)
+        .min_by(|a, b| {
+            let distance =
+                |entry: &DcChargingTimeEntry| (entry.charger_power_kw - charger_power_kw).abs();
+            distance(a).total_cmp(&distance(b))
+        })
+}
+
+/// AC supply a vehicle is plugged into: a wallbox or a domestic outlet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AcSupply {
+    /// Phase-to-neutral voltage, e.g. 230 V in Europe or 240 V split-phase in North America.
+    pub voltage_v: f64,
+    pub phases: u8,
+    /// Current available on each phase.
+    pub current_a: f64,
+}
+
+impl AcSupply {
+    /// Power the supply can deliver over all of its phases.
+    #[must_use]
+    pub fn power_kw(&self) -> f64 {
+        self.voltage_v * f64::from(self.phases) * self.current_a / 1000.0
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut errors: Vec<ValidationError> = [
+            validate_positive("supply.voltage_v", self.voltage_v),
+            validate_positive("supply.current_a", self.current_a),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+
+        if !(1..=3).contains(&self.phases) {
+            errors.push(ValidationError::OutOfRange {
+                field: "supply.phases".to_string(),
+                value: self.phases.to_string(),
+                min: "1".to_string(),
+                max: "3".to_string(),
+            });
+        }
+
+        into_result(errors)
+    }
+}
+
+/// Operating point of the onboard charger on a given [`AcSupply`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AcChargePower {
+    pub power_kw: f64,
+    /// Phases actually used: the lower of the supply's and the onboard charger's.
+    pub phases: u8,
+    /// Current drawn on each phase.
+    pub current_a: f64,
+}
+
+/// AC charging session estimate.
+///
+/// `energy_kwh` is the energy added to the battery; onboard charger losses are not modelled.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AcChargeEstimate {
+    #[serde(flatten)]
+    pub power: AcChargePower,
+    pub duration_min: f64,
+    pub energy_kwh: f64,
+}
+
+/// Computes the power the onboard charger accepts from `supply`.
+///
+/// A single-phase charger on a three-phase supply uses one phase, the current is capped
+/// per phase by `max_current_a`, and the power by `max_power_kw`. When
+/// `supported_power_steps_kw` is set, the charger runs at the highest step the supply can
+/// feed. An unknown phase count is assumed to match the supply.
+pub fn ac_charge_power(
+    ac: &ChargingAc,
+    supply: &AcSupply,
+) -> Result<AcChargePower, ValidationError> {
+    supply.validate()?;
+
+    if let Some(ref range) = ac.voltage_range_v
+        && (range.min_v.is_some_and(|min| supply.voltage_v < min)
+            || range.max_v.is_some_and(|max| supply.voltage_v > max))
+    {
+        return Err(ValidationError::OutOfRange {
+            field: "supply.voltage_v".to_string(),
+            value: supply.voltage_v.to_string(),
+            min: range
+                .min_v
+                .map_or_else(|| "0".to_string(), |v| v.to_string()),
+            max: range
+                .max_v
+                .map_or_else(|| "unbounded".to_string(), |v| v.to_string()),
+        });
+    }
+
+    let phases = ac
+        .phases
+        .map_or(supply.phases, |phases| phases.min(supply.phases));
+    let current_a = ac
+        .max_current_a
+        .map_or(supply.current_a, |max| max.min(supply.current_a));
+    let available_kw =
+        (supply.voltage_v * f64::from(phases) * current_a / 1000.0).min(ac.max_power_kw);
+
+    let power_kw = match ac.supported_power_steps_kw.as_deref() {
+        Some(steps) if !steps.is_empty() => steps
+            .iter()
+            .copied()
+            .filter(|&step| step > 0.0 && step <= available_kw)
+            .max_by(f64::total_cmp)
+            .ok_or_else(|| ValidationError::ExceedsLimit {
+                field: "ac.supported_power_steps_kw".to_string(),
+                value: steps
+                    .iter()
+                    .copied()
+                    .fold(f64::INFINITY, f64::min)
+                    .to_string(),
+                limit_field: "supply power_kw".to_string(),
+                limit: available_kw.to_string(),
+            })?,
+        _ => available_kw,
+    };
+
+    Ok(AcChargePower {
+        power_kw,
+        phases,
+        current_a: power_kw * 1000.0 / (supply.voltage_v * f64::from(phases)),
+    })
+}
+
+/// Estimates an AC charging session from `from_soc_percent` to `to_soc_percent` on
+/// `supply`, at the constant power given by [`ac_charge_power`].
+pub fn estimate_ac_charge(
+    vehicle: &Vehicle,
+    supply: &AcSupply,
+    from_soc_percent: f64,
+    to_soc_percent: f64,
+) -> Result<AcChargeEstimate, ValidationError> {
+    let mut errors = Vec::new();
+    soc_window_errors(&mut errors, from_soc_percent, to_soc_percent);
+    into_result(errors)?;
+
+    let capacity_kwh = vehicle
+        .battery
+        .usable_capacity_kwh()
+        .ok_or(ValidationError::MissingBatteryCapacity)?;
+    let ac = vehicle
+        .charging
+        .ac
+        .as_ref()
+        .ok_or_else(|| ValidationError::missing_field("charging.ac"))?;
+
+    let power = ac_charge_power(ac, supply)?;
+    validate_positive("charging.ac.max_power_kw", power.power_kw)?;
+
+    let energy_kwh = capacity_kwh * (to_soc_percent - from_soc_percent) / 100.0;
+    Ok(AcChargeEstimate {
+        power,
+        duration_min: energy_kwh / power.power_kw * 60.0,
+        energy_kwh,
+    })
+}
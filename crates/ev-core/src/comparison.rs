@@ -0,0 +1,313 @@
+//! Side-by-side comparison of vehicles.
+//!
+//! [`compare`] lines up every field of the vehicles by JSON pointer and scores
+//! the headline specs: each metric marks the best and worst vehicle and gives
+//! every value's difference to the best one and to the first vehicle.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::diff::pointer_token;
+use crate::domain::Vehicle;
+
+/// Direction in which a metric improves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Better {
+    Higher,
+    Lower,
+}
+
+/// A numeric spec compared across vehicles.
+#[derive(Debug, Clone, Copy)]
+pub struct Metric {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub unit: &'static str,
+    pub better: Better,
+    value: fn(&Vehicle) -> Option<f64>,
+}
+
+impl Metric {
+    /// Returns this metric for `vehicle`, `None` when the vehicle lacks the data.
+    #[must_use]
+    pub fn value(&self, vehicle: &Vehicle) -> Option<f64> {
+        (self.value)(vehicle)
+    }
+}
+
+/// Metrics compared by [`compare`], besides the MSRP.
+pub const METRICS: &[Metric] = &[
+    Metric {
+        key: "range_wltp_km",
+        label: "WLTP range",
+        unit: "km",
+        better: Better::Higher,
+        value: Vehicle::wltp_range_km,
+    },
+    Metric {
+        key: "range_epa_km",
+        label: "EPA range",
+        unit: "km",
+        better: Better::Higher,
+        value: Vehicle::epa_range_km,
+    },
+    Metric {
+        key: "battery_capacity_kwh",
+        label: "Usable battery capacity",
+        unit: "kWh",
+        better: Better::Higher,
+        value: Vehicle::usable_battery_kwh,
+    },
+    Metric {
+        key: "dc_max_power_kw",
+        label: "Maximum DC charging power",
+        unit: "kW",
+        better: Better::Higher,
+        value: Vehicle::max_dc_power_kw,
+    },
+    Metric {
+        key: "ac_max_power_kw",
+        label: "Maximum AC charging power",
+        unit: "kW",
+        better: Better::Higher,
+        value: Vehicle::max_ac_power_kw,
+    },
+    Metric {
+        key: "acceleration_0_100_s",
+        label: "Acceleration 0-100 km/h",
+        unit: "s",
+        better: Better::Lower,
+        value: |vehicle| vehicle.performance.as_ref()?.acceleration_0_100_kmh_s,
+    },
+    Metric {
+        key: "energy_consumption_wh_per_km",
+        label: "Energy consumption",
+        unit: "Wh/km",
+        better: Better::Lower,
+        value: |vehicle| vehicle.efficiency.as_ref()?.energy_consumption_wh_per_km,
+    },
+];
+
+/// Result of [`compare`]; every `values` list follows the order of the compared vehicles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Comparison {
+    pub metrics: Vec<MetricComparison>,
+    pub fields: Vec<FieldComparison>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct MetricComparison {
+    pub key: String,
+    pub label: String,
+    /// Unit of the values; the currency code for `msrp`.
+    pub unit: String,
+    pub better: Better,
+    pub values: Vec<MetricValue>,
+}
+
+/// One vehicle's value of a metric.
+///
+/// Markers and deltas are only set when at least two vehicles have a value.
+/// Tied vehicles share the marker, and nobody is worst when all values are equal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct MetricValue {
+    pub value: Option<f64>,
+    pub best: bool,
+    pub worst: bool,
+    /// `value` minus the best value.
+    pub delta_to_best: Option<f64>,
+    /// `delta_to_best` as a percentage of the best value.
+    pub delta_to_best_percent: Option<f64>,
+    /// `value` minus the first vehicle's value.
+    pub delta_to_baseline: Option<f64>,
+}
+
+/// One leaf field of the vehicle documents, addressed by JSON pointer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FieldComparison {
+    pub path: String,
+    /// Each vehicle's value, `None` when the vehicle lacks the field.
+    #[cfg_attr(feature = "utoipa", schema(value_type = Vec<Object>))]
+    pub values: Vec<Option<serde_json::Value>>,
+    /// Whether the vehicles disagree on the field, a missing value counting as different.
+    pub differs: bool,
+}
+
+/// Compares `vehicles` metric by metric and field by field.
+///
+/// The MSRP metric (`msrp`) uses each vehicle's lowest price in `currency`,
+/// or in the currency most vehicles are priced in when `currency` is `None`;
+/// it is left out when no vehicle has a price.
+#[must_use]
+pub fn compare(vehicles: &[Vehicle], currency: Option<&str>) -> Comparison {
+    let mut metrics: Vec<MetricComparison> = METRICS
+        .iter()
+        .map(|metric| MetricComparison {
+            key: metric.key.to_string(),
+            label: metric.label.to_string(),
+            unit: metric.unit.to_string(),
+            better: metric.better,
+            values: score(
+                &vehicles.iter().map(|v| metric.value(v)).collect::<Vec<_>>(),
+                metric.better,
+            ),
+        })
+        .collect();
+
+    let currency = currency
+        .map(str::to_string)
+        .or_else(|| most_common_currency(vehicles));
+    if let Some(currency) = currency {
+        let prices: Vec<Option<f64>> = vehicles
+            .iter()
+            .map(|vehicle| lowest_msrp(vehicle, &currency))
+            .collect();
+        metrics.push(MetricComparison {
+            key: "msrp".to_string(),
+            label: "MSRP".to_string(),
+            unit: currency,
+            better: Better::Lower,
+            values: score(&prices, Better::Lower),
+        });
+    }
+
+    Comparison {
+        metrics,
+        fields: align_fields(vehicles),
+    }
+}
+
+/// Lowest MSRP of `vehicle` in `currency`, across markets and years.
+#[must_use]
+pub fn lowest_msrp(vehicle: &Vehicle, currency: &str) -> Option<f64> {
+    vehicle
+        .pricing
+        .as_ref()?
+        .msrp
+        .iter()
+        .flatten()
+        .filter(|msrp| msrp.currency == currency)
+        .map(|msrp| msrp.amount)
+        .min_by(f64::total_cmp)
+}
+
+fn most_common_currency(vehicles: &[Vehicle]) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for vehicle in vehicles {
+        let currencies: HashSet<&str> = vehicle
+            .pricing
+            .iter()
+            .flat_map(|pricing| pricing.msrp.iter().flatten())
+            .map(|msrp| msrp.currency.as_str())
+            .collect();
+        for currency in currencies {
+            *counts.entry(currency).or_default() += 1;
+        }
+    }
+
+    // Ties go to the alphabetically first currency.
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(currency, _)| currency.to_string())
+}
+
+fn score(values: &[Option<f64>], better: Better) -> Vec<MetricValue> {
+    let present: Vec<f64> = values.iter().flatten().copied().collect();
+    let range = (present.len() >= 2).then(|| {
+        let min = present.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = present.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        match better {
+            Better::Higher => (max, min),
+            Better::Lower => (min, max),
+        }
+    });
+    let baseline = values.first().copied().flatten();
+
+    values
+        .iter()
+        .map(|&value| {
+            let (Some(value), Some((best, worst))) = (value, range) else {
+                return MetricValue {
+                    value,
+                    best: false,
+                    worst: false,
+                    delta_to_best: None,
+                    delta_to_best_percent: None,
+                    delta_to_baseline: None,
+                };
+            };
+            MetricValue {
+                value: Some(value),
+                best: value == best,
+                worst: value == worst && best != worst,
+                delta_to_best: Some(value - best),
+                delta_to_best_percent: (best != 0.0).then(|| (value - best) / best * 100.0),
+                delta_to_baseline: baseline.map(|baseline| value - baseline),
+            }
+        })
+        .collect()
+}
+
+fn align_fields(vehicles: &[Vehicle]) -> Vec<FieldComparison> {
+    let documents: Vec<BTreeMap<String, serde_json::Value>> = vehicles
+        .iter()
+        .map(|vehicle| {
+            let mut leaves = BTreeMap::new();
+            if let Ok(document) = serde_json::to_value(vehicle) {
+                collect_leaves(&document, String::new(), &mut leaves);
+            }
+            leaves
+        })
+        .collect();
+
+    let mut paths: Vec<&String> = documents.iter().flat_map(BTreeMap::keys).collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let values: Vec<Option<serde_json::Value>> = documents
+                .iter()
+                .map(|leaves| leaves.get(path).cloned())
+                .collect();
+            let differs = values.windows(2).any(|pair| pair[0] != pair[1]);
+            FieldComparison {
+                path: path.clone(),
+                values,
+                differs,
+            }
+        })
+        .collect()
+}
+
+fn collect_leaves(
+    value: &serde_json::Value,
+    pointer: String,
+    leaves: &mut BTreeMap<String, serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                collect_leaves(child, format!("{pointer}/{}", pointer_token(key)), leaves);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                collect_leaves(child, format!("{pointer}/{index}"), leaves);
+            }
+        }
+        leaf => {
+            leaves.insert(pointer, leaf.clone());
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Charger compatibility: which charge port a vehicle plugs in with, through which
+//! adapter, and at what maximum power.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::enums::{ChargerVoltageClass, ConnectorType, PortKind};
+use crate::domain::{ChargePort, ChargingDc, Vehicle};
+
+/// Current delivered by a charger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CurrentType {
+    Ac,
+    Dc,
+}
+
+wire_strings!(CurrentType, "current_type", {
+    Ac => "ac",
+    Dc => "dc",
+});
+
+impl CurrentType {
+    /// Current carried by a connector, or `None` for connectors used for both
+    /// (NACS, Tesla Type 2, other).
+    #[must_use]
+    pub fn of_connector(connector: ConnectorType) -> Option<Self> {
+        match connector {
+            ConnectorType::Type1 | ConnectorType::Type2 | ConnectorType::GbTAc => Some(Self::Ac),
+            ConnectorType::Ccs1
+            | ConnectorType::Ccs2
+            | ConnectorType::Chademo
+            | ConnectorType::GbTDc => Some(Self::Dc),
+            ConnectorType::Nacs | ConnectorType::TeslaType2 | ConnectorType::Other => None,
+        }
+    }
+}
+
+/// A charger as advertised at the station: its plug, current and power.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ChargerSpec {
+    pub connector: ConnectorType,
+    pub current: CurrentType,
+    pub max_power_kw: f64,
+    /// Output voltage class of a DC charger, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voltage_class: Option<ChargerVoltageClass>,
+}
+
+/// Adapter between a charger plug and a different vehicle inlet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Adapter {
+    /// Plug of the charger the adapter goes on.
+    pub charger: ConnectorType,
+    /// Plug the adapter presents to the vehicle.
+    pub vehicle: ConnectorType,
+    pub current: CurrentType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_power_kw: Option<f64>,
+}
+
+/// Commonly available adapters.
+pub const ADAPTERS: &[Adapter] = &[
+    Adapter {
+        charger: ConnectorType::Ccs1,
+        vehicle: ConnectorType::Nacs,
+        current: CurrentType::Dc,
+        max_power_kw: None,
+    },
+    Adapter {
+        charger: ConnectorType::Nacs,
+        vehicle: ConnectorType::Ccs1,
+        current: CurrentType::Dc,
+        max_power_kw: None,
+    },
+    Adapter {
+        charger: ConnectorType::Type1,
+        vehicle: ConnectorType::Nacs,
+        current: CurrentType::Ac,
+        max_power_kw: None,
+    },
+    Adapter {
+        charger: ConnectorType::Nacs,
+        vehicle: ConnectorType::Type1,
+        current: CurrentType::Ac,
+        max_power_kw: None,
+    },
+    Adapter {
+        charger: ConnectorType::Chademo,
+        vehicle: ConnectorType::Nacs,
+        current: CurrentType::Dc,
+        max_power_kw: Some(50.0),
+    },
+];
+
+/// What caps the power of a charging session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PowerLimit {
+    Charger,
+    Vehicle,
+    /// The vehicle's `power_limits_by_voltage` entry for the charger's voltage class.
+    VoltageClass,
+    Adapter,
+}
+
+/// How a vehicle connects to a charger and the power it can draw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Compatibility {
+    /// Connector of the vehicle's charge port used.
+    pub port: ConnectorType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adapter: Option<Adapter>,
+    pub max_power_kw: f64,
+    pub limited_by: PowerLimit,
+}
+
+/// Returns how `vehicle` can charge on `charger`, or `None` when it cannot.
+///
+/// Native connections are preferred over adapters from `adapters` (pass [`ADAPTERS`]
+/// for the common ones, or an empty slice for native only); among equals the highest
+/// power wins. DC power is capped by the vehicle's `power_limits_by_voltage` entry for
+/// the charger's voltage class, so an 800 V vehicle on a 400 V charger gets its 400 V limit.
+/// Without `charging.ac` or `charging.dc` data only the charger and adapter limit the power.
+#[must_use]
+pub fn check_compatibility(
+    vehicle: &Vehicle,
+    charger: &ChargerSpec,
+    adapters: &[Adapter],
+) -> Option<Compatibility> {
+    let vehicle_limit = vehicle_power_limit(vehicle, charger);
+
+    let native = vehicle
+        .charge_ports
+        .iter()
+        .filter(|port| accepts(port, charger.connector, charger.current))
+        .map(|port| (port, None));
+    let adapted = adapters
+        .iter()
+        .filter(|adapter| {
+            adapter.charger == charger.connector && adapter.current == charger.current
+        })
+        .flat_map(|adapter| {
+            vehicle
+                .charge_ports
+                .iter()
+                .filter(|port| accepts(port, adapter.vehicle, adapter.current))
+                .map(move |port| (port, Some(*adapter)))
+        });
+
+    native
+        .chain(adapted)
+        .map(|(port, adapter)| {
+            let mut limit = (charger.max_power_kw, PowerLimit::Charger);
+            if let Some(vehicle_limit) = vehicle_limit
+                && vehicle_limit.0 < limit.0
+            {
+                limit = vehicle_limit;
+            }
+            if let Some(adapter_limit) = adapter.and_then(|adapter| adapter.max_power_kw)
+                && adapter_limit < limit.0
+            {
+                limit = (adapter_limit, PowerLimit::Adapter);
+            }
+
+            Compatibility {
+                port: port.connector,
+                adapter,
+                max_power_kw: limit.0,
+                limited_by: limit.1,
+            }
+        })
+        .min_by(|a, b| {
+            a.adapter
+                .is_some()
+                .cmp(&b.adapter.is_some())
+                .then(b.max_power_kw.total_cmp(&a.max_power_kw))
+        })
+}
+
+/// Port connectors a vehicle needs for [`check_compatibility`] to connect it to
+/// `charger`, natively or through `adapters`.
+///
+/// Vehicles without a charge port of one of these connectors are never
+/// compatible, so storage can select candidates by port before checking them.
+#[must_use]
+pub fn candidate_port_connectors(
+    charger: &ChargerSpec,
+    adapters: &[Adapter],
+) -> Vec<ConnectorType> {
+    let plugs: Vec<ConnectorType> = std::iter::once(charger.connector)
+        .chain(
+            adapters
+                .iter()
+                .filter(|adapter| {
+                    adapter.charger == charger.connector && adapter.current == charger.current
+                })
+                .map(|adapter| adapter.vehicle),
+        )
+        .collect();
+
+    ConnectorType::ALL
+        .iter()
+        .copied()
+        .filter(|&port| plugs.iter().any(|&plug| plug_fits(port, plug)))
+        .collect()
+}
+
+/// Returns `true` when `plug` fits the port and the port takes `current` through it.
+fn accepts(port: &ChargePort, plug: ConnectorType, current: CurrentType) -> bool {
+    let port_current = match port.kind {
+        PortKind::AcOnly => current == CurrentType::Ac,
+        PortKind::DcOnly => current == CurrentType::Dc,
+        PortKind::Combo => true,
+    };
+
+    port_current
+        && plug_fits(port.connector, plug)
+        && CurrentType::of_connector(plug).is_none_or(|plug_current| plug_current == current)
+}
+
+/// Returns `true` when `plug` goes into a `port` inlet, e.g. a Type 2 plug into CCS2.
+fn plug_fits(port: ConnectorType, plug: ConnectorType) -> bool {
+    plug == port
+        || matches!(
+            (port, plug),
+            (ConnectorType::Ccs1, ConnectorType::Type1)
+                | (ConnectorType::Ccs2, ConnectorType::Type2)
+                | (ConnectorType::TeslaType2, ConnectorType::Type2)
+        )
+}
+
+fn vehicle_power_limit(vehicle: &Vehicle, charger: &ChargerSpec) -> Option<(f64, PowerLimit)> {
+    match charger.current {
+        CurrentType::Ac => Some((
+            vehicle.charging.ac.as_ref()?.max_power_kw,
+            PowerLimit::Vehicle,
+        )),
+        CurrentType::Dc => {
+            let dc = vehicle.charging.dc.as_ref()?;
+            Some(
+                charger
+                    .voltage_class
+                    .and_then(|class| voltage_class_limit(dc, class))
+                    .filter(|&limit| limit < dc.max_power_kw)
+                    .map_or((dc.max_power_kw, PowerLimit::Vehicle), |limit| {
+                        (limit, PowerLimit::VoltageClass)
+                    }),
+            )
+        }
+    }
+}
+
+fn voltage_class_limit(dc: &ChargingDc, class: ChargerVoltageClass) -> Option<f64> {
+    dc.power_limits_by_voltage
+        .iter()
+        .flatten()
+        .find(|limit| limit.voltage_class.eq_ignore_ascii_case(class.as_str()))
+        .map(|limit| limit.max_power_kw)
+}
@@ -0,0 +1,145 @@
+//! Structural diff between two vehicle records.
+//!
+//! Both vehicles are compared as their serialized JSON documents. Objects are
+//! compared key by key and arrays index by index, so every change is located
+//! by an RFC 6901 JSON pointer. A field present on one side only is reported
+//! once with its whole value instead of one entry per nested leaf.
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::Vehicle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Present in the new vehicle only.
+    Added,
+    /// Present in the old vehicle only.
+    Removed,
+    /// Present in both with different values.
+    Changed,
+}
+
+/// One difference between two vehicles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FieldChange {
+    /// JSON pointer to the field, e.g. `/charging/dc/max_power_kw`.
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Old value, `None` for added fields.
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<Object>))]
+    pub old: Option<Value>,
+    /// New value, `None` for removed fields.
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<Object>))]
+    pub new: Option<Value>,
+}
+
+/// Every difference between two vehicles, ordered by path.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct VehicleDiff {
+    pub changes: Vec<FieldChange>,
+}
+
+impl VehicleDiff {
+    /// Returns `true` when the vehicles are identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the changes of `kind`.
+    pub fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &FieldChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.kind == kind)
+    }
+}
+
+/// Lists the fields added, removed or changed from `old` to `new`.
+#[must_use]
+pub fn diff_vehicles(old: &Vehicle, new: &Vehicle) -> VehicleDiff {
+    // `Vehicle` derives `Serialize` over structs, sequences, strings and numbers and has
+    // no maps with non-string keys, the only thing `to_value` rejects.
+    let old = serde_json::to_value(old).expect("a Vehicle always serializes to JSON");
+    let new = serde_json::to_value(new).expect("a Vehicle always serializes to JSON");
+
+    diff_documents(&old, &new)
+}
+
+/// Like [`diff_vehicles`], for vehicle JSON documents that need not deserialize into
+/// a [`Vehicle`], e.g. of a release written for another schema version.
+#[must_use]
+pub fn diff_documents(old: &Value, new: &Value) -> VehicleDiff {
+    let mut changes = Vec::new();
+    diff_values(String::new(), old, new, &mut changes);
+    VehicleDiff { changes }
+}
+
+/// Escapes an object key for use as a JSON pointer token (RFC 6901).
+pub fn pointer_token(key: &str) -> Cow<'_, str> {
+    if key.contains(['~', '/']) {
+        Cow::Owned(key.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+fn diff_values(path: String, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let path = format!("{path}/{}", pointer_token(key));
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_values(path, old, new, changes),
+                    (Some(old), None) => changes.push(removed(path, old)),
+                    (None, Some(new)) => changes.push(added(path, new)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let path = format!("{path}/{index}");
+                match (old.get(index), new.get(index)) {
+                    (Some(old), Some(new)) => diff_values(path, old, new, changes),
+                    (Some(old), None) => changes.push(removed(path, old)),
+                    (None, Some(new)) => changes.push(added(path, new)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (old, new) if old != new => changes.push(FieldChange {
+            path,
+            kind: ChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn added(path: String, value: &Value) -> FieldChange {
+    FieldChange {
+        path,
+        kind: ChangeKind::Added,
+        old: None,
+        new: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &Value) -> FieldChange {
+    FieldChange {
+        path,
+        kind: ChangeKind::Removed,
+        old: Some(value.clone()),
+        new: None,
+    }
+}
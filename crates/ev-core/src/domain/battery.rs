@@ -1,8 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::enums::ThermalManagement;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Battery {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manufacturer: Option<String>,
@@ -63,18 +65,142 @@ impl Battery {
 }
 
 use crate::error::ValidationError;
-use crate::validation::Validate;
+use crate::validation::{
+    Finding, Validate, push_error, push_nested, validate_at_most, validate_bounds,
+    validate_percent, validate_positive,
+};
 
 impl Validate for Battery {
-    fn validate(&self) -> Result<(), ValidationError> {
+    fn findings(&self) -> Vec<Finding> {
         if !self.has_capacity() {
-            return Err(ValidationError::MissingBatteryCapacity);
+            return vec![Finding::new("", ValidationError::MissingBatteryCapacity)];
         }
-        Ok(())
+
+        let mut findings = Vec::new();
+
+        if let Some(gross) = self.pack_capacity_kwh_gross {
+            push_error(
+                &mut findings,
+                "/pack_capacity_kwh_gross",
+                validate_positive("pack_capacity_kwh_gross", gross),
+            );
+        }
+
+        if let Some(net) = self.pack_capacity_kwh_net {
+            push_error(
+                &mut findings,
+                "/pack_capacity_kwh_net",
+                validate_positive("pack_capacity_kwh_net", net),
+            );
+        }
+
+        if let (Some(net), Some(gross)) = (self.pack_capacity_kwh_net, self.pack_capacity_kwh_gross)
+        {
+            push_error(
+                &mut findings,
+                "/pack_capacity_kwh_net",
+                validate_at_most(
+                    "pack_capacity_kwh_net",
+                    net,
+                    "pack_capacity_kwh_gross",
+                    gross,
+                ),
+            );
+        }
+
+        let voltages = [
+            ("pack_voltage_min_v", self.pack_voltage_min_v),
+            ("pack_voltage_nominal_v", self.pack_voltage_nominal_v),
+            ("pack_voltage_max_v", self.pack_voltage_max_v),
+        ];
+        for (field, voltage) in voltages {
+            if let Some(voltage) = voltage {
+                push_error(
+                    &mut findings,
+                    &format!("/{field}"),
+                    validate_positive(field, voltage),
+                );
+            }
+        }
+
+        let present: Vec<(&str, f64)> = voltages
+            .iter()
+            .filter_map(|(field, v)| v.map(|v| (*field, v)))
+            .collect();
+        for pair in present.windows(2) {
+            push_error(
+                &mut findings,
+                &format!("/{}", pair[1].0),
+                validate_bounds("pack_voltage_v", pair[0].1, pair[1].1),
+            );
+        }
+
+        if let Some(ref warranty) = self.warranty {
+            push_nested(&mut findings, "/warranty", warranty.findings());
+        }
+
+        if let Some(ref window) = self.usable_soc_window_percent {
+            push_nested(
+                &mut findings,
+                "/usable_soc_window_percent",
+                window.findings(),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for Warranty {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(retention) = self.capacity_retention_percent {
+            push_error(
+                &mut findings,
+                "/capacity_retention_percent",
+                validate_percent("warranty.capacity_retention_percent", retention),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for UsableSocWindow {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(min) = self.min_percent {
+            push_error(
+                &mut findings,
+                "/min_percent",
+                validate_percent("usable_soc_window_percent.min_percent", min),
+            );
+        }
+
+        if let Some(max) = self.max_percent {
+            push_error(
+                &mut findings,
+                "/max_percent",
+                validate_percent("usable_soc_window_percent.max_percent", max),
+            );
+        }
+
+        if let (Some(min), Some(max)) = (self.min_percent, self.max_percent) {
+            push_error(
+                &mut findings,
+                "/max_percent",
+                validate_bounds("usable_soc_window_percent", min, max),
+            );
+        }
+
+        findings
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Preconditioning {
     pub supported: bool,
 
@@ -85,7 +211,8 @@ pub struct Preconditioning {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Warranty {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub years: Option<u32>,
@@ -97,7 +224,8 @@ pub struct Warranty {
     pub capacity_retention_percent: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UsableSocWindow {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_percent: Option<f64>,
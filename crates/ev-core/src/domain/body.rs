@@ -1,6 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+use crate::error::ValidationError;
+use crate::validation::{Finding, Validate, push_error, validate_at_most, validate_positive};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Body {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<String>,
@@ -21,7 +26,8 @@ pub struct Body {
     pub drag_coefficient_cd: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Dimensions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub length_mm: Option<f64>,
@@ -45,7 +51,8 @@ pub struct Dimensions {
     pub turning_circle_m: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Weights {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub curb_weight_kg: Option<f64>,
@@ -60,7 +67,8 @@ pub struct Weights {
     pub roof_load_kg: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Capacity {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cargo_l: Option<f64>,
@@ -81,7 +89,8 @@ pub struct Capacity {
     pub towing_vertical_load_kg: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Performance {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub acceleration_0_100_kmh_s: Option<f64>,
@@ -95,3 +104,111 @@ pub struct Performance {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quarter_mile_s: Option<f64>,
 }
+
+impl Validate for Body {
+    fn findings(&self) -> Vec<Finding> {
+        match self.drag_coefficient_cd {
+            Some(cd) if !(cd > 0.0 && cd <= 1.0) => vec![Finding::new(
+                "/drag_coefficient_cd",
+                ValidationError::OutOfRange {
+                    field: "body.drag_coefficient_cd".to_string(),
+                    value: cd.to_string(),
+                    min: "0".to_string(),
+                    max: "1".to_string(),
+                },
+            )],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Validate for Dimensions {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = positive_findings(
+            "dimensions",
+            &[
+                ("length_mm", self.length_mm),
+                ("width_mm", self.width_mm),
+                ("width_with_mirrors_mm", self.width_with_mirrors_mm),
+                ("height_mm", self.height_mm),
+                ("wheelbase_mm", self.wheelbase_mm),
+                ("ground_clearance_mm", self.ground_clearance_mm),
+                ("turning_circle_m", self.turning_circle_m),
+            ],
+        );
+
+        if let (Some(wheelbase), Some(length)) = (self.wheelbase_mm, self.length_mm) {
+            push_error(
+                &mut findings,
+                "/wheelbase_mm",
+                validate_at_most(
+                    "dimensions.wheelbase_mm",
+                    wheelbase,
+                    "dimensions.length_mm",
+                    length,
+                ),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for Weights {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = positive_findings(
+            "weights",
+            &[
+                ("curb_weight_kg", self.curb_weight_kg),
+                ("gross_vehicle_weight_kg", self.gross_vehicle_weight_kg),
+                ("max_payload_kg", self.max_payload_kg),
+                ("roof_load_kg", self.roof_load_kg),
+            ],
+        );
+
+        if let (Some(curb), Some(gross)) = (self.curb_weight_kg, self.gross_vehicle_weight_kg) {
+            push_error(
+                &mut findings,
+                "/curb_weight_kg",
+                validate_at_most(
+                    "weights.curb_weight_kg",
+                    curb,
+                    "weights.gross_vehicle_weight_kg",
+                    gross,
+                ),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for Performance {
+    fn findings(&self) -> Vec<Finding> {
+        positive_findings(
+            "performance",
+            &[
+                ("acceleration_0_100_kmh_s", self.acceleration_0_100_kmh_s),
+                ("acceleration_0_60_mph_s", self.acceleration_0_60_mph_s),
+                ("top_speed_kmh", self.top_speed_kmh),
+                ("quarter_mile_s", self.quarter_mile_s),
+            ],
+        )
+    }
+}
+
+fn positive_findings(section: &str, values: &[(&str, Option<f64>)]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (key, value) in values {
+        if let Some(value) = value {
+            push_error(
+                &mut findings,
+                &format!("/{key}"),
+                validate_positive(&format!("{section}.{key}"), *value),
+            );
+        }
+    }
+
+    findings
+}
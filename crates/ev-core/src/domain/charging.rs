@@ -1,10 +1,17 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::enums::{
     ChargeCurveType, ChargerVoltageClass, ConnectorType, PortKind, PortLocation, PortPosition,
 };
+use crate::error::ValidationError;
+use crate::validation::{
+    Finding, Validate, push_error, push_nested, validate_at_most, validate_bounds,
+    validate_non_negative, validate_percent, validate_positive,
+};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargePort {
     pub kind: PortKind,
     pub connector: ConnectorType,
@@ -25,7 +32,8 @@ pub struct ChargePort {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargePortLocation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub side: Option<PortLocation>,
@@ -37,7 +45,8 @@ pub struct ChargePortLocation {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Charging {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ac: Option<ChargingAc>,
@@ -55,7 +64,8 @@ pub struct Charging {
     pub charging_time: Option<ChargingTime>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargingAc {
     pub max_power_kw: f64,
 
@@ -81,7 +91,8 @@ pub struct ChargingAc {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargingDc {
     pub max_power_kw: f64,
 
@@ -101,7 +112,8 @@ pub struct ChargingDc {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct VoltageRange {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_v: Option<f64>,
@@ -110,7 +122,8 @@ pub struct VoltageRange {
     pub max_v: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct PowerLimitByVoltage {
     pub voltage_class: String,
     pub max_power_kw: f64,
@@ -119,7 +132,8 @@ pub struct PowerLimitByVoltage {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargingProtocols {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ac: Option<Vec<String>>,
@@ -134,7 +148,8 @@ pub struct ChargingProtocols {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargeCurve {
     pub curve_type: ChargeCurveType,
     pub points: Vec<ChargeCurvePoint>,
@@ -149,7 +164,8 @@ pub struct ChargeCurve {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargeCurvePoint {
     pub soc_percent: f64,
 
@@ -163,7 +179,8 @@ pub struct ChargeCurvePoint {
     pub voltage_v: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Conditions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub battery_temp_c: Option<f64>,
@@ -181,7 +198,8 @@ pub struct Conditions {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargingTime {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ac: Option<Vec<ChargingTimeEntry>>,
@@ -190,7 +208,8 @@ pub struct ChargingTime {
     pub dc: Option<Vec<DcChargingTimeEntry>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ChargingTimeEntry {
     pub power_kw: f64,
     pub from_soc_percent: f64,
@@ -204,7 +223,8 @@ pub struct ChargingTimeEntry {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct DcChargingTimeEntry {
     pub charger_power_kw: f64,
     pub from_soc_percent: f64,
@@ -218,7 +238,8 @@ pub struct DcChargingTimeEntry {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct V2X {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub v2l: Option<V2L>,
@@ -230,7 +251,8 @@ pub struct V2X {
     pub v2g: Option<V2G>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct V2L {
     pub supported: bool,
 
@@ -244,7 +266,8 @@ pub struct V2L {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct V2LOutlet {
     pub kind: String,
 
@@ -258,7 +281,8 @@ pub struct V2LOutlet {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct V2H {
     pub supported: bool,
 
@@ -275,7 +299,8 @@ pub struct V2H {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct V2G {
     pub supported: bool,
 
@@ -291,3 +316,443 @@ pub struct V2G {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
 }
+
+impl Validate for Charging {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(ref ac) = self.ac {
+            push_nested(&mut findings, "/ac", ac.findings());
+        }
+
+        if let Some(ref dc) = self.dc {
+            push_nested(&mut findings, "/dc", dc.findings());
+        }
+
+        if let Some(ref curve) = self.dc_charge_curve {
+            push_nested(&mut findings, "/dc_charge_curve", curve.findings());
+
+            if let Some(ref dc) = self.dc {
+                push_nested(
+                    &mut findings,
+                    "/dc_charge_curve",
+                    curve.findings_against(dc),
+                );
+            }
+        }
+
+        if let Some(ref charging_time) = self.charging_time {
+            push_nested(&mut findings, "/charging_time", charging_time.findings());
+        }
+
+        findings
+    }
+}
+
+impl Validate for ChargingAc {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(
+            &mut findings,
+            "/max_power_kw",
+            validate_positive("ac.max_power_kw", self.max_power_kw),
+        );
+
+        if let Some(phases) = self.phases
+            && !(1..=3).contains(&phases)
+        {
+            findings.push(Finding::new(
+                "/phases",
+                ValidationError::OutOfRange {
+                    field: "ac.phases".to_string(),
+                    value: phases.to_string(),
+                    min: "1".to_string(),
+                    max: "3".to_string(),
+                },
+            ));
+        }
+
+        if let Some(current) = self.max_current_a {
+            push_error(
+                &mut findings,
+                "/max_current_a",
+                validate_positive("ac.max_current_a", current),
+            );
+        }
+
+        if let Some(frequency) = self.frequency_hz {
+            push_error(
+                &mut findings,
+                "/frequency_hz",
+                validate_positive("ac.frequency_hz", frequency),
+            );
+        }
+
+        if let Some(ref range) = self.voltage_range_v {
+            push_nested(&mut findings, "/voltage_range_v", range.findings());
+        }
+
+        for (index, step) in self.supported_power_steps_kw.iter().flatten().enumerate() {
+            let pointer = format!("/supported_power_steps_kw/{index}");
+            push_error(
+                &mut findings,
+                &pointer,
+                validate_positive("ac.supported_power_steps_kw", *step),
+            );
+            push_error(
+                &mut findings,
+                &pointer,
+                validate_at_most(
+                    "ac.supported_power_steps_kw",
+                    *step,
+                    "ac.max_power_kw",
+                    self.max_power_kw,
+                ),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for ChargingDc {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(
+            &mut findings,
+            "/max_power_kw",
+            validate_positive("dc.max_power_kw", self.max_power_kw),
+        );
+
+        if let Some(current) = self.max_current_a {
+            push_error(
+                &mut findings,
+                "/max_current_a",
+                validate_positive("dc.max_current_a", current),
+            );
+        }
+
+        if let Some(ref range) = self.voltage_range_v {
+            push_nested(&mut findings, "/voltage_range_v", range.findings());
+        }
+
+        for (index, limit) in self.power_limits_by_voltage.iter().flatten().enumerate() {
+            let pointer = format!("/power_limits_by_voltage/{index}/max_power_kw");
+            push_error(
+                &mut findings,
+                &pointer,
+                validate_positive(
+                    "dc.power_limits_by_voltage.max_power_kw",
+                    limit.max_power_kw,
+                ),
+            );
+            push_error(
+                &mut findings,
+                &pointer,
+                validate_at_most(
+                    "dc.power_limits_by_voltage.max_power_kw",
+                    limit.max_power_kw,
+                    "dc.max_power_kw",
+                    self.max_power_kw,
+                ),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for VoltageRange {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(min) = self.min_v {
+            push_error(
+                &mut findings,
+                "/min_v",
+                validate_positive("voltage_range_v.min_v", min),
+            );
+        }
+
+        if let Some(max) = self.max_v {
+            push_error(
+                &mut findings,
+                "/max_v",
+                validate_positive("voltage_range_v.max_v", max),
+            );
+        }
+
+        if let (Some(min), Some(max)) = (self.min_v, self.max_v) {
+            push_error(
+                &mut findings,
+                "/min_v",
+                validate_bounds("voltage_range_v", min, max),
+            );
+        }
+
+        findings
+    }
+}
+
+impl ChargeCurvePoint {
+    /// Charging power at this point, derived from current and voltage when not given directly.
+    #[must_use]
+    pub fn effective_power_kw(&self) -> Option<f64> {
+        self.power_kw.or(match (self.current_a, self.voltage_v) {
+            (Some(current), Some(voltage)) => Some(current * voltage / 1000.0),
+            _ => None,
+        })
+    }
+}
+
+impl ChargeCurve {
+    /// Charging power at `soc_percent`, linearly interpolated between points.
+    ///
+    /// Outside the curve the first or last known power is held. Points without a
+    /// power (or current and voltage) are skipped; `None` means no point has one.
+    #[must_use]
+    pub fn power_at(&self, soc_percent: f64) -> Option<f64> {
+        let points: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .filter_map(|point| Some((point.soc_percent, point.effective_power_kw()?)))
+            .collect();
+
+        let (first, last) = (points.first()?, points.last()?);
+        if soc_percent <= first.0 {
+            return Some(first.1);
+        }
+        if soc_percent >= last.0 {
+            return Some(last.1);
+        }
+
+        points.windows(2).find_map(|pair| {
+            let ((soc_a, power_a), (soc_b, power_b)) = (pair[0], pair[1]);
+            (soc_percent >= soc_a && soc_percent <= soc_b).then(|| {
+                if soc_b == soc_a {
+                    power_b
+                } else {
+                    power_a + (power_b - power_a) * (soc_percent - soc_a) / (soc_b - soc_a)
+                }
+            })
+        })
+    }
+
+    /// Checks the curve against the vehicle's DC charging limits.
+    #[must_use]
+    pub fn findings_against(&self, dc: &ChargingDc) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for (index, point) in self.points.iter().enumerate() {
+            if let Some(power) = point.power_kw {
+                push_error(
+                    &mut findings,
+                    &format!("/points/{index}/power_kw"),
+                    validate_at_most(
+                        "dc_charge_curve.points.power_kw",
+                        power,
+                        "dc.max_power_kw",
+                        dc.max_power_kw,
+                    ),
+                );
+            }
+
+            if let (Some(current), Some(max_current)) = (point.current_a, dc.max_current_a) {
+                push_error(
+                    &mut findings,
+                    &format!("/points/{index}/current_a"),
+                    validate_at_most(
+                        "dc_charge_curve.points.current_a",
+                        current,
+                        "dc.max_current_a",
+                        max_current,
+                    ),
+                );
+            }
+        }
+
+        findings
+    }
+}
+
+impl Validate for ChargeCurve {
+    fn findings(&self) -> Vec<Finding> {
+        if self.points.is_empty() {
+            return vec![Finding::new(
+                "/points",
+                ValidationError::missing_field("dc_charge_curve.points"),
+            )];
+        }
+
+        let mut findings = Vec::new();
+
+        for (index, point) in self.points.iter().enumerate() {
+            push_nested(&mut findings, &format!("/points/{index}"), point.findings());
+        }
+
+        for (index, pair) in self.points.windows(2).enumerate() {
+            if pair[1].soc_percent <= pair[0].soc_percent {
+                findings.push(Finding::new(
+                    format!("/points/{}/soc_percent", index + 1),
+                    ValidationError::UnsortedChargeCurve { index: index + 1 },
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+impl Validate for ChargeCurvePoint {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(
+            &mut findings,
+            "/soc_percent",
+            validate_percent("dc_charge_curve.points.soc_percent", self.soc_percent),
+        );
+
+        if let Some(power) = self.power_kw {
+            push_error(
+                &mut findings,
+                "/power_kw",
+                validate_non_negative("dc_charge_curve.points.power_kw", power),
+            );
+        }
+
+        if let Some(current) = self.current_a {
+            push_error(
+                &mut findings,
+                "/current_a",
+                validate_non_negative("dc_charge_curve.points.current_a", current),
+            );
+        }
+
+        if let Some(voltage) = self.voltage_v {
+            push_error(
+                &mut findings,
+                "/voltage_v",
+                validate_positive("dc_charge_curve.points.voltage_v", voltage),
+            );
+        }
+
+        findings
+    }
+}
+
+impl Validate for ChargingTime {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for (index, entry) in self.ac.iter().flatten().enumerate() {
+            push_nested(&mut findings, &format!("/ac/{index}"), entry.findings());
+        }
+
+        for (index, entry) in self.dc.iter().flatten().enumerate() {
+            push_nested(&mut findings, &format!("/dc/{index}"), entry.findings());
+        }
+
+        findings
+    }
+}
+
+impl Validate for ChargingTimeEntry {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(
+            &mut findings,
+            "/power_kw",
+            validate_positive("charging_time.ac.power_kw", self.power_kw),
+        );
+        soc_interval_findings(
+            &mut findings,
+            "charging_time.ac",
+            self.from_soc_percent,
+            self.to_soc_percent,
+        );
+        push_error(
+            &mut findings,
+            "/time_min",
+            validate_positive("charging_time.ac.time_min", self.time_min),
+        );
+
+        findings
+    }
+}
+
+impl Validate for DcChargingTimeEntry {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(
+            &mut findings,
+            "/charger_power_kw",
+            validate_positive("charging_time.dc.charger_power_kw", self.charger_power_kw),
+        );
+        soc_interval_findings(
+            &mut findings,
+            "charging_time.dc",
+            self.from_soc_percent,
+            self.to_soc_percent,
+        );
+        push_error(
+            &mut findings,
+            "/time_min",
+            validate_positive("charging_time.dc.time_min", self.time_min),
+        );
+
+        findings
+    }
+}
+
+fn soc_interval_findings(findings: &mut Vec<Finding>, field: &str, from: f64, to: f64) {
+    push_error(
+        findings,
+        "/from_soc_percent",
+        validate_percent(&format!("{field}.from_soc_percent"), from),
+    );
+    push_error(
+        findings,
+        "/to_soc_percent",
+        validate_percent(&format!("{field}.to_soc_percent"), to),
+    );
+
+    if from >= to {
+        findings.push(Finding::new(
+            "/to_soc_percent",
+            ValidationError::InvalidSocInterval {
+                field: field.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+        ));
+    }
+}
+
+impl Validate for V2X {
+    fn findings(&self) -> Vec<Finding> {
+        let limits = [
+            ("v2l", self.v2l.as_ref().and_then(|v| v.max_power_kw)),
+            ("v2h", self.v2h.as_ref().and_then(|v| v.max_power_kw)),
+            ("v2g", self.v2g.as_ref().and_then(|v| v.max_power_kw)),
+        ];
+
+        let mut findings = Vec::new();
+
+        for (key, power) in limits {
+            if let Some(power) = power {
+                push_error(
+                    &mut findings,
+                    &format!("/{key}/max_power_kw"),
+                    validate_positive(&format!("v2x.{key}.max_power_kw"), power),
+                );
+            }
+        }
+
+        findings
+    }
+}
@@ -1,6 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum VehicleType {
     PassengerCar,
@@ -15,7 +17,8 @@ pub enum VehicleType {
     Other,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Drivetrain {
     Fwd,
@@ -25,7 +28,8 @@ pub enum Drivetrain {
     FourWd,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum MotorPosition {
     Front,
@@ -33,7 +37,8 @@ pub enum MotorPosition {
     Other,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ThermalManagement {
     Liquid,
@@ -43,7 +48,8 @@ pub enum ThermalManagement {
     None,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum PortKind {
     AcOnly,
@@ -51,7 +57,8 @@ pub enum PortKind {
     Combo,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ConnectorType {
     Type1,
@@ -66,7 +73,8 @@ pub enum ConnectorType {
     Other,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum PortLocation {
     Left,
@@ -76,7 +84,8 @@ pub enum PortLocation {
     Center,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum PortPosition {
     Front,
@@ -84,7 +93,8 @@ pub enum PortPosition {
     Mid,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ChargerVoltageClass {
     #[serde(rename = "400v")]
@@ -94,14 +104,16 @@ pub enum ChargerVoltageClass {
     Other,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ChargeCurveType {
     PowerBySoc,
     CurrentBySoc,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum RangeCycle {
     Wltp,
@@ -112,7 +124,8 @@ pub enum RangeCycle {
     Other,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum RealWorldProfile {
     Highway,
@@ -123,7 +136,8 @@ pub enum RealWorldProfile {
     Summer,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum AvailabilityStatus {
     Production,
@@ -133,7 +147,8 @@ pub enum AvailabilityStatus {
     Prototype,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum SourceType {
     Oem,
@@ -200,3 +215,115 @@ impl std::fmt::Display for RangeCycle {
         }
     }
 }
+
+wire_strings!(VehicleType, "vehicle_type", {
+    PassengerCar => "passenger_car",
+    Suv => "suv",
+    Pickup => "pickup",
+    Van => "van",
+    Bus => "bus",
+    Motorcycle => "motorcycle",
+    Scooter => "scooter",
+    Commercial => "commercial",
+    Truck => "truck",
+    Other => "other",
+});
+
+wire_strings!(Drivetrain, "drivetrain", {
+    Fwd => "fwd",
+    Rwd => "rwd",
+    Awd => "awd",
+    FourWd => "4wd",
+});
+
+wire_strings!(MotorPosition, "motor_position", {
+    Front => "front",
+    Rear => "rear",
+    Other => "other",
+});
+
+wire_strings!(ThermalManagement, "thermal_management", {
+    Liquid => "liquid",
+    Air => "air",
+    Passive => "passive",
+    Refrigerant => "refrigerant",
+    None => "none",
+});
+
+wire_strings!(PortKind, "port_kind", {
+    AcOnly => "ac_only",
+    DcOnly => "dc_only",
+    Combo => "combo",
+});
+
+wire_strings!(ConnectorType, "connector_type", {
+    Type1 => "type1",
+    Type2 => "type2",
+    Ccs1 => "ccs1",
+    Ccs2 => "ccs2",
+    Nacs => "nacs",
+    Chademo => "chademo",
+    GbTAc => "gb_t_ac",
+    GbTDc => "gb_t_dc",
+    TeslaType2 => "tesla_type2",
+    Other => "other",
+});
+
+wire_strings!(PortLocation, "port_location", {
+    Left => "left",
+    Right => "right",
+    Front => "front",
+    Rear => "rear",
+    Center => "center",
+});
+
+wire_strings!(PortPosition, "port_position", {
+    Front => "front",
+    Rear => "rear",
+    Mid => "mid",
+});
+
+wire_strings!(ChargerVoltageClass, "charger_voltage_class", {
+    V400 => "400v",
+    V800 => "800v",
+    Other => "other",
+});
+
+wire_strings!(ChargeCurveType, "charge_curve_type", {
+    PowerBySoc => "power_by_soc",
+    CurrentBySoc => "current_by_soc",
+});
+
+wire_strings!(RangeCycle, "range_cycle", {
+    Wltp => "wltp",
+    Epa => "epa",
+    Nedc => "nedc",
+    Cltc => "cltc",
+    Jc08 => "jc08",
+    Other => "other",
+});
+
+wire_strings!(RealWorldProfile, "real_world_profile", {
+    Highway => "highway",
+    City => "city",
+    Mixed => "mixed",
+    ColdWeather => "cold_weather",
+    Winter => "winter",
+    Summer => "summer",
+});
+
+wire_strings!(AvailabilityStatus, "availability_status", {
+    Production => "production",
+    Discontinued => "discontinued",
+    Concept => "concept",
+    Announced => "announced",
+    Prototype => "prototype",
+});
+
+wire_strings!(SourceType, "source_type", {
+    Oem => "oem",
+    Regulatory => "regulatory",
+    Press => "press",
+    Community => "community",
+    TestingOrg => "testing_org",
+});
@@ -1,8 +1,15 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::enums::AvailabilityStatus;
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+use crate::error::ValidationError;
+use crate::validation::{
+    Finding, Validate, push_error, push_nested, validate_country_code, validate_currency_code,
+    validate_positive, validate_slug, validate_url, validate_year,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Variant {
     pub slug: String,
     pub name: String,
@@ -14,7 +21,8 @@ pub struct Variant {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[allow(dead_code)]
 pub struct VehicleAvailability {
     pub status: AvailabilityStatus,
@@ -29,13 +37,15 @@ pub struct VehicleAvailability {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Pricing {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub msrp: Option<Vec<Msrp>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Msrp {
     pub currency: String,
     pub amount: f64,
@@ -50,7 +60,8 @@ pub struct Msrp {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Software {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub os: Option<String>,
@@ -62,7 +73,8 @@ pub struct Software {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Links {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manufacturer_url: Option<String>,
@@ -77,7 +89,8 @@ pub struct Links {
     pub configurator_url: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Images {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exterior_url: Option<String>,
@@ -89,7 +102,8 @@ pub struct Images {
     pub charging_curve_plot_url: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
@@ -107,7 +121,8 @@ pub struct Metadata {
     pub internal_notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct WheelsTires {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub standard_wheel_size_in: Option<f64>,
@@ -125,7 +140,8 @@ pub struct WheelsTires {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct TirePressure {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub front_kpa: Option<f64>,
@@ -133,3 +149,92 @@ pub struct TirePressure {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rear_kpa: Option<f64>,
 }
+
+impl Validate for Variant {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(&mut findings, "/slug", validate_slug(&self.slug));
+
+        if self.name.is_empty() {
+            findings.push(Finding::new(
+                "/name",
+                ValidationError::empty_value("variant.name"),
+            ));
+        }
+
+        findings
+    }
+}
+
+impl Validate for Pricing {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for (index, msrp) in self.msrp.iter().flatten().enumerate() {
+            push_nested(&mut findings, &format!("/msrp/{index}"), msrp.findings());
+        }
+
+        findings
+    }
+}
+
+impl Validate for Msrp {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(
+            &mut findings,
+            "/currency",
+            validate_currency_code(&self.currency),
+        );
+        push_error(
+            &mut findings,
+            "/amount",
+            validate_positive("pricing.msrp.amount", self.amount),
+        );
+
+        if let Some(ref country) = self.country {
+            push_error(&mut findings, "/country", validate_country_code(country));
+        }
+
+        if let Some(year) = self.year {
+            push_error(&mut findings, "/year", validate_year(year));
+        }
+
+        findings
+    }
+}
+
+impl Validate for Links {
+    fn findings(&self) -> Vec<Finding> {
+        url_findings(&[
+            ("manufacturer_url", &self.manufacturer_url),
+            ("press_kit_url", &self.press_kit_url),
+            ("spec_sheet_url", &self.spec_sheet_url),
+            ("configurator_url", &self.configurator_url),
+        ])
+    }
+}
+
+impl Validate for Images {
+    fn findings(&self) -> Vec<Finding> {
+        url_findings(&[
+            ("exterior_url", &self.exterior_url),
+            ("interior_url", &self.interior_url),
+            ("charging_curve_plot_url", &self.charging_curve_plot_url),
+        ])
+    }
+}
+
+fn url_findings(urls: &[(&str, &Option<String>)]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (key, url) in urls {
+        if let Some(url) = url {
+            push_error(&mut findings, &format!("/{key}"), validate_url(url));
+        }
+    }
+
+    findings
+}
@@ -13,7 +13,8 @@ pub use battery::{Battery, Preconditioning, UsableSocWindow, Warranty};
 pub use body::{Body, Capacity, Dimensions, Performance, Weights};
 pub use charging::{
     ChargeCurve, ChargeCurvePoint, ChargePort, Charging, ChargingAc, ChargingDc, ChargingProtocols,
-    ChargingTime, Conditions,
+    ChargingTime, ChargingTimeEntry, Conditions, DcChargingTimeEntry, PowerLimitByVoltage,
+    VoltageRange,
 };
 pub use metadata::{Images, Links, Metadata, Variant};
 pub use powertrain::{Motor, Powertrain, Transmission};
@@ -1,8 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::enums::{Drivetrain, MotorPosition};
+use crate::validation::{Finding, Validate, push_error, push_nested, validate_positive};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Powertrain {
     pub drivetrain: Drivetrain,
 
@@ -35,7 +38,36 @@ impl Powertrain {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl Validate for Powertrain {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(power) = self.system_power_kw {
+            push_error(
+                &mut findings,
+                "/system_power_kw",
+                validate_positive("powertrain.system_power_kw", power),
+            );
+        }
+
+        if let Some(torque) = self.system_torque_nm {
+            push_error(
+                &mut findings,
+                "/system_torque_nm",
+                validate_positive("powertrain.system_torque_nm", torque),
+            );
+        }
+
+        for (index, motor) in self.motors.iter().flatten().enumerate() {
+            push_nested(&mut findings, &format!("/motors/{index}"), motor.findings());
+        }
+
+        findings
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Motor {
     pub position: MotorPosition,
 
@@ -53,7 +85,32 @@ pub struct Motor {
     pub cooling: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl Validate for Motor {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(power) = self.power_kw {
+            push_error(
+                &mut findings,
+                "/power_kw",
+                validate_positive("powertrain.motors.power_kw", power),
+            );
+        }
+
+        if let Some(torque) = self.torque_nm {
+            push_error(
+                &mut findings,
+                "/torque_nm",
+                validate_positive("powertrain.motors.torque_nm", torque),
+            );
+        }
+
+        findings
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Transmission {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gears: Option<u8>,
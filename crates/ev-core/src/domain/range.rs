@@ -1,8 +1,12 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::enums::{RangeCycle, RealWorldProfile};
+use crate::error::ValidationError;
+use crate::validation::{Finding, Validate, push_error, validate_positive};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Range {
     pub rated: Vec<RangeRated>,
 
@@ -33,7 +37,42 @@ impl Range {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl Validate for Range {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for (index, rated) in self.rated.iter().enumerate() {
+            push_error(
+                &mut findings,
+                &format!("/rated/{index}/range_km"),
+                validate_positive("range.rated.range_km", rated.range_km),
+            );
+
+            if self.rated[..index].iter().any(|r| r.cycle == rated.cycle) {
+                findings.push(Finding::new(
+                    format!("/rated/{index}/cycle"),
+                    ValidationError::DuplicateEntry {
+                        field: "range.rated.cycle".to_string(),
+                        value: rated.cycle.as_str().to_string(),
+                    },
+                ));
+            }
+        }
+
+        for (index, real_world) in self.real_world.iter().flatten().enumerate() {
+            push_error(
+                &mut findings,
+                &format!("/real_world/{index}/range_km"),
+                validate_positive("range.real_world.range_km", real_world.range_km),
+            );
+        }
+
+        findings
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct RangeRated {
     pub cycle: RangeCycle,
     pub range_km: f64,
@@ -42,7 +81,8 @@ pub struct RangeRated {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct RangeRealWorld {
     pub profile: RealWorldProfile,
     pub range_km: f64,
@@ -54,7 +94,8 @@ pub struct RangeRealWorld {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct RealWorldConditions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub weather: Option<String>,
@@ -63,7 +104,8 @@ pub struct RealWorldConditions {
     pub speed_kmh: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Default)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Efficiency {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub energy_consumption_wh_per_km: Option<f64>,
@@ -81,3 +123,27 @@ impl Efficiency {
         self.energy_consumption_wh_per_km.map(|wh| 1000.0 / wh)
     }
 }
+
+impl Validate for Efficiency {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(consumption) = self.energy_consumption_wh_per_km {
+            push_error(
+                &mut findings,
+                "/energy_consumption_wh_per_km",
+                validate_positive("efficiency.energy_consumption_wh_per_km", consumption),
+            );
+        }
+
+        if let Some(mpge) = self.mpge {
+            push_error(
+                &mut findings,
+                "/mpge",
+                validate_positive("efficiency.mpge", mpge),
+            );
+        }
+
+        findings
+    }
+}
@@ -1,8 +1,12 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::enums::SourceType;
+use crate::error::ValidationError;
+use crate::validation::{Finding, Validate, push_error, validate_date, validate_url};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct Source {
     #[serde(rename = "type")]
     pub source_type: SourceType,
@@ -27,3 +31,25 @@ impl Source {
         matches!(self.source_type, SourceType::Oem | SourceType::Regulatory)
     }
 }
+
+impl Validate for Source {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if self.title.is_empty() {
+            findings.push(Finding::new(
+                "/title",
+                ValidationError::empty_value("sources.title"),
+            ));
+        }
+
+        push_error(&mut findings, "/url", validate_url(&self.url));
+        push_error(
+            &mut findings,
+            "/accessed_at",
+            validate_date(&self.accessed_at),
+        );
+
+        findings
+    }
+}
@@ -1,9 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::error::ValidationError;
-use crate::validation::{Validate, validate_slug, validate_year};
+use crate::validation::{Finding, Validate, push_error, validate_slug, validate_year};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct SlugName {
     pub slug: String,
     pub name: String,
@@ -35,12 +37,16 @@ impl SlugName {
 }
 
 impl Validate for SlugName {
-    fn validate(&self) -> Result<(), ValidationError> {
-        validate_slug(&self.slug)?;
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_error(&mut findings, "/slug", validate_slug(&self.slug));
+
         if self.name.is_empty() {
-            return Err(ValidationError::empty_value("name"));
+            findings.push(Finding::new("/name", ValidationError::empty_value("name")));
         }
-        Ok(())
+
+        findings
     }
 }
 
@@ -50,7 +56,10 @@ impl std::fmt::Display for SlugName {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(transparent)]
 pub struct Year(u16);
 
@@ -67,8 +76,10 @@ impl Year {
 }
 
 impl Validate for Year {
-    fn validate(&self) -> Result<(), ValidationError> {
-        validate_year(self.0)
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        push_error(&mut findings, "", validate_year(self.0));
+        findings
     }
 }
 
@@ -92,7 +103,12 @@ impl TryFrom<u16> for Year {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Canonical vehicle identifier, written `make:model:year:trim[:variant]`.
+///
+/// This is the `unique_code` the ETL assigns to every vehicle; [`FromStr`](std::str::FromStr)
+/// parses it back, so `id.to_string().parse::<VehicleId>() == Ok(id)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct VehicleId {
     pub make_slug: String,
     pub model_slug: String,
@@ -131,6 +147,59 @@ impl VehicleId {
             variant_slug,
         })
     }
+
+    /// Reads the id from the `make`, `model`, `year`, `trim` and `variant` slugs of a
+    /// vehicle JSON document, without deserializing (or validating) the rest of it.
+    ///
+    /// Returns `None` when one of the required slugs or the year is missing.
+    #[must_use]
+    pub fn from_document(document: &serde_json::Value) -> Option<Self> {
+        let slug = |field: &str| {
+            document
+                .get(field)?
+                .get("slug")?
+                .as_str()
+                .map(str::to_string)
+        };
+
+        Some(Self {
+            make_slug: slug("make")?,
+            model_slug: slug("model")?,
+            year: u16::try_from(document.get("year")?.as_u64()?).ok()?,
+            trim_slug: slug("trim")?,
+            variant_slug: slug("variant"),
+        })
+    }
+}
+
+impl std::str::FromStr for VehicleId {
+    type Err = ValidationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| ValidationError::InvalidVehicleId {
+            value: value.to_string(),
+            reason,
+        };
+
+        let parts: Vec<&str> = value.split(':').collect();
+        let (make, model, year, trim, variant) = match parts[..] {
+            [make, model, year, trim] => (make, model, year, trim, None),
+            [make, model, year, trim, variant] => (make, model, year, trim, Some(variant)),
+            _ => {
+                return Err(invalid(format!(
+                    "expected make:model:year:trim[:variant], got {} segment(s)",
+                    parts.len()
+                )));
+            }
+        };
+
+        let year = year
+            .parse::<u16>()
+            .map_err(|_| invalid(format!("year '{}' is not a number", year)))?;
+
+        Self::new(make, model, year, trim, variant.map(str::to_string))
+            .map_err(|e| invalid(e.to_string()))
+    }
 }
 
 impl std::fmt::Display for VehicleId {
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::battery::Battery;
@@ -8,11 +9,15 @@ use super::metadata::{Images, Links, Metadata, Pricing, Software, Variant, Wheel
 use super::powertrain::Powertrain;
 use super::range::{Efficiency, Range};
 use super::sources::Source;
-use super::types::{SlugName, VehicleId, Year};
+use super::types::{SlugName, VehicleId};
 use crate::error::ValidationError;
-use crate::validation::Validate;
+use crate::validation::{
+    Finding, Validate, push_error, push_nested, validate_bounds, validate_country_code,
+    validate_year,
+};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(deny_unknown_fields)]
 pub struct Vehicle {
     #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
@@ -82,7 +87,8 @@ pub struct Vehicle {
     pub metadata: Option<Metadata>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct VehicleAvailability {
     pub status: super::enums::AvailabilityStatus,
 
@@ -96,6 +102,30 @@ pub struct VehicleAvailability {
     pub notes: Option<String>,
 }
 
+impl Validate for VehicleAvailability {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(start) = self.start_year {
+            push_error(&mut findings, "/start_year", validate_year(start));
+        }
+
+        if let Some(end) = self.end_year {
+            push_error(&mut findings, "/end_year", validate_year(end));
+        }
+
+        if let (Some(start), Some(end)) = (self.start_year, self.end_year) {
+            push_error(
+                &mut findings,
+                "/end_year",
+                validate_bounds("availability.year", start, end),
+            );
+        }
+
+        findings
+    }
+}
+
 impl Vehicle {
     #[must_use]
     pub fn id(&self) -> VehicleId {
@@ -145,50 +175,119 @@ impl Vehicle {
     pub fn max_ac_power_kw(&self) -> Option<f64> {
         self.charging.ac.as_ref().map(|ac| ac.max_power_kw)
     }
+
+    /// Vehicle-to-load support, `None` when unknown.
+    #[must_use]
+    pub fn v2l_supported(&self) -> Option<bool> {
+        self.v2x.as_ref()?.v2l.as_ref().map(|v2l| v2l.supported)
+    }
+
+    /// Vehicle-to-home support, `None` when unknown.
+    #[must_use]
+    pub fn v2h_supported(&self) -> Option<bool> {
+        self.v2x.as_ref()?.v2h.as_ref().map(|v2h| v2h.supported)
+    }
+
+    /// Vehicle-to-grid support, `None` when unknown.
+    #[must_use]
+    pub fn v2g_supported(&self) -> Option<bool> {
+        self.v2x.as_ref()?.v2g.as_ref().map(|v2g| v2g.supported)
+    }
 }
 
 impl Validate for Vehicle {
-    fn validate(&self) -> Result<(), ValidationError> {
-        let mut errors = Vec::new();
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        push_nested(&mut findings, "/make", self.make.findings());
+        push_nested(&mut findings, "/model", self.model.findings());
+        push_error(&mut findings, "/year", validate_year(self.year));
+        push_nested(&mut findings, "/trim", self.trim.findings());
+        push_nested(&mut findings, "/powertrain", self.powertrain.findings());
+        push_nested(&mut findings, "/battery", self.battery.findings());
 
-        if let Err(e) = self.make.validate() {
-            errors.push(e);
+        if self.charge_ports.is_empty() {
+            findings.push(Finding::new(
+                "/charge_ports",
+                ValidationError::MissingChargePort,
+            ));
         }
 
-        if let Err(e) = self.model.validate() {
-            errors.push(e);
+        push_nested(&mut findings, "/charging", self.charging.findings());
+
+        if self.range.rated.is_empty() {
+            findings.push(Finding::new(
+                "/range/rated",
+                ValidationError::MissingRatedRange,
+            ));
         }
 
-        if let Err(e) = Year::new(self.year) {
-            errors.push(e);
+        push_nested(&mut findings, "/range", self.range.findings());
+
+        if self.sources.is_empty() {
+            findings.push(Finding::new("/sources", ValidationError::MissingSource));
         }
 
-        if let Err(e) = self.trim.validate() {
-            errors.push(e);
+        for (index, source) in self.sources.iter().enumerate() {
+            push_nested(
+                &mut findings,
+                &format!("/sources/{index}"),
+                source.findings(),
+            );
         }
 
-        if !self.battery.has_capacity() {
-            errors.push(ValidationError::MissingBatteryCapacity);
+        if let Some(ref variant) = self.variant {
+            push_nested(&mut findings, "/variant", variant.findings());
         }
 
-        if self.charge_ports.is_empty() {
-            errors.push(ValidationError::MissingChargePort);
+        for (index, market) in self.markets.iter().flatten().enumerate() {
+            push_error(
+                &mut findings,
+                &format!("/markets/{index}"),
+                validate_country_code(market),
+            );
         }
 
-        if self.range.rated.is_empty() {
-            errors.push(ValidationError::MissingRatedRange);
+        if let Some(ref availability) = self.availability {
+            push_nested(&mut findings, "/availability", availability.findings());
         }
 
-        if self.sources.is_empty() {
-            errors.push(ValidationError::MissingSource);
+        if let Some(ref body) = self.body {
+            push_nested(&mut findings, "/body", body.findings());
         }
 
-        if errors.is_empty() {
-            Ok(())
-        } else if errors.len() == 1 {
-            Err(errors.into_iter().next().expect("checked non-empty"))
-        } else {
-            Err(ValidationError::Multiple(errors))
+        if let Some(ref dimensions) = self.dimensions {
+            push_nested(&mut findings, "/dimensions", dimensions.findings());
         }
+
+        if let Some(ref weights) = self.weights {
+            push_nested(&mut findings, "/weights", weights.findings());
+        }
+
+        if let Some(ref v2x) = self.v2x {
+            push_nested(&mut findings, "/v2x", v2x.findings());
+        }
+
+        if let Some(ref efficiency) = self.efficiency {
+            push_nested(&mut findings, "/efficiency", efficiency.findings());
+        }
+
+        if let Some(ref performance) = self.performance {
+            push_nested(&mut findings, "/performance", performance.findings());
+        }
+
+        if let Some(ref pricing) = self.pricing {
+            push_nested(&mut findings, "/pricing", pricing.findings());
+        }
+
+        if let Some(ref links) = self.links {
+            push_nested(&mut findings, "/links", links.findings());
+        }
+
+        if let Some(ref images) = self.images {
+            push_nested(&mut findings, "/images", images.findings());
+        }
+
+        findings
     }
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -55,11 +56,109 @@ pub enum ValidationError {
     #[error("Invalid date-time format: {value}")]
     InvalidDateTime { value: String },
 
-    #[error("Multiple validation errors: {0:?}")]
+    #[error("Value for {field} must be greater than zero, got {value}")]
+    NonPositive { field: String, value: String },
+
+    #[error("Value for {field} must not be negative, got {value}")]
+    NegativeValue { field: String, value: String },
+
+    #[error("Value for {field} ({value}) exceeds {limit_field} ({limit})")]
+    ExceedsLimit {
+        field: String,
+        value: String,
+        limit_field: String,
+        limit: String,
+    },
+
+    #[error("Invalid bounds for {field}: lower bound {lower} is greater than upper bound {upper}")]
+    InvalidBounds {
+        field: String,
+        lower: String,
+        upper: String,
+    },
+
+    #[error("Invalid SOC interval for {field}: from {from}% must be lower than to {to}%")]
+    InvalidSocInterval {
+        field: String,
+        from: String,
+        to: String,
+    },
+
+    #[error("Charge curve points must be in ascending soc_percent order (point {index})")]
+    UnsortedChargeCurve { index: usize },
+
+    #[error("Duplicate entry for {field}: {value}")]
+    DuplicateEntry { field: String, value: String },
+
+    #[error("Invalid vehicle id '{value}': {reason}")]
+    InvalidVehicleId { value: String, reason: String },
+
+    #[error("Invalid {kind} '{value}', expected one of: {allowed}")]
+    InvalidEnumValue {
+        kind: String,
+        value: String,
+        allowed: String,
+    },
+
+    #[error("{} validation errors: {}", .0.len(), join_messages(.0))]
     Multiple(Vec<ValidationError>),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+fn join_messages(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl ValidationError {
+    /// Stable identifier of the violated rule, suitable for reports and tooling.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingField { .. } => "missing_field",
+            Self::InvalidYear { .. } => "invalid_year",
+            Self::InvalidSlug { .. } => "invalid_slug",
+            Self::EmptyValue { .. } => "empty_value",
+            Self::MissingBatteryCapacity => "missing_battery_capacity",
+            Self::MissingChargePort => "missing_charge_port",
+            Self::MissingRatedRange => "missing_rated_range",
+            Self::MissingSource => "missing_source",
+            Self::InvalidUrl { .. } => "invalid_url",
+            Self::InvalidCountryCode { .. } => "invalid_country_code",
+            Self::InvalidCurrencyCode { .. } => "invalid_currency_code",
+            Self::OutOfRange { .. } => "out_of_range",
+            Self::InvalidDateTime { .. } => "invalid_date_time",
+            Self::NonPositive { .. } => "non_positive",
+            Self::NegativeValue { .. } => "negative_value",
+            Self::ExceedsLimit { .. } => "exceeds_limit",
+            Self::InvalidBounds { .. } => "invalid_bounds",
+            Self::InvalidSocInterval { .. } => "invalid_soc_interval",
+            Self::UnsortedChargeCurve { .. } => "unsorted_charge_curve",
+            Self::DuplicateEntry { .. } => "duplicate_entry",
+            Self::InvalidVehicleId { .. } => "invalid_vehicle_id",
+            Self::InvalidEnumValue { .. } => "invalid_enum_value",
+            Self::Multiple(_) => "multiple",
+        }
+    }
+
     #[must_use]
     pub fn missing_field(field: impl Into<String>) -> Self {
         Self::MissingField {
@@ -4,16 +4,25 @@
 //! implementing a pure domain library with no I/O dependencies.
 #![forbid(unsafe_code)]
 
+#[macro_use]
+mod macros;
+
+pub mod charge_time;
+pub mod comparison;
+pub mod compatibility;
+pub mod diff;
 pub mod domain;
 pub mod error;
+pub mod schema;
 pub mod validation;
 
 pub use domain::{
     Battery, Body, Capacity, ChargeCurve, ChargeCurvePoint, ChargePort, Charging, ChargingAc,
-    ChargingDc, ChargingProtocols, ChargingTime, Conditions, Dimensions, Efficiency, Images, Links,
-    Metadata, Motor, Msrp, Performance, Powertrain, Preconditioning, Pricing, Range, RangeRated,
-    RangeRealWorld, SlugName, Source, Transmission, UsableSocWindow, V2G, V2H, V2L, V2LOutlet, V2X,
-    Variant, Vehicle, VehicleAvailability, VehicleId, Warranty, Weights, WheelsTires, Year,
+    ChargingDc, ChargingProtocols, ChargingTime, ChargingTimeEntry, Conditions,
+    DcChargingTimeEntry, Dimensions, Efficiency, Images, Links, Metadata, Motor, Msrp, Performance,
+    PowerLimitByVoltage, Powertrain, Preconditioning, Pricing, Range, RangeRated, RangeRealWorld,
+    SlugName, Source, Transmission, UsableSocWindow, V2G, V2H, V2L, V2LOutlet, V2X, Variant,
+    Vehicle, VehicleAvailability, VehicleId, VoltageRange, Warranty, Weights, WheelsTires, Year,
 };
 
 pub use domain::enums::{
@@ -22,5 +31,18 @@ pub use domain::enums::{
     ThermalManagement, VehicleType,
 };
 
-pub use error::{CoreError, ValidationError};
-pub use validation::Validate;
+pub use charge_time::{
+    AcChargeEstimate, AcChargePower, AcSupply, ChargeEstimate, EstimateBasis, ac_charge_power,
+    estimate_ac_charge, estimate_dc_charge,
+};
+pub use comparison::{
+    Better, Comparison, FieldComparison, METRICS, Metric, MetricComparison, MetricValue, compare,
+    lowest_msrp,
+};
+pub use compatibility::{
+    ADAPTERS, Adapter, ChargerSpec, Compatibility, CurrentType, PowerLimit,
+    candidate_port_connectors, check_compatibility,
+};
+pub use diff::{ChangeKind, FieldChange, VehicleDiff, diff_documents, diff_vehicles};
+pub use error::{CoreError, Severity, ValidationError};
+pub use validation::{Finding, Validate};
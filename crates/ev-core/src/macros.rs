@@ -0,0 +1,40 @@
+/// Gives a fieldless enum its canonical wire strings: `ALL`, `as_str` and `FromStr`.
+///
+/// The strings must match the enum's serde representation; unknown values fail with
+/// `ValidationError::InvalidEnumValue` listing the allowed ones.
+macro_rules! wire_strings {
+    ($name:ident, $kind:literal, { $($variant:ident => $wire:literal),+ $(,)? }) => {
+        impl $name {
+            /// Every variant, in declaration order.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Canonical wire string, identical to the serde representation.
+            #[must_use]
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $wire),+
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = $crate::error::ValidationError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|variant| variant.as_str() == value)
+                    .ok_or_else(|| $crate::error::ValidationError::InvalidEnumValue {
+                        kind: $kind.to_string(),
+                        value: value.to_string(),
+                        allowed: Self::ALL
+                            .iter()
+                            .map(Self::as_str)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    })
+            }
+        }
+    };
+}
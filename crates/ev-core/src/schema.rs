@@ -0,0 +1,11 @@
+//! JSON Schema for the canonical vehicle document, generated from the domain types.
+
+use serde_json::Value;
+
+use crate::domain::Vehicle;
+
+/// Returns the JSON Schema (draft 2020-12) describing a merged [`Vehicle`] document.
+#[must_use]
+pub fn vehicle_schema() -> Value {
+    schemars::schema_for!(Vehicle).to_value()
+}
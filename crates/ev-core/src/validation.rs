@@ -1,13 +1,86 @@
-use crate::error::ValidationError;
+use std::fmt::Display;
+
+use chrono::{DateTime, NaiveDate};
+
+use crate::error::{Severity, ValidationError};
 
 pub trait Validate {
-    fn validate(&self) -> Result<(), ValidationError>;
+    /// Returns every rule violation, located by JSON pointers relative to `self`.
+    fn findings(&self) -> Vec<Finding>;
+
+    /// Fails with the error-severity findings; warnings are ignored.
+    fn validate(&self) -> Result<(), ValidationError> {
+        into_result(
+            self.findings()
+                .into_iter()
+                .filter(Finding::is_error)
+                .map(|finding| finding.error)
+                .collect(),
+        )
+    }
 
     fn validate_all(&self) -> Result<(), ValidationError> {
         self.validate()
     }
 }
 
+/// A single validation result located within a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// RFC 6901 JSON pointer to the offending value, e.g. `/charging/dc/max_power_kw`.
+    pub pointer: String,
+    pub severity: Severity,
+    pub error: ValidationError,
+}
+
+impl Finding {
+    /// An error-severity finding. The built-in rules only report errors; set
+    /// `severity` to [`Severity::Warning`] for an advisory check of your own.
+    #[must_use]
+    pub fn new(pointer: impl Into<String>, error: ValidationError) -> Self {
+        Self {
+            pointer: pointer.into(),
+            severity: Severity::Error,
+            error,
+        }
+    }
+
+    /// Stable rule code of the underlying error.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    #[must_use]
+    pub fn prefixed(mut self, prefix: &str) -> Self {
+        self.pointer = format!("{prefix}{}", self.pointer);
+        self
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity,
+            self.code(),
+            pointer,
+            self.error
+        )
+    }
+}
+
 pub fn validate_slug(value: &str) -> Result<(), ValidationError> {
     if value.is_empty() {
         return Err(ValidationError::empty_value("slug"));
@@ -63,6 +136,108 @@ pub fn validate_url(url: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+pub fn validate_date(value: &str) -> Result<(), ValidationError> {
+    let is_valid = NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        || DateTime::parse_from_rfc3339(value).is_ok();
+
+    if !is_valid {
+        return Err(ValidationError::InvalidDateTime {
+            value: value.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_positive(field: &str, value: f64) -> Result<(), ValidationError> {
+    if value.is_nan() || value <= 0.0 {
+        return Err(ValidationError::NonPositive {
+            field: field.to_string(),
+            value: value.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_non_negative(field: &str, value: f64) -> Result<(), ValidationError> {
+    if value.is_nan() || value < 0.0 {
+        return Err(ValidationError::NegativeValue {
+            field: field.to_string(),
+            value: value.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_percent(field: &str, value: f64) -> Result<(), ValidationError> {
+    if !(0.0..=100.0).contains(&value) {
+        return Err(ValidationError::OutOfRange {
+            field: field.to_string(),
+            value: value.to_string(),
+            min: "0".to_string(),
+            max: "100".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_bounds<T: PartialOrd + Display>(
+    field: &str,
+    lower: T,
+    upper: T,
+) -> Result<(), ValidationError> {
+    if lower > upper {
+        return Err(ValidationError::InvalidBounds {
+            field: field.to_string(),
+            lower: lower.to_string(),
+            upper: upper.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_at_most(
+    field: &str,
+    value: f64,
+    limit_field: &str,
+    limit: f64,
+) -> Result<(), ValidationError> {
+    if value > limit {
+        return Err(ValidationError::ExceedsLimit {
+            field: field.to_string(),
+            value: value.to_string(),
+            limit_field: limit_field.to_string(),
+            limit: limit.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Records the error of `result` at `pointer`, flattening nested `Multiple` errors.
+pub fn push_error(findings: &mut Vec<Finding>, pointer: &str, result: Result<(), ValidationError>) {
+    match result {
+        Ok(()) => {}
+        Err(ValidationError::Multiple(nested)) => {
+            findings.extend(nested.into_iter().map(|error| Finding::new(pointer, error)))
+        }
+        Err(error) => findings.push(Finding::new(pointer, error)),
+    }
+}
+
+/// Records the findings of a nested value, prefixing their pointers with `prefix`.
+pub fn push_nested(findings: &mut Vec<Finding>, prefix: &str, nested: Vec<Finding>) {
+    findings.extend(nested.into_iter().map(|finding| finding.prefixed(prefix)));
+}
+
+/// Turns accumulated errors into a result: a single error is returned as-is,
+/// several are wrapped in `ValidationError::Multiple`.
+pub fn into_result(mut errors: Vec<ValidationError>) -> Result<(), ValidationError> {
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.pop().expect("checked non-empty")),
+        _ => Err(ValidationError::Multiple(errors)),
+    }
+}
+
 pub fn collect_errors<T, I>(
     items: I,
     validator: fn(&T) -> Result<(), ValidationError>,
@@ -75,11 +250,5 @@ where
         .filter_map(|item| validator(&item).err())
         .collect();
 
-    if errors.is_empty() {
-        Ok(())
-    } else if errors.len() == 1 {
-        Err(errors.into_iter().next().expect("checked non-empty"))
-    } else {
-        Err(ValidationError::Multiple(errors))
-    }
+    into_result(errors)
 }
@@ -0,0 +1,154 @@
+//! Content-hash cache for incremental builds.
+//!
+//! Each make/model group is keyed on the SHA-256 of its layer files, the schema
+//! and the ETL version. A group whose key matches the cached one reuses its
+//! vehicles and validation findings instead of being merged and validated again.
+//! Output artifacts are keyed on the vehicles they are written from and are only
+//! rewritten when those change or the file is missing.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ev_core::Vehicle;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::ingest::VehicleFile;
+use crate::validate::ValidationReport;
+
+/// Name of the cache directory inside the output directory.
+pub const DEFAULT_DIR_NAME: &str = ".ev-etl-cache";
+
+const OUTPUTS_FILE: &str = "outputs.json";
+
+/// Valid vehicles and validation findings of one make/model group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelBuild {
+    /// [`model_key`] of the layer files the vehicles were built from.
+    pub key: String,
+    pub vehicles: Vec<Vehicle>,
+    pub report: ValidationReport,
+}
+
+/// Cached model builds and output keys in one directory.
+///
+/// A cache opened without `reuse` never reports a hit but still records what
+/// was built, so the next run can rely on it.
+#[derive(Debug)]
+pub struct BuildCache {
+    dir: PathBuf,
+    reuse: bool,
+    /// Key of each output artifact, by path.
+    outputs: BTreeMap<String, String>,
+}
+
+impl BuildCache {
+    pub fn open(dir: &Path, reuse: bool) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory {:?}", dir))?;
+
+        // A missing or unreadable manifest only means every output is rewritten.
+        let outputs = std::fs::read_to_string(dir.join(OUTPUTS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            reuse,
+            outputs,
+        })
+    }
+
+    /// Returns the cached build of a model if it was made from layers with `key`.
+    #[must_use]
+    pub fn load_model(&self, make_slug: &str, model_slug: &str, key: &str) -> Option<ModelBuild> {
+        if !self.reuse {
+            return None;
+        }
+
+        let content = std::fs::read_to_string(self.model_path(make_slug, model_slug)).ok()?;
+        serde_json::from_str::<ModelBuild>(&content)
+            .ok()
+            .filter(|build| build.key == key)
+    }
+
+    pub fn store_model(&self, make_slug: &str, model_slug: &str, build: &ModelBuild) -> Result<()> {
+        let path = self.model_path(make_slug, model_slug);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create cache directory {:?}", parent))?;
+        }
+        std::fs::write(&path, serde_json::to_vec(build)?)
+            .with_context(|| format!("Failed to write cache entry {:?}", path))
+    }
+
+    /// Returns `true` when `path` exists and was last written from output `key`.
+    #[must_use]
+    pub fn is_output_current(&self, path: &Path, key: &str) -> bool {
+        self.reuse
+            && path.exists()
+            && self
+                .outputs
+                .get(&path.display().to_string())
+                .map(String::as_str)
+                == Some(key)
+    }
+
+    /// Records that `path` was written from output `key`; see [`BuildCache::save`].
+    pub fn record_output(&mut self, path: &Path, key: String) {
+        self.outputs.insert(path.display().to_string(), key);
+    }
+
+    /// Writes the recorded output keys.
+    pub fn save(&self) -> Result<()> {
+        let path = self.dir.join(OUTPUTS_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(&self.outputs)?)
+            .with_context(|| format!("Failed to write cache manifest {:?}", path))
+    }
+
+    fn model_path(&self, make_slug: &str, model_slug: &str) -> PathBuf {
+        self.dir
+            .join("models")
+            .join(make_slug)
+            .join(format!("{}.json", model_slug))
+    }
+}
+
+/// SHA-256 of a schema, part of every [`model_key`].
+#[must_use]
+pub fn schema_hash(schema: &Value) -> String {
+    hex::encode(Sha256::digest(schema.to_string().as_bytes()))
+}
+
+/// Key of one model's layer files: their names and SHA-256, the schema and the ETL version.
+#[must_use]
+pub fn model_key(files: &[&VehicleFile], schema_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update([0]);
+    hasher.update(schema_hash);
+
+    for file in files {
+        let year = file.year.map(|year| year.to_string()).unwrap_or_default();
+        for part in [year.as_str(), &file.file_slug, &file.sha256] {
+            hasher.update([0]);
+            hasher.update(part);
+        }
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+/// Key of the `format` artifact written from `vehicles`.
+pub fn output_key(format: &str, vehicles: &[Vehicle]) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update([0]);
+    hasher.update(format);
+    hasher.update([0]);
+    hasher.update(serde_json::to_vec(vehicles)?);
+    Ok(hex::encode(hasher.finalize()))
+}
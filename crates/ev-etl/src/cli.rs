@@ -1,15 +1,104 @@
-use std::path::PathBuf;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
 #[command(name = "ev-etl")]
 #[command(author, version, about = "OpenEV Data ETL Pipeline")]
-#[command(long_about = "Transform layered JSON vehicle data into multiple output formats")]
+#[command(
+    long_about = "Transform layered JSON vehicle data into multiple output formats. \
+    Without a subcommand, runs `build`"
+)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Cli {
-    #[arg(short, long, value_name = "DIR")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options of `build` when it runs as the default subcommand.
+    #[command(flatten)]
+    pub build: BuildArgs,
+
+    #[arg(short, long, global = true)]
+    #[arg(help = "Enable verbose output")]
+    pub verbose: bool,
+}
+
+impl Cli {
+    /// The subcommand to run, `build` when none is given.
+    #[must_use]
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Build(self.build))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Merge and validate the dataset and write the output artifacts (default)")]
+    Build(BuildArgs),
+
+    #[command(about = "Merge and validate the dataset without writing anything")]
+    Validate(DatasetArgs),
+
+    #[command(about = "Check layer files for skipped files, formatting and mismatched slugs")]
+    Lint(LintArgs),
+
+    #[command(
+        about = "Report the vehicles added, removed and changed between two vehicles.json releases"
+    )]
+    Diff(DiffArgs),
+
+    #[command(about = "Show which layer file produced each value of a merged vehicle")]
+    Explain(ExplainArgs),
+
+    #[command(about = "Write the Vehicle JSON Schema generated from the ev-core types")]
+    Schema(SchemaArgs),
+
+    #[command(about = "Create the layer files of a new vehicle")]
+    New(NewArgs),
+
+    #[command(about = "Rewrite the dataset's JSON files in the canonical formatting")]
+    Fmt(FmtArgs),
+
+    #[command(about = "Print statistics of the merged and validated dataset")]
+    Stats(StatsArgs),
+
+    #[command(about = "Rebuild and validate each make/model as its layer files change")]
+    Watch(WatchArgs),
+}
+
+/// Options shared by the subcommands that merge and validate the dataset.
+#[derive(Debug, Clone, Args)]
+pub struct DatasetArgs {
+    #[arg(short, long, value_name = "DIR", required = true)]
     #[arg(help = "Input directory containing vehicle JSON files")]
-    pub input: PathBuf,
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE")]
+    #[arg(help = "JSON Schema to check layer files and merged vehicles against")]
+    #[arg(
+        long_help = "JSON Schema to check layer files and merged vehicles against. \
+        Defaults to the schema generated from the ev-core types"
+    )]
+    pub schema: Option<PathBuf>,
+
+    #[arg(short, long, value_name = "N")]
+    #[arg(help = "Worker threads for ingest, merge, validation and output (default: one per CPU)")]
+    pub jobs: Option<NonZeroUsize>,
+}
+
+impl DatasetArgs {
+    /// Input directory of the dataset; only absent when another subcommand is used.
+    pub fn input_dir(&self) -> Result<&Path> {
+        self.input.as_deref().context("--input is required")
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BuildArgs {
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
 
     #[arg(short, long, value_name = "DIR", default_value = "./output")]
     #[arg(help = "Output directory for generated artifacts")]
@@ -19,11 +108,164 @@ pub struct Cli {
     #[arg(help = "Output formats: json, sqlite, postgresql, csv, xml")]
     pub formats: Vec<String>,
 
+    #[arg(long = "output-path", value_name = "FORMAT=FILE", value_parser = parse_output_path)]
+    #[arg(help = "Write a format's artifact to FILE instead of the output directory")]
+    pub output_paths: Vec<(String, PathBuf)>,
+
+    #[arg(long, value_delimiter = ',', value_name = "FORMATS")]
+    #[arg(help = "Gzip the artifacts of these formats, adding .gz to their default file names")]
+    pub compress: Vec<String>,
+
     #[arg(long)]
-    #[arg(help = "Validate without generating output")]
+    #[arg(help = "Validate without generating output (same as the validate subcommand)")]
     pub validate_only: bool,
 
-    #[arg(short, long)]
-    #[arg(help = "Enable verbose output")]
-    pub verbose: bool,
+    #[arg(long)]
+    #[arg(help = "Rebuild every model and output, ignoring the build cache")]
+    pub no_cache: bool,
+
+    #[arg(long, value_name = "DIR")]
+    #[arg(help = "Build cache directory (default: .ev-etl-cache in the output directory)")]
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl BuildArgs {
+    /// Directory of the incremental build cache.
+    pub fn cache_dir(&self) -> PathBuf {
+        self.cache_dir
+            .clone()
+            .unwrap_or_else(|| self.output.join(crate::cache::DEFAULT_DIR_NAME))
+    }
+}
+
+fn parse_output_path(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((format, path)) if !format.is_empty() && !path.is_empty() => {
+            Ok((format.to_string(), PathBuf::from(path)))
+        }
+        _ => Err(format!("expected FORMAT=FILE, got '{}'", value)),
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    #[arg(short, long, value_name = "DIR")]
+    #[arg(help = "Input directory containing vehicle JSON files")]
+    pub input: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct NewArgs {
+    #[arg(value_name = "MAKE")]
+    #[arg(help = "Make slug, e.g. tesla")]
+    pub make: String,
+
+    #[arg(value_name = "MODEL")]
+    #[arg(help = "Model slug, e.g. model_3")]
+    pub model: String,
+
+    #[arg(value_name = "YEAR")]
+    #[arg(help = "Model year")]
+    pub year: u16,
+
+    #[arg(long, value_name = "SLUG")]
+    #[arg(help = "Also create a variant file for this variant slug")]
+    pub variant: Option<String>,
+
+    #[arg(short, long, value_name = "DIR")]
+    #[arg(help = "Input directory containing vehicle JSON files")]
+    pub input: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct FmtArgs {
+    #[arg(short, long, value_name = "DIR")]
+    #[arg(help = "Input directory containing vehicle JSON files")]
+    pub input: PathBuf,
+
+    #[arg(long)]
+    #[arg(help = "List the files that need formatting instead of rewriting them")]
+    pub check: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+
+    #[arg(short, long, value_name = "FILE")]
+    #[arg(help = "Output file (defaults to stdout)")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    #[arg(short, long, value_name = "FILE")]
+    #[arg(help = "Output file (defaults to stdout)")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    #[arg(value_name = "OLD")]
+    #[arg(help = "vehicles.json of the previous release")]
+    pub old: PathBuf,
+
+    #[arg(value_name = "NEW")]
+    #[arg(help = "vehicles.json of the new release")]
+    pub new: PathBuf,
+
+    #[arg(long, value_enum, default_value = "markdown")]
+    #[arg(help = "Report format")]
+    pub format: DiffFormat,
+
+    #[arg(short, long, value_name = "FILE")]
+    #[arg(help = "Output file (defaults to stdout)")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct ExplainArgs {
+    #[arg(value_name = "UNIQUE_CODE")]
+    #[arg(help = "Vehicle code, e.g. tesla:model_3:2024:long_range:awd")]
+    pub unique_code: String,
+
+    #[arg(short, long, value_name = "DIR")]
+    #[arg(help = "Input directory containing vehicle JSON files")]
+    pub input: PathBuf,
+
+    #[arg(long, value_enum, default_value = "text")]
+    #[arg(help = "Report format")]
+    pub format: ExplainFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[arg(short, long, value_name = "DIR")]
+    #[arg(help = "Input directory containing vehicle JSON files")]
+    pub input: PathBuf,
+
+    #[arg(long, value_name = "FILE")]
+    #[arg(help = "JSON Schema to check layer files and merged vehicles against")]
+    pub schema: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE")]
+    #[arg(help = "SQLite database to update after every change, e.g. the one ev-server reads")]
+    pub sqlite: Option<PathBuf>,
+
+    #[arg(long, value_name = "MS", default_value = "500")]
+    #[arg(help = "How often to check the input directory for changes, in milliseconds")]
+    pub interval: u64,
 }
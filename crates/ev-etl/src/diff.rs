@@ -0,0 +1,188 @@
+//! Changelog between two `vehicles.json` releases.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use ev_core::{ChangeKind, FieldChange, VehicleId, diff_documents};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Vehicles added, removed and changed between two releases, keyed by `unique_code`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatasetDiff {
+    pub old_vehicle_count: usize,
+    pub new_vehicle_count: usize,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<VehicleChanges>,
+    pub unchanged_count: usize,
+}
+
+/// Field changes of one vehicle present in both releases.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VehicleChanges {
+    pub unique_code: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Deserialize)]
+struct VehiclesFile {
+    vehicles: Vec<Value>,
+}
+
+/// Reads the vehicle documents of a `vehicles.json` artifact, or of a bare JSON
+/// array of vehicles.
+///
+/// Documents are kept as JSON, so releases written for another schema version
+/// can still be compared.
+pub fn load_vehicles(path: &Path) -> Result<Vec<Value>> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse JSON in {:?}", path))?;
+
+    let vehicles = if value.is_array() {
+        serde_json::from_value(value)
+    } else {
+        serde_json::from_value::<VehiclesFile>(value).map(|file| file.vehicles)
+    };
+    vehicles.with_context(|| format!("Failed to read vehicles from {:?}", path))
+}
+
+/// Compares two releases by `unique_code`, or by the code of a document's make,
+/// model, year, trim and variant slugs when it has none.
+///
+/// Fails when a release holds two vehicles with the same code, or a vehicle
+/// without a code or the slugs to build one.
+pub fn diff_datasets(old: &[Value], new: &[Value]) -> Result<DatasetDiff> {
+    let old_by_code = by_code(old, "old")?;
+    let new_by_code = by_code(new, "new")?;
+
+    let mut diff = DatasetDiff {
+        old_vehicle_count: old.len(),
+        new_vehicle_count: new.len(),
+        ..DatasetDiff::default()
+    };
+
+    for (code, old_vehicle) in &old_by_code {
+        match new_by_code.get(code) {
+            None => diff.removed.push(code.clone()),
+            Some(new_vehicle) => {
+                let changes = diff_documents(old_vehicle, new_vehicle);
+                if changes.is_empty() {
+                    diff.unchanged_count += 1;
+                } else {
+                    diff.changed.push(VehicleChanges {
+                        unique_code: code.clone(),
+                        changes: changes.changes,
+                    });
+                }
+            }
+        }
+    }
+    diff.added = new_by_code
+        .keys()
+        .filter(|code| !old_by_code.contains_key(*code))
+        .cloned()
+        .collect();
+
+    Ok(diff)
+}
+
+/// Indexes the vehicles of the `release` release by code.
+fn by_code<'a>(vehicles: &'a [Value], release: &str) -> Result<BTreeMap<String, &'a Value>> {
+    let mut by_code = BTreeMap::new();
+    for (index, vehicle) in vehicles.iter().enumerate() {
+        let code = match vehicle.get("unique_code").and_then(Value::as_str) {
+            Some(code) => code.to_string(),
+            None => VehicleId::from_document(vehicle)
+                .with_context(|| {
+                    format!(
+                        "Vehicle {} of the {} release has no unique_code, make, model, year or trim",
+                        index, release
+                    )
+                })?
+                .to_string(),
+        };
+        if by_code.insert(code.clone(), vehicle).is_some() {
+            bail!("Duplicate unique_code {} in the {} release", code, release);
+        }
+    }
+    Ok(by_code)
+}
+
+impl DatasetDiff {
+    /// Returns `true` when both releases hold the same vehicles with the same data.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Renders the diff as a Markdown changelog.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        self.write_markdown(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    fn write_markdown(&self, out: &mut String) -> std::fmt::Result {
+        out.push_str("# Dataset Changes\n\n");
+        writeln!(
+            out,
+            "{} vehicles before, {} after: {} added, {} removed, {} changed, {} unchanged.",
+            self.old_vehicle_count,
+            self.new_vehicle_count,
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+            self.unchanged_count
+        )?;
+
+        for (title, codes) in [("Added", &self.added), ("Removed", &self.removed)] {
+            if codes.is_empty() {
+                continue;
+            }
+            writeln!(out, "\n## {title}\n")?;
+            for code in codes {
+                writeln!(out, "- `{code}`")?;
+            }
+        }
+
+        if !self.changed.is_empty() {
+            out.push_str("\n## Changed\n");
+            for vehicle in &self.changed {
+                writeln!(out, "\n### `{}`\n", vehicle.unique_code)?;
+                out.push_str("| Field | Change | Old | New |\n");
+                out.push_str("|-------|--------|-----|-----|\n");
+                for change in &vehicle.changes {
+                    let kind = match change.kind {
+                        ChangeKind::Added => "added",
+                        ChangeKind::Removed => "removed",
+                        ChangeKind::Changed => "changed",
+                    };
+                    writeln!(
+                        out,
+                        "| `{}` | {} | {} | {} |",
+                        change.path,
+                        kind,
+                        markdown_value(change.old.as_ref()),
+                        markdown_value(change.new.as_ref())
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn markdown_value(value: Option<&Value>) -> String {
+    match value {
+        None => String::new(),
+        Some(value) => format!("`{}`", value.to_string().replace('|', "\\|")),
+    }
+}
@@ -30,3 +30,48 @@ pub enum EtlError {
 
 #[allow(dead_code)]
 pub type Result<T> = std::result::Result<T, EtlError>;
+
+/// The dataset was read but failed a check: a malformed or misnamed layer file,
+/// a merge error, invalid vehicles, lint findings or unformatted files.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct ValidationFailure(pub String);
+
+/// Layer files or merged vehicles violate the JSON Schema. `report` records each
+/// violation so the build can still write it out before failing.
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct SchemaFailure {
+    pub message: String,
+    pub report: crate::validate::ValidationReport,
+}
+
+/// The command line names something that does not exist, such as an output
+/// format missing from the registry.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct UsageError(pub String);
+
+/// Exit code when the dataset fails a check.
+pub const EXIT_VALIDATION_FAILED: u8 = 1;
+
+/// Exit code for an invalid command line, the same as clap uses.
+pub const EXIT_USAGE: u8 = 2;
+
+/// Exit code for I/O and other errors.
+pub const EXIT_ERROR: u8 = 3;
+
+/// Exit code for an error returned by a subcommand.
+#[must_use]
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    if error.chain().any(|cause| cause.is::<UsageError>()) {
+        EXIT_USAGE
+    } else if error
+        .chain()
+        .any(|cause| cause.is::<ValidationFailure>() || cause.is::<SchemaFailure>())
+    {
+        EXIT_VALIDATION_FAILED
+    } else {
+        EXIT_ERROR
+    }
+}
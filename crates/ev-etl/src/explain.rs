@@ -0,0 +1,102 @@
+//! Origin of every value of one merged vehicle.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use anyhow::{Result, bail};
+use ev_core::VehicleId;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::UsageError;
+use crate::ingest::VehicleFile;
+use crate::merge::{self, Override, Provenance};
+
+/// A merged vehicle document with the file that set each of its values.
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub unique_code: String,
+    /// Dataset-relative label of the leaf file, e.g. `tesla/model_3/2024/model_3.json`.
+    pub file: String,
+    pub vehicle: Value,
+    #[serde(flatten)]
+    pub provenance: Provenance,
+}
+
+/// Merges the layers of the vehicle's make/model with provenance and returns the
+/// vehicle whose `unique_code` matches.
+///
+/// Other models are not merged, so their errors do not hide this vehicle. Codes come
+/// from the slugs of the merged documents, so a vehicle that fails to deserialize can
+/// still be explained.
+pub fn explain(files: &[VehicleFile], unique_code: &str) -> Result<Explanation> {
+    let wanted: VehicleId = unique_code
+        .parse()
+        .map_err(|error| UsageError(format!("Invalid unique_code: {}", error)))?;
+
+    let model_files: Vec<VehicleFile> = merge::group_by_model(files)
+        .remove(&(wanted.make_slug.as_str(), wanted.model_slug.as_str()))
+        .unwrap_or_default()
+        .into_iter()
+        .cloned()
+        .collect();
+
+    for document in merge::merge_documents_traced(&model_files)? {
+        if VehicleId::from_document(&document.content).as_ref() == Some(&wanted) {
+            return Ok(Explanation {
+                unique_code: wanted.to_string(),
+                file: document.label,
+                vehicle: document.content,
+                provenance: document.provenance.unwrap_or_default(),
+            });
+        }
+    }
+
+    bail!("No vehicle with unique_code {}", wanted)
+}
+
+impl Explanation {
+    /// Renders one line per value with its source file, followed by the values it replaced.
+    ///
+    /// Values replaced by a shorter array or a non-object are listed without a current value.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    fn write_text(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "{} ({})\n", self.unique_code, self.file)?;
+
+        let mut paths: BTreeMap<&str, Vec<&Override>> = self
+            .provenance
+            .sources
+            .keys()
+            .map(|path| (path.as_str(), Vec::new()))
+            .collect();
+        for replaced in &self.provenance.overrides {
+            paths
+                .entry(replaced.path.as_str())
+                .or_default()
+                .push(replaced);
+        }
+
+        for (path, overrides) in paths {
+            match (self.vehicle.pointer(path), self.provenance.source(path)) {
+                (Some(value), Some(source)) => writeln!(out, "{path} = {value}  [{source}]")?,
+                _ => writeln!(out, "{path} (unset)")?,
+            }
+            for replaced in overrides {
+                writeln!(
+                    out,
+                    "    overrides {} [{}], replaced by {}",
+                    replaced.value, replaced.source, replaced.overridden_by
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Canonical formatting of dataset JSON files.
+//!
+//! Files are laid out like `JSON.stringify(value, null, 4)`: one member or item
+//! per line, four-space indentation, empty objects and arrays kept as `{}` and
+//! `[]`, and a trailing newline. Keys keep their order and strings and numbers
+//! keep their exact spelling, so formatting never changes a value.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::error::ValidationFailure;
+use crate::ingest::reader::json_paths;
+
+const INDENT: &str = "    ";
+
+/// Returns `source` in the canonical formatting.
+pub fn format_json(source: &str) -> Result<String> {
+    serde_json::from_str::<serde::de::IgnoredAny>(source)
+        .map_err(|e| ValidationFailure(format!("Invalid JSON: {}", e)))?;
+
+    let mut out = String::with_capacity(source.len());
+    let mut depth = 0;
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut end = source.len();
+                let mut escaped = false;
+                for (index, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => {
+                            end = index + 1;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                out.push_str(&source[start..end]);
+            }
+            '{' | '[' => {
+                while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                let close = if c == '{' { '}' } else { ']' };
+                out.push(c);
+                if chars.next_if(|&(_, next)| next == close).is_some() {
+                    out.push(close);
+                } else {
+                    depth += 1;
+                    new_line(&mut out, depth);
+                }
+            }
+            '}' | ']' => {
+                depth -= 1;
+                new_line(&mut out, depth);
+                out.push(c);
+            }
+            ',' => {
+                out.push(',');
+                new_line(&mut out, depth);
+            }
+            ':' => out.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    }
+
+    out.push('\n');
+    Ok(out)
+}
+
+/// Formats every JSON file under `input_dir`, returning those that were not in
+/// the canonical formatting. They are rewritten unless `check` is set.
+pub fn format_dataset(input_dir: &Path, check: bool) -> Result<Vec<PathBuf>> {
+    let mut changed = Vec::new();
+
+    for path in json_paths(input_dir) {
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read: {:?}", path))?;
+        let formatted = format_json(&source).with_context(|| format!("{:?}", path))?;
+        if formatted == source {
+            continue;
+        }
+        if !check {
+            std::fs::write(&path, formatted)
+                .with_context(|| format!("Failed to write: {:?}", path))?;
+        }
+        changed.push(path);
+    }
+
+    Ok(changed)
+}
+
+fn new_line(out: &mut String, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+}
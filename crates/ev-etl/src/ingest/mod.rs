@@ -11,3 +11,9 @@ pub use reader::{FileType, VehicleFile};
 pub fn load_dataset(input_dir: &Path) -> Result<Vec<VehicleFile>> {
     reader::scan_directory(input_dir).context("Failed to scan dataset directory")
 }
+
+/// Reads the layer files of one make/model; empty when the model directory is gone.
+pub fn load_model(input_dir: &Path, make_slug: &str, model_slug: &str) -> Result<Vec<VehicleFile>> {
+    reader::scan_model(input_dir, make_slug, model_slug)
+        .with_context(|| format!("Failed to scan {}/{}", make_slug, model_slug))
+}
@@ -1,9 +1,13 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
+use crate::error::ValidationFailure;
+
 #[derive(Debug, Clone)]
 pub struct VehicleFile {
     pub path: PathBuf,
@@ -13,6 +17,8 @@ pub struct VehicleFile {
     pub file_type: FileType,
     pub file_slug: String,
     pub content: Value,
+    /// Hex SHA-256 of the file as read from disk.
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,28 +28,45 @@ pub enum FileType {
     Variant,
 }
 
+/// Reads every layer file under `input_dir`, parsing them in parallel.
+///
+/// Files are returned in a stable order, and the first failing file in that
+/// order is reported.
 pub fn scan_directory(input_dir: &Path) -> Result<Vec<VehicleFile>> {
-    let mut files = Vec::new();
+    scan(input_dir, input_dir)
+}
 
-    for entry in WalkDir::new(input_dir)
+/// Reads the layer files of one make/model of the dataset in `input_dir`, as
+/// [`scan_directory`] would.
+pub fn scan_model(input_dir: &Path, make_slug: &str, model_slug: &str) -> Result<Vec<VehicleFile>> {
+    scan(&input_dir.join(make_slug).join(model_slug), input_dir)
+}
+
+/// Paths of the JSON files under `root`, sorted, skipping dotfiles.
+pub fn json_paths(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
         .min_depth(1)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
         .filter(|e| e.file_type().is_file())
         .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-    {
-        let path = entry.path();
-
-        if path
-            .file_name()
-            .is_some_and(|n| n.to_string_lossy().starts_with('.'))
-        {
-            continue;
-        }
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| e.into_path())
+        .collect()
+}
 
-        if let Some(vehicle_file) = parse_vehicle_file(path, input_dir)? {
-            files.push(vehicle_file);
-        }
+fn scan(root: &Path, input_dir: &Path) -> Result<Vec<VehicleFile>> {
+    let paths = json_paths(root);
+
+    let parsed: Vec<Result<Option<VehicleFile>>> = paths
+        .par_iter()
+        .map(|path| parse_vehicle_file(path, input_dir))
+        .collect();
+
+    let mut files = Vec::new();
+    for vehicle_file in parsed {
+        files.extend(vehicle_file?);
     }
 
     files.sort_by(|a, b| {
@@ -114,13 +137,11 @@ fn parse_vehicle_file(path: &Path, base_dir: &Path) -> Result<Option<VehicleFile
         } else if file_name.starts_with(&format!("{}_", model_slug)) {
             (Some(year), FileType::Variant)
         } else {
-            return Err(anyhow::anyhow!(
+            return Err(ValidationFailure(format!(
                 "Invalid file name '{}.json' in year {} folder. Expected exact match '{}' or starts with '{}_'",
-                file_name,
-                year,
-                model_slug,
-                model_slug
-            ));
+                file_name, year, model_slug, model_slug
+            ))
+            .into());
         }
     } else {
         return Ok(None);
@@ -130,7 +151,7 @@ fn parse_vehicle_file(path: &Path, base_dir: &Path) -> Result<Option<VehicleFile
         std::fs::read_to_string(path).with_context(|| format!("Failed to read: {:?}", path))?;
 
     let content: Value = serde_json::from_str(&content_str)
-        .with_context(|| format!("Failed to parse: {:?}", path))?;
+        .map_err(|e| ValidationFailure(format!("Failed to parse: {:?}: {}", path, e)))?;
 
     Ok(Some(VehicleFile {
         path: path.to_path_buf(),
@@ -140,5 +161,6 @@ fn parse_vehicle_file(path: &Path, base_dir: &Path) -> Result<Option<VehicleFile
         file_type,
         file_slug: file_name.to_string(),
         content,
+        sha256: hex::encode(Sha256::digest(content_str.as_bytes())),
     }))
 }
@@ -1,109 +1,289 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use tracing::{info, warn};
 
+pub mod cache;
 pub mod cli;
+pub mod diff;
 pub mod error;
+pub mod explain;
+pub mod format;
 pub mod ingest;
+pub mod lint;
 pub mod merge;
 pub mod output;
+pub mod scaffold;
 pub mod validate;
+pub mod watch;
+
+use cli::{
+    BuildArgs, DatasetArgs, DiffArgs, DiffFormat, ExplainArgs, ExplainFormat, FmtArgs, LintArgs,
+    NewArgs, SchemaArgs, StatsArgs, WatchArgs,
+};
+use error::{SchemaFailure, UsageError, ValidationFailure};
+use output::{Compression, OutputOptions, OutputRegistry, OutputWriter};
 
-use cli::Cli;
+pub fn run_validation(args: &DatasetArgs) -> Result<()> {
+    with_jobs(args.jobs, || validation(args))
+}
 
-pub fn run_validation(cli: &Cli) -> Result<()> {
+fn validation(args: &DatasetArgs) -> Result<()> {
     info!("Running validation-only mode");
 
-    let raw_vehicles = ingest::load_dataset(&cli.input)?;
+    let raw_vehicles = ingest::load_dataset(args.input_dir()?)?;
     info!("Loaded {} vehicle files", raw_vehicles.len());
 
-    let merged_vehicles = merge::merge_all(&raw_vehicles)?;
-    info!("Merged into {} vehicles", merged_vehicles.len());
-
-    let results = validate::validate_all(&merged_vehicles);
-    let (valid, invalid): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
+    let (_, report) = build_models(args, &raw_vehicles, None)?;
+    log_findings(&report);
 
     info!(
-        "Validation complete: {} valid, {} invalid",
-        valid.len(),
-        invalid.len()
+        "Validation complete: {} valid, {} invalid, {} warnings",
+        report.valid_count,
+        report.total_vehicles - report.valid_count,
+        report.warning_count
     );
 
-    if !invalid.is_empty() {
-        for result in &invalid {
-            if let Err(e) = result {
-                tracing::error!("{}", e);
-            }
+    if report.valid_count < report.total_vehicles {
+        return Err(ValidationFailure(format!(
+            "{} vehicles failed validation",
+            report.total_vehicles - report.valid_count
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+pub fn run_schema(args: &SchemaArgs) -> Result<()> {
+    let schema = serde_json::to_string_pretty(&ev_core::schema::vehicle_schema())?;
+
+    match args.output {
+        Some(ref path) => {
+            std::fs::write(path, schema + "\n")
+                .with_context(|| format!("Failed to write schema to {:?}", path))?;
+            info!("Generated: {:?}", path);
+        }
+        None => {
+            use std::io::Write;
+            writeln!(std::io::stdout(), "{}", schema).context("Failed to write schema")?;
         }
-        anyhow::bail!("{} vehicles failed validation", invalid.len());
     }
 
     Ok(())
 }
 
-pub fn run_pipeline(cli: &Cli) -> Result<()> {
-    let start_time = std::time::Instant::now();
+pub fn run_diff(args: &DiffArgs) -> Result<()> {
+    let old = diff::load_vehicles(&args.old)?;
+    let new = diff::load_vehicles(&args.new)?;
+    let changes = diff::diff_datasets(&old, &new)?;
 
-    info!("Input directory: {:?}", cli.input);
-    info!("Output directory: {:?}", cli.output);
-    info!("Formats: {:?}", cli.formats);
+    let report = match args.format {
+        DiffFormat::Markdown => changes.to_markdown(),
+        DiffFormat::Json => serde_json::to_string_pretty(&changes)? + "\n",
+    };
 
-    std::fs::create_dir_all(&cli.output).context("Failed to create output directory")?;
+    match args.output {
+        Some(ref path) => {
+            std::fs::write(path, report)
+                .with_context(|| format!("Failed to write diff to {:?}", path))?;
+            info!(
+                "{} added, {} removed, {} changed",
+                changes.added.len(),
+                changes.removed.len(),
+                changes.changed.len()
+            );
+            info!("Generated: {:?}", path);
+        }
+        None => {
+            use std::io::Write;
+            write!(std::io::stdout(), "{}", report).context("Failed to write diff")?;
+        }
+    }
 
-    let raw_vehicles = ingest::load_dataset(&cli.input)?;
-    info!("Loaded {} raw vehicle files", raw_vehicles.len());
+    Ok(())
+}
 
-    let merged_vehicles = merge::merge_all(&raw_vehicles)?;
-    info!("Merged into {} canonical vehicles", merged_vehicles.len());
+pub fn run_explain(args: &ExplainArgs) -> Result<()> {
+    let files = ingest::load_dataset(&args.input)?;
+    let explanation = explain::explain(&files, &args.unique_code)?;
 
-    let validation_results = validate::validate_all(&merged_vehicles);
-    let valid_vehicles: Vec<_> = validation_results
-        .into_iter()
-        .zip(merged_vehicles.into_iter())
-        .filter_map(|(result, vehicle)| {
-            if result.is_ok() {
-                Some(vehicle)
-            } else {
-                warn!("Skipping invalid vehicle: {:?}", result.err());
-                None
-            }
-        })
-        .collect();
+    let report = match args.format {
+        ExplainFormat::Text => explanation.to_text(),
+        ExplainFormat::Json => serde_json::to_string_pretty(&explanation)? + "\n",
+    };
 
-    info!("{} vehicles passed validation", valid_vehicles.len());
+    use std::io::Write;
+    write!(std::io::stdout(), "{}", report).context("Failed to write explanation")
+}
 
-    for format in &cli.formats {
-        match format.as_str() {
-            "json" => {
-                let path = cli.output.join("vehicles.json");
-                output::json::generate(&valid_vehicles, &path)?;
-                info!("Generated: {:?}", path);
-            }
-            "sqlite" => {
-                let path = cli.output.join("vehicles.db");
-                output::sqlite::generate(&valid_vehicles, &path)?;
-                info!("Generated: {:?}", path);
-            }
-            "postgresql" => {
-                let path = cli.output.join("vehicles.sql");
-                output::postgresql::generate(&valid_vehicles, &path)?;
+/// Rebuilds each make/model as its layer files change, until interrupted.
+pub fn run_watch(args: &WatchArgs) -> Result<()> {
+    let mut watcher = watch::Watcher::new(args)?;
+    info!("Watching {:?} for changes", args.input);
+
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(args.interval));
+        watcher.poll()?;
+    }
+}
+
+pub fn run_lint(args: &LintArgs) -> Result<()> {
+    let findings = lint::lint_dataset(&args.input)?;
+
+    use std::io::Write;
+    let mut stdout = std::io::stdout().lock();
+    for finding in &findings {
+        writeln!(stdout, "{}", finding).context("Failed to write lint findings")?;
+    }
+
+    if !findings.is_empty() {
+        return Err(ValidationFailure(format!("{} lint finding(s)", findings.len())).into());
+    }
+    info!("No lint findings");
+    Ok(())
+}
+
+pub fn run_fmt(args: &FmtArgs) -> Result<()> {
+    let changed = format::format_dataset(&args.input, args.check)?;
+
+    if args.check {
+        use std::io::Write;
+        let mut stdout = std::io::stdout().lock();
+        for path in &changed {
+            writeln!(stdout, "{}", path.display()).context("Failed to write file list")?;
+        }
+        if !changed.is_empty() {
+            return Err(
+                ValidationFailure(format!("{} file(s) need formatting", changed.len())).into(),
+            );
+        }
+    } else {
+        for path in &changed {
+            info!("Formatted: {:?}", path);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_new(args: &NewArgs) -> Result<()> {
+    let created = scaffold::new_vehicle(
+        &args.input,
+        &args.make,
+        &args.model,
+        args.year,
+        args.variant.as_deref(),
+    )?;
+    for path in created {
+        info!("Created: {:?}", path);
+    }
+    Ok(())
+}
+
+pub fn run_stats(args: &StatsArgs) -> Result<()> {
+    with_jobs(args.dataset.jobs, || {
+        let start_time = std::time::Instant::now();
+        let files = ingest::load_dataset(args.dataset.input_dir()?)?;
+        let (vehicles, _) = build_models(&args.dataset, &files, None)?;
+        let stats = output::statistics::generate(&vehicles, start_time.elapsed());
+        let stats = serde_json::to_string_pretty(&stats)? + "\n";
+
+        match args.output {
+            Some(ref path) => {
+                std::fs::write(path, stats)
+                    .with_context(|| format!("Failed to write statistics to {:?}", path))?;
                 info!("Generated: {:?}", path);
             }
-            "csv" => {
-                let path = cli.output.join("vehicles.csv");
-                output::csv::generate(&valid_vehicles, &path)?;
-                info!("Generated: {:?}", path);
+            None => {
+                use std::io::Write;
+                write!(std::io::stdout(), "{}", stats).context("Failed to write statistics")?;
             }
-            "xml" => {
-                let path = cli.output.join("vehicles.xml");
-                output::xml::generate(&valid_vehicles, &path)?;
-                info!("Generated: {:?}", path);
+        }
+        Ok(())
+    })
+}
+
+pub fn run_pipeline(args: &BuildArgs) -> Result<()> {
+    run_pipeline_with(args, &OutputRegistry::default())
+}
+
+/// Runs `build` with the output formats of `registry`, which may hold writers
+/// of other crates besides the built-in ones.
+pub fn run_pipeline_with(args: &BuildArgs, registry: &OutputRegistry) -> Result<()> {
+    let targets = output_targets(args, registry)?;
+    with_jobs(args.dataset.jobs, || pipeline(args, &targets))
+}
+
+/// Runs `f` on a pool of `jobs` worker threads, or on the default pool with one
+/// thread per CPU.
+fn with_jobs<T: Send>(
+    jobs: Option<NonZeroUsize>,
+    f: impl FnOnce() -> Result<T> + Send,
+) -> Result<T> {
+    match jobs {
+        Some(jobs) => rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.get())
+            .build()
+            .context("Failed to start worker threads")?
+            .install(f),
+        None => f(),
+    }
+}
+
+fn pipeline(args: &BuildArgs, targets: &[OutputTarget]) -> Result<()> {
+    let start_time = std::time::Instant::now();
+
+    info!("Input directory: {:?}", args.dataset.input_dir()?);
+    info!("Output directory: {:?}", args.output);
+    info!("Formats: {:?}", args.formats);
+
+    std::fs::create_dir_all(&args.output).context("Failed to create output directory")?;
+
+    let raw_vehicles = ingest::load_dataset(args.dataset.input_dir()?)?;
+    info!("Loaded {} raw vehicle files", raw_vehicles.len());
+
+    let mut cache = cache::BuildCache::open(&args.cache_dir(), !args.no_cache)?;
+
+    let report_path = args.output.join("validation_report.json");
+    let (valid_vehicles, report) = match build_models(&args.dataset, &raw_vehicles, Some(&cache)) {
+        Ok(built) => built,
+        Err(error) => {
+            // Schema violations stop the build, but still belong in the report.
+            if let Some(failure) = error.downcast_ref::<SchemaFailure>() {
+                failure.report.save_to_file(&report_path)?;
+                info!("Generated: {:?}", report_path);
             }
-            _ => warn!("Unknown format: {}", format),
+            return Err(error);
+        }
+    };
+    log_findings(&report);
+
+    report.save_to_file(&report_path)?;
+    info!("Generated: {:?}", report_path);
+
+    info!("{} vehicles passed validation", valid_vehicles.len());
+
+    // Writers are independent; results are reported in the order of --formats.
+    let generated: Vec<Result<Artifact>> = targets
+        .par_iter()
+        .map(|target| write_output(target, &valid_vehicles, &cache))
+        .collect();
+    for artifact in generated {
+        let artifact = artifact?;
+        if artifact.written {
+            info!("Generated: {:?}", artifact.path);
+            cache.record_output(&artifact.path, artifact.key);
+        } else {
+            info!("Unchanged: {:?}", artifact.path);
         }
     }
+    cache.save()?;
 
     let stats = output::statistics::generate(&valid_vehicles, start_time.elapsed());
-    let stats_path = cli.output.join("statistics.json");
+    let stats_path = args.output.join("statistics.json");
     std::fs::write(&stats_path, serde_json::to_string_pretty(&stats)?)?;
     info!("Generated: {:?}", stats_path);
 
@@ -114,3 +294,318 @@ pub fn run_pipeline(cli: &Cli) -> Result<()> {
 
     Ok(())
 }
+
+/// An artifact requested on the command line.
+struct OutputTarget {
+    writer: Arc<dyn OutputWriter>,
+    options: OutputOptions,
+}
+
+/// Resolves `--formats`, `--output-path` and `--compress` against `registry`.
+fn output_targets(args: &BuildArgs, registry: &OutputRegistry) -> Result<Vec<OutputTarget>> {
+    let mut seen = std::collections::HashSet::new();
+    let formats: Vec<&str> = args
+        .formats
+        .iter()
+        .map(String::as_str)
+        .filter(|format| seen.insert(*format))
+        .collect();
+
+    for format in args
+        .compress
+        .iter()
+        .chain(args.output_paths.iter().map(|(f, _)| f))
+    {
+        if !formats.contains(&format.as_str()) {
+            return Err(UsageError(format!(
+                "Output format '{}' has options but is not in --formats",
+                format
+            ))
+            .into());
+        }
+    }
+
+    formats
+        .into_iter()
+        .map(|format| {
+            let writer = registry.get(format).cloned().ok_or_else(|| {
+                UsageError(format!(
+                    "Unknown output format '{}' (available: {})",
+                    format,
+                    registry.names().collect::<Vec<_>>().join(", ")
+                ))
+            })?;
+
+            let compression = if args.compress.iter().any(|f| f == format) {
+                if !writer.supports_compression() {
+                    return Err(UsageError(format!(
+                        "Output format '{}' cannot be compressed",
+                        format
+                    ))
+                    .into());
+                }
+                Compression::Gzip
+            } else {
+                Compression::None
+            };
+            let path = match args.output_paths.iter().rfind(|(f, _)| f == format) {
+                Some((_, path)) => path.clone(),
+                None => args.output.join(format!(
+                    "{}{}",
+                    writer.default_file_name(),
+                    compression.extension()
+                )),
+            };
+
+            Ok(OutputTarget {
+                writer,
+                options: OutputOptions { path, compression },
+            })
+        })
+        .collect()
+}
+
+/// An output file of the pipeline.
+struct Artifact {
+    path: std::path::PathBuf,
+    /// [`cache::output_key`] of the vehicles it holds.
+    key: String,
+    /// `false` when the file was already current and left untouched.
+    written: bool,
+}
+
+/// Writes the artifact of `target` unless `cache` shows it is current.
+fn write_output(
+    target: &OutputTarget,
+    vehicles: &[ev_core::Vehicle],
+    cache: &cache::BuildCache,
+) -> Result<Artifact> {
+    let path = target.options.path.clone();
+    let format = format!(
+        "{}{}",
+        target.writer.name(),
+        target.options.compression.extension()
+    );
+    let key = cache::output_key(&format, vehicles)?;
+    let written = !cache.is_output_current(&path, &key);
+    if written {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        output::write_all(target.writer.as_ref(), vehicles, &target.options)?;
+    }
+    Ok(Artifact { path, key, written })
+}
+
+/// Merges, schema-checks and validates the layer files, returning the valid
+/// vehicles and the report of all findings.
+fn build_models(
+    args: &DatasetArgs,
+    files: &[ingest::VehicleFile],
+    cache: Option<&cache::BuildCache>,
+) -> Result<(Vec<ev_core::Vehicle>, validate::ValidationReport)> {
+    let schema = Schema::load(args.schema.as_deref())?;
+    let builds = build_model_groups(files, args.input_dir()?, &schema, cache)?;
+    combine_builds(builds.into_values())
+}
+
+/// Builds of each make/model group, keyed by make and model directory.
+type ModelBuilds = std::collections::BTreeMap<(String, String), cache::ModelBuild>;
+
+/// Merges, schema-checks and validates the layer files one make/model at a time.
+///
+/// Models found in `cache` are reused as they are; the others are rebuilt
+/// together and stored back.
+fn build_model_groups(
+    files: &[ingest::VehicleFile],
+    input_dir: &std::path::Path,
+    schema: &Schema,
+    cache: Option<&cache::BuildCache>,
+) -> Result<ModelBuilds> {
+    let schema_hash = cache::schema_hash(&schema.value);
+
+    let models = merge::group_by_model(files);
+    let cached: Vec<(String, Option<cache::ModelBuild>)> = models
+        .par_iter()
+        .map(|(&(make_slug, model_slug), model_files)| {
+            let key = cache::model_key(model_files, &schema_hash);
+            let build = cache.and_then(|cache| cache.load_model(make_slug, model_slug, &key));
+            (key, build)
+        })
+        .collect();
+
+    let stale_files: Vec<ingest::VehicleFile> = models
+        .values()
+        .zip(&cached)
+        .filter(|(_, (_, build))| build.is_none())
+        .flat_map(|(model_files, _)| model_files.iter().map(|&file| file.clone()))
+        .collect();
+    if cache.is_some() {
+        let reused = cached.iter().filter(|(_, build)| build.is_some()).count();
+        info!(
+            "Reusing {} of {} models from the build cache",
+            reused,
+            models.len()
+        );
+    }
+
+    let mut stale_vehicles = if stale_files.is_empty() {
+        std::collections::BTreeMap::new()
+    } else {
+        let vehicles = merge_and_check(&stale_files, input_dir, schema)?;
+        info!(
+            "Merged {} models into {} canonical vehicles",
+            vehicles.len(),
+            vehicles.values().map(Vec::len).sum::<usize>()
+        );
+        vehicles
+    };
+
+    let pending: Vec<_> = models
+        .keys()
+        .zip(cached)
+        .map(|(&(make_slug, model_slug), (key, build))| {
+            let vehicles = match build {
+                Some(_) => Vec::new(),
+                None => stale_vehicles
+                    .remove(&(make_slug.to_string(), model_slug.to_string()))
+                    .unwrap_or_default(),
+            };
+            (make_slug, model_slug, key, build, vehicles)
+        })
+        .collect();
+    pending
+        .into_par_iter()
+        .map(|(make_slug, model_slug, key, build, vehicles)| {
+            let model = (make_slug.to_string(), model_slug.to_string());
+            if let Some(build) = build {
+                return Ok((model, build));
+            }
+            let (vehicles, report) = validate::validate_with_report(vehicles);
+            let build = cache::ModelBuild {
+                key,
+                vehicles,
+                report,
+            };
+            if let Some(cache) = cache {
+                cache.store_model(make_slug, model_slug, &build)?;
+            }
+            Ok((model, build))
+        })
+        .collect()
+}
+
+/// Joins model builds into the sorted valid vehicles and one report.
+fn combine_builds(
+    builds: impl IntoIterator<Item = cache::ModelBuild>,
+) -> Result<(Vec<ev_core::Vehicle>, validate::ValidationReport)> {
+    let mut vehicles = Vec::new();
+    let mut report = validate::ValidationReport::new();
+    let mut codes = std::collections::HashSet::new();
+    for build in builds {
+        for vehicle in &build.vehicles {
+            let code = vehicle.id().to_string();
+            if !codes.insert(code.clone()) {
+                return Err(ValidationFailure(format!(
+                    "Duplicate unique_code {} in more than one model",
+                    code
+                ))
+                .into());
+            }
+        }
+        vehicles.extend(build.vehicles);
+        report.extend(build.report);
+    }
+    merge::sort_vehicles(&mut vehicles);
+
+    Ok((vehicles, report))
+}
+
+/// The JSON Schema layer files and merged vehicles are checked against.
+struct Schema {
+    value: serde_json::Value,
+    /// Name for log messages.
+    name: String,
+}
+
+impl Schema {
+    /// Loads `path`, or the schema generated from ev-core.
+    fn load(path: Option<&std::path::Path>) -> Result<Self> {
+        Ok(match path {
+            Some(path) => Self {
+                value: validate::load_schema(path)?,
+                name: format!("schema {:?}", path),
+            },
+            None => Self {
+                value: ev_core::schema::vehicle_schema(),
+                name: "bundled vehicle schema".to_string(),
+            },
+        })
+    }
+}
+
+/// Merges layer files into vehicles, checking layers and merged documents against
+/// `schema` before deserialization.
+fn merge_and_check(
+    files: &[ingest::VehicleFile],
+    input_dir: &std::path::Path,
+    schema: &Schema,
+) -> Result<merge::OriginModelVehicles> {
+    // Traced, so validation findings can name the layer that set the value.
+    let documents = merge::merge_documents_traced(files)?;
+
+    let mut violations = validate::validate_layer_files(files, &schema.value, input_dir)?;
+    violations.extend(validate::validate_merged_documents(
+        &documents,
+        &schema.value,
+    )?);
+
+    if !violations.is_empty() {
+        let mut report = validate::ValidationReport::new();
+        for violation in &violations {
+            tracing::error!("{}", violation);
+            report.add_schema_violation(violation);
+        }
+        let file_count = violations
+            .iter()
+            .map(|v| v.file.as_str())
+            .collect::<std::collections::BTreeSet<_>>()
+            .len();
+        return Err(SchemaFailure {
+            message: format!(
+                "{} schema violation(s) in {} file(s)",
+                violations.len(),
+                file_count
+            ),
+            report,
+        }
+        .into());
+    }
+
+    info!("All files conform to the {}", schema.name);
+
+    merge::into_model_vehicles_with_origin(documents)
+}
+
+fn log_findings(report: &validate::ValidationReport) {
+    for error in &report.errors {
+        tracing::error!(
+            "{} {} at {}: {} [{}]",
+            error.vehicle_id,
+            error.file,
+            error.path,
+            error.message,
+            error.error_type
+        );
+    }
+    for warning in &report.warnings {
+        warn!(
+            "{} {} at {}: {} [{}]",
+            warning.vehicle_id, warning.file, warning.path, warning.message, warning.error_type
+        );
+    }
+}
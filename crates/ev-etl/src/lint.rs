@@ -0,0 +1,91 @@
+//! Dataset checks that the schema cannot express: files ingest skips, formatting,
+//! and layers whose identity fields disagree with their directory.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::format::format_json;
+use crate::ingest::{self, reader::json_paths};
+
+/// A problem found in one dataset file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    /// Dataset-relative path of the file.
+    pub file: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} [{}]", self.file, self.message, self.code)
+    }
+}
+
+/// Lints every JSON file under `input_dir`, returning the findings ordered by file.
+pub fn lint_dataset(input_dir: &Path) -> Result<Vec<LintFinding>> {
+    let files = ingest::load_dataset(input_dir)?;
+    let loaded: HashSet<&Path> = files.iter().map(|file| file.path.as_path()).collect();
+    let label = |path: &Path| {
+        path.strip_prefix(input_dir)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+
+    let mut findings = Vec::new();
+
+    for path in json_paths(input_dir) {
+        if !loaded.contains(path.as_path()) {
+            findings.push(LintFinding {
+                file: label(&path),
+                code: "ignored_file",
+                message: "not a model base, year base or variant file, so ingest skips it"
+                    .to_string(),
+            });
+            continue;
+        }
+
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read: {:?}", path))?;
+        if format_json(&source)? != source {
+            findings.push(LintFinding {
+                file: label(&path),
+                code: "unformatted",
+                message: "not in the canonical formatting; run `ev-etl fmt`".to_string(),
+            });
+        }
+    }
+
+    for file in &files {
+        let expected = [
+            ("/make/slug", Some(file.make_slug.clone())),
+            ("/model/slug", Some(file.model_slug.clone())),
+            ("/year", file.year.map(|year| year.to_string())),
+        ];
+        for (pointer, directory) in expected {
+            let (Some(value), Some(directory)) = (file.content.pointer(pointer), directory) else {
+                continue;
+            };
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            if value != directory {
+                findings.push(LintFinding {
+                    file: label(&file.path),
+                    code: "directory_mismatch",
+                    message: format!(
+                        "{} is {} but the directory is {}",
+                        pointer, value, directory
+                    ),
+                });
+            }
+        }
+    }
+
+    findings.sort_by(|a, b| a.file.cmp(&b.file));
+    Ok(findings)
+}
@@ -1,10 +1,16 @@
-use anyhow::{Context, Result};
+use std::process::ExitCode;
+
 use clap::Parser;
-use ev_etl::{cli::Cli, run_pipeline, run_validation};
+use ev_etl::cli::{Cli, Command};
+use ev_etl::error::{EXIT_ERROR, exit_code};
+use ev_etl::{
+    run_diff, run_explain, run_fmt, run_lint, run_new, run_pipeline, run_schema, run_stats,
+    run_validation, run_watch,
+};
 use tracing::{Level, info};
 use tracing_subscriber::FmtSubscriber;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
     let level = if cli.verbose {
@@ -16,14 +22,41 @@ fn main() -> Result<()> {
         .with_max_level(level)
         .with_target(false)
         .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .context("Failed to set tracing subscriber")?;
-
-    info!("OpenEV Data ETL Pipeline v{}", env!("CARGO_PKG_VERSION"));
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Error: Failed to set tracing subscriber: {}", e);
+        return ExitCode::from(EXIT_ERROR);
+    }
 
-    if cli.validate_only {
-        return run_validation(&cli);
+    match run(cli.into_command()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(exit_code(&e))
+        }
     }
+}
 
-    run_pipeline(&cli)
+fn run(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Build(args) => {
+            info!("OpenEV Data ETL Pipeline v{}", env!("CARGO_PKG_VERSION"));
+            if args.validate_only {
+                run_validation(&args.dataset)
+            } else {
+                run_pipeline(&args)
+            }
+        }
+        Command::Validate(args) => {
+            info!("OpenEV Data ETL Pipeline v{}", env!("CARGO_PKG_VERSION"));
+            run_validation(&args)
+        }
+        Command::Lint(args) => run_lint(&args),
+        Command::Diff(args) => run_diff(&args),
+        Command::Explain(args) => run_explain(&args),
+        Command::Schema(args) => run_schema(&args),
+        Command::New(args) => run_new(&args),
+        Command::Fmt(args) => run_fmt(&args),
+        Command::Stats(args) => run_stats(&args),
+        Command::Watch(args) => run_watch(&args),
+    }
 }
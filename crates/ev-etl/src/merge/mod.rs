@@ -1,132 +1,256 @@
+mod provenance;
 mod strategy;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use ev_core::Vehicle;
+use rayon::prelude::*;
 use serde_json::Value;
 
+use crate::error::ValidationFailure;
 use crate::ingest::{FileType, VehicleFile};
 
-pub use strategy::{deep_merge, merge_arrays_replace, remove_null_values};
+pub use provenance::{Override, Provenance, deep_merge_traced};
+pub use strategy::{
+    Directive, deep_merge, default_merge_key, invalid_array_item, remove_null_values,
+};
 
-fn inject_unique_code(
-    value: &mut Value,
-    make_slug: &str,
-    model_slug: &str,
-    year: u16,
-    file_slug: &str,
-) {
-    if let Value::Object(map) = value {
-        let unique_code = format!("{}:{}:{}:{}", make_slug, model_slug, year, file_slug);
-        map.insert("unique_code".to_string(), Value::String(unique_code));
+/// A fully merged vehicle document, before deserialization into [`Vehicle`].
+#[derive(Debug, Clone)]
+pub struct MergedDocument {
+    /// Dataset-relative label of the leaf file, e.g. `tesla/model_3/2024/model_3.json`.
+    pub label: String,
+    /// Make and model directory of the layers.
+    pub make_slug: String,
+    pub model_slug: String,
+    /// Path of the leaf (year base or variant) file.
+    pub path: PathBuf,
+    pub content: Value,
+    /// Source file of each value; only built by [`merge_documents_traced`].
+    pub provenance: Option<Provenance>,
+}
+
+/// Leaf file and provenance of a merged vehicle, naming the file behind a finding.
+#[derive(Debug, Clone, Default)]
+pub struct VehicleOrigin {
+    /// [`MergedDocument::label`] of the vehicle's document.
+    pub label: String,
+    pub provenance: Option<Provenance>,
+}
+
+impl VehicleOrigin {
+    /// Layer file that set the value at `pointer`, or the leaf file when no single
+    /// layer did, e.g. for a missing field or without provenance.
+    #[must_use]
+    pub fn file_of(&self, pointer: &str) -> &str {
+        self.provenance
+            .as_ref()
+            .and_then(|provenance| provenance.layer_of(pointer))
+            .unwrap_or(&self.label)
     }
 }
 
+/// Vehicles and their origins by make and model directory.
+pub type OriginModelVehicles = BTreeMap<(String, String), Vec<(Vehicle, VehicleOrigin)>>;
+
 pub fn merge_all(files: &[VehicleFile]) -> Result<Vec<Vehicle>> {
-    let mut grouped: HashMap<(String, String), Vec<&VehicleFile>> = HashMap::new();
+    into_vehicles(merge_documents(files)?)
+}
+
+/// Merges each model's layers into one JSON document per year base and variant file.
+pub fn merge_documents(files: &[VehicleFile]) -> Result<Vec<MergedDocument>> {
+    merge_layers(files, false)
+}
+
+/// Like [`merge_documents`], also recording which file produced each value.
+pub fn merge_documents_traced(files: &[VehicleFile]) -> Result<Vec<MergedDocument>> {
+    merge_layers(files, true)
+}
+
+/// Groups layer files by make and model directory, keeping their order within a group.
+#[must_use]
+pub fn group_by_model(files: &[VehicleFile]) -> BTreeMap<(&str, &str), Vec<&VehicleFile>> {
+    let mut grouped: BTreeMap<(&str, &str), Vec<&VehicleFile>> = BTreeMap::new();
 
     for file in files {
-        let key = (file.make_slug.clone(), file.model_slug.clone());
+        let key = (file.make_slug.as_str(), file.model_slug.as_str());
         grouped.entry(key).or_default().push(file);
     }
 
-    let mut vehicles = Vec::new();
+    grouped
+}
+
+fn merge_layers(files: &[VehicleFile], traced: bool) -> Result<Vec<MergedDocument>> {
+    // Model groups are independent, so they merge in parallel.
+    let merged: Vec<(Vec<MergedDocument>, Vec<String>)> = group_by_model(files)
+        .into_par_iter()
+        .map(|((make_slug, model_slug), model_files)| {
+            merge_model(make_slug, model_slug, &model_files, traced)
+        })
+        .collect();
+
+    let mut documents = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for (model_documents, model_errors) in merged {
+        documents.extend(model_documents);
+        errors.extend(model_errors);
+    }
+
+    if !errors.is_empty() {
+        errors.sort();
+        let error_msg = format!(
+            "Failed to parse {} vehicle(s):\n{}",
+            errors.len(),
+            errors.join("\n")
+        );
+        return Err(ValidationFailure(error_msg).into());
+    }
+
+    documents.sort_by(|a, b| a.label.cmp(&b.label));
+
+    Ok(documents)
+}
+
+/// Merges the layers of one make/model, returning its documents and errors.
+fn merge_model(
+    make_slug: &str,
+    model_slug: &str,
+    model_files: &[&VehicleFile],
+    traced: bool,
+) -> (Vec<MergedDocument>, Vec<String>) {
+    let mut documents = Vec::new();
     let mut errors: Vec<String> = Vec::new();
 
-    for ((make_slug, model_slug), model_files) in grouped {
-        let base_file = model_files
+    for file in model_files {
+        if let Some(pointer) = invalid_array_item(&file.content) {
+            errors.push(format!(
+                "{}: array item at {} is null or a directive; remove items with $merge and \"$delete\": true",
+                file_label(file),
+                pointer
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        return (documents, errors);
+    }
+
+    let base_file = model_files
+        .iter()
+        .find(|f| f.file_type == FileType::ModelBase);
+
+    // Base file is optional for the model, but if missing, year files must be complete
+    let empty = Layer::empty(traced);
+    let base_layer = match base_file {
+        Some(file) => empty.merge(file),
+        None => empty,
+    };
+
+    let mut years: BTreeMap<u16, Vec<&VehicleFile>> = BTreeMap::new();
+    for file in model_files.iter().filter(|f| f.year.is_some()) {
+        years
+            .entry(file.year.expect("checked"))
+            .or_default()
+            .push(file);
+    }
+
+    for (year, year_files) in years {
+        let year_base = year_files
+            .iter()
+            .find(|f| f.file_type == FileType::YearBase);
+        let variants: Vec<_> = year_files
             .iter()
-            .find(|f| f.file_type == FileType::ModelBase);
-
-        // Base file is optional for the model, but if missing, year files must be complete
-        let base_content = base_file
-            .map(|f| f.content.clone())
-            .unwrap_or(Value::Object(serde_json::Map::new()));
-
-        let mut years: HashMap<u16, Vec<&VehicleFile>> = HashMap::new();
-        for file in model_files.iter().filter(|f| f.year.is_some()) {
-            years
-                .entry(file.year.expect("checked"))
-                .or_default()
-                .push(file);
+            .filter(|f| f.file_type == FileType::Variant)
+            .collect();
+
+        if let Some(year_base_file) = year_base {
+            let merged_year_base = base_layer.merge(year_base_file);
+
+            for variant_file in variants {
+                documents.push(merged_year_base.merge(variant_file).finish(variant_file));
+            }
+
+            documents.push(merged_year_base.finish(year_base_file));
+        } else {
+            // Critical Error: Year Base file missing
+            let msg = format!(
+                "Missing Year Base file for {}/{} (Year {}). Expected file named '{}.json'",
+                make_slug, model_slug, year, model_slug
+            );
+            errors.push(msg);
         }
+    }
 
-        for (year, year_files) in years {
-            let year_base = year_files
-                .iter()
-                .find(|f| f.file_type == FileType::YearBase);
-            let variants: Vec<_> = year_files
-                .iter()
-                .filter(|f| f.file_type == FileType::Variant)
-                .collect();
-
-            if let Some(year_base_file) = year_base {
-                let mut merged_year_base = deep_merge(&base_content, &year_base_file.content);
-                inject_unique_code(
-                    &mut merged_year_base,
-                    &make_slug,
-                    &model_slug,
-                    year,
-                    &year_base_file.file_slug,
-                );
+    (documents, errors)
+}
 
-                match serde_json::from_value::<Vehicle>(merged_year_base.clone()) {
-                    Ok(vehicle) => vehicles.push(vehicle),
-                    Err(e) => {
-                        let msg = format!(
-                            "{}/{}/{}/{}: {}",
-                            make_slug,
-                            model_slug,
-                            year,
-                            year_base_file
-                                .path
-                                .file_name()
-                                .unwrap_or_default()
-                                .to_string_lossy(),
-                            e
-                        );
-                        errors.push(msg);
-                    }
-                }
+/// Deserializes merged documents, collecting every serde error before failing.
+///
+/// Each vehicle's `unique_code` is set to its [`VehicleId`](ev_core::VehicleId); two
+/// documents resolving to the same code are reported as errors.
+pub fn into_vehicles(documents: Vec<MergedDocument>) -> Result<Vec<Vehicle>> {
+    let mut vehicles: Vec<Vehicle> = into_model_vehicles(documents)?
+        .into_values()
+        .flatten()
+        .collect();
+    sort_vehicles(&mut vehicles);
+    Ok(vehicles)
+}
 
-                for variant_file in variants {
-                    let mut merged_variant = deep_merge(&merged_year_base, &variant_file.content);
-                    inject_unique_code(
-                        &mut merged_variant,
-                        &make_slug,
-                        &model_slug,
-                        year,
-                        &variant_file.file_slug,
-                    );
-
-                    match serde_json::from_value::<Vehicle>(merged_variant) {
-                        Ok(vehicle) => vehicles.push(vehicle),
-                        Err(e) => {
-                            let msg = format!(
-                                "{}/{}/{}/{}: {}",
-                                make_slug,
-                                model_slug,
-                                year,
-                                variant_file
-                                    .path
-                                    .file_name()
-                                    .unwrap_or_default()
-                                    .to_string_lossy(),
-                                e
-                            );
-                            errors.push(msg);
-                        }
-                    }
+/// Like [`into_vehicles`], with the vehicles grouped by the make and model
+/// directory of their documents.
+pub fn into_model_vehicles(
+    documents: Vec<MergedDocument>,
+) -> Result<BTreeMap<(String, String), Vec<Vehicle>>> {
+    Ok(into_model_vehicles_with_origin(documents)?
+        .into_iter()
+        .map(|(model, vehicles)| {
+            let vehicles = vehicles.into_iter().map(|(vehicle, _)| vehicle).collect();
+            (model, vehicles)
+        })
+        .collect())
+}
+
+/// Like [`into_model_vehicles`], keeping the leaf label and provenance of each
+/// vehicle's document.
+pub fn into_model_vehicles_with_origin(
+    documents: Vec<MergedDocument>,
+) -> Result<OriginModelVehicles> {
+    let mut vehicles = OriginModelVehicles::new();
+    let mut errors: Vec<String> = Vec::new();
+
+    let mut labels_by_code: HashMap<String, String> = HashMap::new();
+
+    let parsed: Vec<_> = documents
+        .into_par_iter()
+        .map(|document| {
+            let model = (document.make_slug, document.model_slug);
+            let vehicle = serde_json::from_value::<Vehicle>(document.content);
+            let origin = VehicleOrigin {
+                label: document.label,
+                provenance: document.provenance,
+            };
+            (model, origin, vehicle)
+        })
+        .collect();
+
+    for (model, origin, parsed) in parsed {
+        match parsed {
+            Ok(mut vehicle) => {
+                let unique_code = vehicle.id().to_string();
+                if let Some(other) = labels_by_code.get(&unique_code) {
+                    errors.push(format!(
+                        "{}: duplicate unique_code {} (also {})",
+                        origin.label, unique_code, other
+                    ));
+                    continue;
                 }
-            } else {
-                // Critical Error: Year Base file missing
-                let msg = format!(
-                    "Missing Year Base file for {}/{} (Year {}). Expected file named '{}.json'",
-                    make_slug, model_slug, year, model_slug
-                );
-                errors.push(msg);
+                labels_by_code.insert(unique_code.clone(), origin.label.clone());
+                vehicle.unique_code = Some(unique_code);
+                vehicles.entry(model).or_default().push((vehicle, origin));
             }
+            Err(e) => errors.push(format!("{}: {}", origin.label, e)),
         }
     }
 
@@ -136,17 +260,92 @@ pub fn merge_all(files: &[VehicleFile]) -> Result<Vec<Vehicle>> {
             errors.len(),
             errors.join("\n")
         );
-        return Err(anyhow!(error_msg));
+        return Err(ValidationFailure(error_msg).into());
     }
 
-    vehicles.sort_by(|a, b| {
-        a.make
-            .slug
-            .cmp(&b.make.slug)
-            .then(a.model.slug.cmp(&b.model.slug))
-            .then(a.year.cmp(&b.year))
-            .then(a.trim.slug.cmp(&b.trim.slug))
-    });
+    for model_vehicles in vehicles.values_mut() {
+        model_vehicles.sort_by(|(a, _), (b, _)| vehicle_order(a, b));
+    }
 
     Ok(vehicles)
 }
+
+/// Sorts vehicles by make, model, year, trim and code, the order of every output.
+pub fn sort_vehicles(vehicles: &mut [Vehicle]) {
+    vehicles.sort_by(vehicle_order);
+}
+
+fn vehicle_order(a: &Vehicle, b: &Vehicle) -> std::cmp::Ordering {
+    a.make
+        .slug
+        .cmp(&b.make.slug)
+        .then(a.model.slug.cmp(&b.model.slug))
+        .then(a.year.cmp(&b.year))
+        .then(a.trim.slug.cmp(&b.trim.slug))
+        .then_with(|| a.unique_code.cmp(&b.unique_code))
+}
+
+/// A partially merged document, with its provenance when tracing.
+struct Layer {
+    content: Value,
+    provenance: Option<Provenance>,
+}
+
+impl Layer {
+    fn empty(traced: bool) -> Self {
+        Self {
+            content: Value::Object(serde_json::Map::new()),
+            provenance: traced.then(Provenance::default),
+        }
+    }
+
+    fn merge(&self, file: &VehicleFile) -> Self {
+        match self.provenance {
+            Some(ref provenance) => {
+                let mut provenance = provenance.clone();
+                let content = deep_merge_traced(
+                    &self.content,
+                    &file.content,
+                    &file_label(file),
+                    &mut provenance,
+                );
+                Self {
+                    content,
+                    provenance: Some(provenance),
+                }
+            }
+            None => Self {
+                content: deep_merge(&self.content, &file.content),
+                provenance: None,
+            },
+        }
+    }
+
+    /// Drops deleted fields and labels the document after its leaf `file`.
+    fn finish(mut self, file: &VehicleFile) -> MergedDocument {
+        remove_null_values(&mut self.content);
+        if let Some(ref mut provenance) = self.provenance {
+            provenance.prune(&self.content);
+        }
+        MergedDocument {
+            label: file_label(file),
+            make_slug: file.make_slug.clone(),
+            model_slug: file.model_slug.clone(),
+            path: file.path.clone(),
+            content: self.content,
+            provenance: self.provenance,
+        }
+    }
+}
+
+/// Dataset-relative path of a layer file, e.g. `tesla/model_3/base.json`.
+fn file_label(file: &VehicleFile) -> String {
+    let file_name = file.path.file_name().unwrap_or_default().to_string_lossy();
+    match file.year {
+        Some(year) => format!(
+            "{}/{}/{}/{}",
+            file.make_slug, file.model_slug, year, file_name
+        ),
+        None => format!("{}/{}/{}", file.make_slug, file.model_slug, file_name),
+    }
+}
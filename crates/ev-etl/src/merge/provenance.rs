@@ -0,0 +1,273 @@
+//! Tracks which layer file set each value of a merged document.
+//!
+//! [`deep_merge_traced`] merges like [`deep_merge`](super::deep_merge), merge
+//! directives included, and records the source of every leaf by JSON pointer.
+//! Empty objects and arrays count as leaves. When a later layer replaces or
+//! deletes a value set by an earlier one, the replaced leaves are kept as
+//! [`Override`]s; items moved by `$prepend` or `$merge` keep their source.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use ev_core::diff::pointer_token;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::strategy::{self, Directive, Slot, plan_array};
+
+/// Origin of every leaf of a merged document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Provenance {
+    /// Source file of each leaf, keyed by JSON pointer.
+    pub sources: BTreeMap<String, String>,
+    /// Leaves replaced by a later layer, in merge order.
+    pub overrides: Vec<Override>,
+}
+
+/// A leaf value set by one layer and replaced by a later one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Override {
+    pub path: String,
+    /// Replaced value.
+    pub value: Value,
+    /// File that set the replaced value.
+    pub source: String,
+    /// File whose value replaced it.
+    pub overridden_by: String,
+}
+
+impl Provenance {
+    /// Source file of the leaf at `pointer`.
+    #[must_use]
+    pub fn source(&self, pointer: &str) -> Option<&str> {
+        self.sources.get(pointer).map(String::as_str)
+    }
+
+    /// Values replaced at `pointer`, oldest first.
+    pub fn overrides_of<'a>(&'a self, pointer: &'a str) -> impl Iterator<Item = &'a Override> {
+        self.overrides
+            .iter()
+            .filter(move |replaced| replaced.path == pointer)
+    }
+
+    /// File that set the value at `pointer`: the source of a leaf, or of an
+    /// object or array whose leaves all come from one file.
+    #[must_use]
+    pub fn layer_of<'a>(&'a self, pointer: &str) -> Option<&'a str> {
+        let mut sources = self.subtree(pointer).map(|(_, source)| source);
+        let first = sources.next()?;
+        sources
+            .all(|source| source == first)
+            .then_some(first.as_str())
+    }
+
+    /// Leaves at or below `pointer`.
+    fn subtree<'a, 'p>(
+        &'a self,
+        pointer: &'p str,
+    ) -> impl Iterator<Item = (&'a String, &'a String)> + use<'a, 'p> {
+        self.sources
+            .range::<str, _>((Bound::Included(pointer), Bound::Unbounded))
+            .take_while(move |(path, _)| path.starts_with(pointer))
+            .filter(move |(path, _)| {
+                path.len() == pointer.len() || path[pointer.len()..].starts_with('/')
+            })
+    }
+
+    /// Removes and returns the leaves at or below `pointer`.
+    fn take_subtree(&mut self, pointer: &str) -> Vec<(String, String)> {
+        let paths: Vec<String> = self
+            .subtree(pointer)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        paths
+            .into_iter()
+            .filter_map(|path| self.sources.remove_entry(&path))
+            .collect()
+    }
+
+    fn record_leaves(&mut self, value: &Value, pointer: String, source: &str) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    self.record_leaves(child, format!("{pointer}/{}", pointer_token(key)), source);
+                }
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for (index, child) in items.iter().enumerate() {
+                    self.record_leaves(child, format!("{pointer}/{index}"), source);
+                }
+            }
+            _ => {
+                self.sources.insert(pointer, source.to_string());
+            }
+        }
+    }
+}
+
+/// Merges `overlay` into `base` like [`deep_merge`](super::deep_merge), recording
+/// `source` as the origin of every leaf taken from `overlay`.
+///
+/// `provenance` must describe `base`; start from [`Provenance::default`] and an
+/// empty object for the first layer. Deleted fields are recorded as `null` leaves
+/// until [`Provenance::prune`] drops them with the document's null values.
+pub fn deep_merge_traced(
+    base: &Value,
+    overlay: &Value,
+    source: &str,
+    provenance: &mut Provenance,
+) -> Value {
+    let mut merge = TracedMerge { source, provenance };
+    merge.merge_at(base, overlay, String::new(), &mut String::new())
+}
+
+impl Provenance {
+    /// Drops the sources of values no longer in `document`, such as deleted fields
+    /// once [`remove_null_values`](super::remove_null_values) ran.
+    pub fn prune(&mut self, document: &Value) {
+        self.sources
+            .retain(|path, _| document.pointer(path).is_some());
+    }
+}
+
+struct TracedMerge<'a> {
+    source: &'a str,
+    provenance: &'a mut Provenance,
+}
+
+impl TracedMerge<'_> {
+    fn merge_at(
+        &mut self,
+        base: &Value,
+        overlay: &Value,
+        pointer: String,
+        field_path: &mut String,
+    ) -> Value {
+        match (base, Directive::parse(overlay)) {
+            (_, Some(Directive::Delete)) => self.replace(base, Value::Null, pointer),
+            (_, Some(directive)) => self.merge_array(base, directive, pointer, field_path),
+            (Value::Object(base_map), None) if overlay.is_object() => {
+                let overlay_map = overlay.as_object().expect("checked");
+                // An empty object stops being a leaf once it gains keys.
+                if base_map.is_empty() && !overlay_map.is_empty() {
+                    self.provenance.take_subtree(&pointer);
+                }
+
+                let mut result = base_map.clone();
+                for (key, overlay_value) in overlay_map {
+                    let child = format!("{pointer}/{}", pointer_token(key));
+                    let len = field_path.len();
+                    field_path.push('/');
+                    field_path.push_str(key);
+                    let merged_value = match base_map.get(key) {
+                        Some(base_value) => {
+                            self.merge_at(base_value, overlay_value, child, field_path)
+                        }
+                        None => {
+                            let value = strategy::resolve(overlay_value, field_path);
+                            self.provenance.record_leaves(&value, child, self.source);
+                            value
+                        }
+                    };
+                    field_path.truncate(len);
+                    result.insert(key.clone(), merged_value);
+                }
+                Value::Object(result)
+            }
+            (_, None) => {
+                let value = strategy::resolve(overlay, field_path);
+                self.replace(base, value, pointer)
+            }
+        }
+    }
+
+    /// Replaces `base` at `pointer` with `value`, keeping the changed leaves as overrides.
+    fn replace(&mut self, base: &Value, value: Value, pointer: String) -> Value {
+        for (path, previous) in self.provenance.take_subtree(&pointer) {
+            let relative = &path[pointer.len()..];
+            if value.pointer(relative) != base.pointer(relative) {
+                self.push_override(base, &pointer, path, previous);
+            }
+        }
+        self.provenance.record_leaves(&value, pointer, self.source);
+        value
+    }
+
+    /// Applies an `$append`, `$prepend` or `$merge` directive, moving the sources
+    /// of inherited items along with them.
+    fn merge_array(
+        &mut self,
+        base: &Value,
+        directive: Directive<'_>,
+        pointer: String,
+        field_path: &mut String,
+    ) -> Value {
+        let base_items = base.as_array().map(Vec::as_slice).unwrap_or_default();
+        let mut previous = self.provenance.take_subtree(&pointer);
+
+        let mut items = Vec::new();
+        for (index, slot) in plan_array(base_items, directive, field_path)
+            .into_iter()
+            .enumerate()
+        {
+            let item_pointer = format!("{pointer}/{index}");
+            let item = match slot {
+                Slot::Base(from) => {
+                    self.move_sources(&mut previous, &format!("{pointer}/{from}"), &item_pointer);
+                    base_items[from].clone()
+                }
+                Slot::Overlay(item) => {
+                    let value = strategy::resolve(item, field_path);
+                    self.provenance
+                        .record_leaves(&value, item_pointer, self.source);
+                    value
+                }
+                Slot::Merged(from, item) => {
+                    self.move_sources(&mut previous, &format!("{pointer}/{from}"), &item_pointer);
+                    self.merge_at(&base_items[from], item, item_pointer, field_path)
+                }
+            };
+            items.push(item);
+        }
+
+        // Left over: deleted items, and whatever stood here if it was not an array.
+        for (path, source) in previous {
+            let is_array_leaf = path == pointer && base.is_array();
+            if !is_array_leaf {
+                self.push_override(base, &pointer, path, source);
+            }
+        }
+
+        let value = Value::Array(items);
+        if value.as_array().is_some_and(Vec::is_empty) {
+            self.provenance.record_leaves(&value, pointer, self.source);
+        }
+        value
+    }
+
+    /// Moves the sources recorded at or below `from` in `previous` to `to`.
+    fn move_sources(&mut self, previous: &mut Vec<(String, String)>, from: &str, to: &str) {
+        previous.retain(|(path, source)| match path.strip_prefix(from) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                self.provenance
+                    .sources
+                    .insert(format!("{to}{rest}"), source.clone());
+                false
+            }
+            _ => true,
+        });
+    }
+
+    fn push_override(&mut self, base: &Value, pointer: &str, path: String, source: String) {
+        let Some(value) = base.pointer(&path[pointer.len()..]) else {
+            return;
+        };
+        self.provenance.overrides.push(Override {
+            path,
+            value: value.clone(),
+            source,
+            overridden_by: self.source.to_string(),
+        });
+    }
+}
@@ -1,31 +1,301 @@
-use serde_json::Value;
+//! Deep merge of layer files, with merge directives.
+//!
+//! Objects merge key by key and any other value replaces the inherited one,
+//! arrays included. A layer can ask for something else by putting a directive
+//! in place of the value:
+//!
+//! - `null` or `{"$delete": true}` removes the inherited field;
+//! - `{"$append": [...]}` and `{"$prepend": [...]}` add items to the inherited array
+//!   (both may be given at once);
+//! - `{"$merge": [...], "$key": "cycle"}` deep-merges each item into the inherited
+//!   item with the same `$key` value and appends the others. An item holding
+//!   `"$delete": true` removes its match instead. Without `$key`, the default key of
+//!   the field is used (see [`default_merge_key`]), or else items merge by position.
+//!
+//! Deleted fields are kept as `null` while merging, so a later layer can set them
+//! again, and are dropped from the final document with [`remove_null_values`].
+//!
+//! Array items are values, not fields: a `null` or directive item in a plain array,
+//! `$append` or `$prepend` is rejected with the file that holds it (see
+//! [`invalid_array_item`]). Inside `$merge`, only `{"$delete": true}` is allowed.
 
+use ev_core::diff::pointer_token;
+use serde_json::{Map, Value};
+
+/// Item key used by `$merge` when the directive has no `$key`.
+const DEFAULT_MERGE_KEYS: &[(&str, &str)] = &[
+    ("/charge_ports", "connector"),
+    ("/range/rated", "cycle"),
+    ("/sources", "url"),
+];
+
+/// A merge directive found in place of a value in a layer file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Directive<'a> {
+    /// `null` or `{"$delete": true}`.
+    Delete,
+    /// `{"$prepend": [...], "$append": [...]}`, either key optional.
+    Extend {
+        prepend: &'a [Value],
+        append: &'a [Value],
+    },
+    /// `{"$merge": [...], "$key": "..."}`.
+    MergeBy {
+        items: &'a [Value],
+        key: Option<&'a str>,
+    },
+}
+
+impl<'a> Directive<'a> {
+    /// Reads the directive in `value`, if any.
+    ///
+    /// An object is a directive when it has keys, all of them start with `$`,
+    /// and one of them is `$delete`, `$append`, `$prepend` or `$merge`.
+    #[must_use]
+    pub fn parse(value: &'a Value) -> Option<Self> {
+        let map = match value {
+            Value::Null => return Some(Self::Delete),
+            Value::Object(map) if is_directive_object(map) => map,
+            _ => return None,
+        };
+
+        if map.get("$delete") == Some(&Value::Bool(true)) {
+            return Some(Self::Delete);
+        }
+        if let Some(items) = map.get("$merge") {
+            return Some(Self::MergeBy {
+                items: items_of(items),
+                key: map.get("$key").and_then(Value::as_str),
+            });
+        }
+        if map.contains_key("$prepend") || map.contains_key("$append") {
+            return Some(Self::Extend {
+                prepend: map.get("$prepend").map(items_of).unwrap_or_default(),
+                append: map.get("$append").map(items_of).unwrap_or_default(),
+            });
+        }
+        None
+    }
+}
+
+/// Where an item of an array merged by an [`Directive::Extend`] or
+/// [`Directive::MergeBy`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Slot<'a> {
+    /// Inherited item at this index, unchanged.
+    Base(usize),
+    /// New item from the layer.
+    Overlay(&'a Value),
+    /// Inherited item at this index with the layer's item merged into it.
+    Merged(usize, &'a Value),
+}
+
+/// Key that `$merge` matches items on at `field_path` when the directive has none.
+///
+/// `field_path` is the JSON pointer of the array without indices, e.g. `/range/rated`.
+#[must_use]
+pub fn default_merge_key(field_path: &str) -> Option<&'static str> {
+    DEFAULT_MERGE_KEYS
+        .iter()
+        .find(|(path, _)| *path == field_path)
+        .map(|(_, key)| *key)
+}
+
+/// Lays out the array produced by an array directive over the `base` items.
+///
+/// Base items left out of the slots were deleted; [`Directive::Delete`] yields no slots.
+#[must_use]
+pub(crate) fn plan_array<'a>(
+    base: &[Value],
+    directive: Directive<'a>,
+    field_path: &str,
+) -> Vec<Slot<'a>> {
+    match directive {
+        Directive::Delete => Vec::new(),
+        Directive::Extend { prepend, append } => prepend
+            .iter()
+            .map(Slot::Overlay)
+            .chain((0..base.len()).map(Slot::Base))
+            .chain(append.iter().map(Slot::Overlay))
+            .collect(),
+        Directive::MergeBy { items, key } => {
+            let key = key.or_else(|| default_merge_key(field_path));
+            let mut slots: Vec<Slot<'a>> = (0..base.len()).map(Slot::Base).collect();
+
+            for (position, item) in items.iter().enumerate() {
+                let matched = match key {
+                    Some(key) => item.get(key).and_then(|wanted| {
+                        slots.iter().position(|slot| match *slot {
+                            Slot::Base(index) | Slot::Merged(index, _) => {
+                                base[index].get(key) == Some(wanted)
+                            }
+                            Slot::Overlay(_) => false,
+                        })
+                    }),
+                    None => slots.iter().position(|slot| {
+                        matches!(*slot, Slot::Base(index) | Slot::Merged(index, _) if index == position)
+                    }),
+                };
+
+                let is_delete = item.get("$delete").is_some_and(|v| v == &Value::Bool(true));
+                match (matched, is_delete) {
+                    (Some(slot), true) => {
+                        slots.remove(slot);
+                    }
+                    (None, true) => {}
+                    (Some(slot), false) => {
+                        if let Slot::Base(index) | Slot::Merged(index, _) = slots[slot] {
+                            slots[slot] = Slot::Merged(index, item);
+                        }
+                    }
+                    (None, false) => slots.push(Slot::Overlay(item)),
+                }
+            }
+
+            slots
+        }
+    }
+}
+
+/// Merges `overlay` into `base`, applying any merge directives in `overlay`.
 pub fn deep_merge(base: &Value, overlay: &Value) -> Value {
-    match (base, overlay) {
-        (Value::Object(base_map), Value::Object(overlay_map)) => {
-            let mut result = base_map.clone();
+    merge_at(base, overlay, &mut String::new())
+}
+
+/// [`deep_merge`] of the value at `field_path`, the JSON pointer of the field
+/// without array indices.
+pub(crate) fn merge_at(base: &Value, overlay: &Value, field_path: &mut String) -> Value {
+    if let Some(directive) = Directive::parse(overlay) {
+        return apply_directive(base, directive, field_path);
+    }
+
+    match overlay {
+        Value::Object(overlay_map) => {
+            let mut result = base.as_object().cloned().unwrap_or_default();
 
             for (key, overlay_value) in overlay_map {
-                let merged_value = if let Some(base_value) = base_map.get(key) {
-                    deep_merge(base_value, overlay_value)
-                } else {
-                    overlay_value.clone()
-                };
+                let len = field_path.len();
+                field_path.push('/');
+                field_path.push_str(key);
+                let merged_value = merge_at(
+                    result.get(key).unwrap_or(&Value::Null),
+                    overlay_value,
+                    field_path,
+                );
+                field_path.truncate(len);
                 result.insert(key.clone(), merged_value);
             }
 
             Value::Object(result)
         }
-        (_, overlay) => overlay.clone(),
+        Value::Array(items) => {
+            let resolved: Vec<Value> = items.iter().map(|item| resolve(item, field_path)).collect();
+            Value::Array(resolved)
+        }
+        overlay => overlay.clone(),
+    }
+}
+
+/// Returns `value` with its directives applied to nothing, as for a field the
+/// layers below do not have.
+pub(crate) fn resolve(value: &Value, field_path: &mut String) -> Value {
+    merge_at(&Value::Null, value, field_path)
+}
+
+fn apply_directive(base: &Value, directive: Directive<'_>, field_path: &mut String) -> Value {
+    if directive == Directive::Delete {
+        return Value::Null;
+    }
+
+    let base_items = base.as_array().map(Vec::as_slice).unwrap_or_default();
+    let slots = plan_array(base_items, directive, field_path);
+    Value::Array(
+        slots
+            .into_iter()
+            .map(|slot| match slot {
+                Slot::Base(index) => base_items[index].clone(),
+                Slot::Overlay(item) => resolve(item, field_path),
+                Slot::Merged(index, item) => merge_at(&base_items[index], item, field_path),
+            })
+            .collect(),
+    )
+}
+
+/// JSON pointer of the first array item in a layer's `value` that is `null` or a
+/// directive, which [`deep_merge`] would otherwise leave as a `null` item.
+#[must_use]
+pub fn invalid_array_item(value: &Value) -> Option<String> {
+    find_invalid_item(value, &mut String::new())
+}
+
+fn find_invalid_item(value: &Value, pointer: &mut String) -> Option<String> {
+    let plain = |item: &Value| Directive::parse(item).is_none();
+    match (value, Directive::parse(value)) {
+        (_, Some(Directive::Delete)) => None,
+        (_, Some(Directive::Extend { prepend, append })) => {
+            within(pointer, "$prepend", |pointer| {
+                check_items(prepend, pointer, plain)
+            })
+            .or_else(|| {
+                within(pointer, "$append", |pointer| {
+                    check_items(append, pointer, plain)
+                })
+            })
+        }
+        (_, Some(Directive::MergeBy { items, .. })) => within(pointer, "$merge", |pointer| {
+            check_items(items, pointer, |item| match Directive::parse(item) {
+                None => true,
+                Some(directive) => !item.is_null() && directive == Directive::Delete,
+            })
+        }),
+        (Value::Object(map), None) => map.iter().find_map(|(key, child)| {
+            within(pointer, &pointer_token(key), |pointer| {
+                find_invalid_item(child, pointer)
+            })
+        }),
+        (Value::Array(items), None) => check_items(items, pointer, plain),
+        _ => None,
     }
 }
 
-#[allow(dead_code)]
-pub fn merge_arrays_replace(_base: &[Value], overlay: &[Value]) -> Vec<Value> {
-    overlay.to_vec()
+/// Checks the array `items` at `pointer`, each of which must be `allowed`.
+fn check_items(
+    items: &[Value],
+    pointer: &mut String,
+    allowed: impl Fn(&Value) -> bool,
+) -> Option<String> {
+    items.iter().enumerate().find_map(|(index, item)| {
+        within(pointer, &index.to_string(), |pointer| {
+            if allowed(item) {
+                find_invalid_item(item, pointer)
+            } else {
+                Some(pointer.clone())
+            }
+        })
+    })
+}
+
+/// Runs `f` with `token` appended to `pointer`.
+fn within<T>(pointer: &mut String, token: &str, f: impl FnOnce(&mut String) -> T) -> T {
+    let len = pointer.len();
+    pointer.push('/');
+    pointer.push_str(token);
+    let result = f(pointer);
+    pointer.truncate(len);
+    result
+}
+
+fn is_directive_object(map: &Map<String, Value>) -> bool {
+    !map.is_empty() && map.keys().all(|key| key.starts_with('$'))
+}
+
+fn items_of(value: &Value) -> &[Value] {
+    value
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(std::slice::from_ref(value))
 }
 
-#[allow(dead_code)]
 pub fn remove_null_values(value: &mut Value) {
     match value {
         Value::Object(map) => {
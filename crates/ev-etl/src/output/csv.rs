@@ -3,48 +3,76 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use ev_core::Vehicle;
 
-pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
-    let mut writer = csv::Writer::from_path(output_path)
-        .with_context(|| format!("Failed to create CSV file at {:?}", output_path))?;
-
-    writer.write_record([
-        "unique_code",
-        "make_slug",
-        "make_name",
-        "model_slug",
-        "model_name",
-        "year",
-        "trim_slug",
-        "trim_name",
-        "variant_slug",
-        "variant_name",
-        "vehicle_type",
-        "drivetrain",
-        "system_power_kw",
-        "system_torque_nm",
-        "battery_capacity_gross_kwh",
-        "battery_capacity_net_kwh",
-        "battery_chemistry",
-        "dc_max_power_kw",
-        "ac_max_power_kw",
-        "range_wltp_km",
-        "range_epa_km",
-        "acceleration_0_100_s",
-        "top_speed_kmh",
-        "charge_connectors",
-        "sources",
-    ])?;
-
-    for vehicle in vehicles {
+use super::{OutputFile, OutputOptions, OutputWriter, VehicleSink, write_all};
+
+/// `vehicles.csv`, one flattened row per vehicle.
+pub struct CsvWriter;
+
+impl OutputWriter for CsvWriter {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn default_file_name(&self) -> &'static str {
+        "vehicles.csv"
+    }
+
+    fn begin(
+        &self,
+        options: &OutputOptions,
+        _vehicle_count: usize,
+    ) -> Result<Box<dyn VehicleSink>> {
+        let file = OutputFile::create(options)
+            .with_context(|| format!("Failed to create CSV file at {:?}", options.path))?;
+        let mut writer = csv::Writer::from_writer(file);
+
+        writer.write_record([
+            "unique_code",
+            "make_slug",
+            "make_name",
+            "model_slug",
+            "model_name",
+            "year",
+            "trim_slug",
+            "trim_name",
+            "variant_slug",
+            "variant_name",
+            "vehicle_type",
+            "drivetrain",
+            "system_power_kw",
+            "system_torque_nm",
+            "battery_capacity_gross_kwh",
+            "battery_capacity_net_kwh",
+            "battery_chemistry",
+            "dc_max_power_kw",
+            "ac_max_power_kw",
+            "range_wltp_km",
+            "range_epa_km",
+            "acceleration_0_100_s",
+            "top_speed_kmh",
+            "charge_connectors",
+            "sources",
+        ])?;
+
+        Ok(Box::new(CsvSink { writer }))
+    }
+}
+
+struct CsvSink {
+    writer: csv::Writer<OutputFile>,
+}
+
+impl VehicleSink for CsvSink {
+    fn write(&mut self, vehicle: &Vehicle) -> Result<()> {
         let unique_code = vehicle
             .unique_code
             .clone()
-            .expect("unique_code must be set by ETL merge");
+            .unwrap_or_else(|| vehicle.id().to_string());
 
         let connectors: Vec<String> = vehicle
             .charge_ports
             .iter()
-            .map(|p| format!("{:?}", p.connector))
+            .map(|p| p.connector.as_str().to_string())
             .collect();
 
         let sources: Vec<String> = vehicle.sources.iter().map(|s| s.url.clone()).collect();
@@ -55,7 +83,7 @@ pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
             .and_then(|p| p.acceleration_0_100_kmh_s);
         let top_speed = vehicle.performance.as_ref().and_then(|p| p.top_speed_kmh);
 
-        writer.write_record([
+        self.writer.write_record([
             unique_code,
             vehicle.make.slug.clone(),
             vehicle.make.name.clone(),
@@ -74,8 +102,8 @@ pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
                 .as_ref()
                 .map(|v| v.name.clone())
                 .unwrap_or_default(),
-            format!("{:?}", vehicle.vehicle_type),
-            format!("{:?}", vehicle.powertrain.drivetrain),
+            vehicle.vehicle_type.as_str().to_string(),
+            vehicle.powertrain.drivetrain.as_str().to_string(),
             vehicle
                 .powertrain
                 .system_power_kw
@@ -124,9 +152,20 @@ pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
             connectors.join("|"),
             sources.join("|"),
         ])?;
+
+        Ok(())
     }
 
-    writer.flush()?;
+    fn finish(self: Box<Self>) -> Result<()> {
+        let file = self
+            .writer
+            .into_inner()
+            .map_err(|e| e.into_error())
+            .context("Failed to write CSV file")?;
+        file.finish()
+    }
+}
 
-    Ok(())
+pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
+    write_all(&CsvWriter, vehicles, &OutputOptions::new(output_path))
 }
@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use chrono::Utc;
@@ -6,46 +7,102 @@ use ev_core::Vehicle;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 
-#[derive(Serialize)]
-pub struct CanonicalOutput {
-    pub schema_version: String,
-    pub generated_at: String,
-    pub vehicle_count: usize,
-    pub vehicles: Vec<Vehicle>,
-    pub metadata: OutputMetadata,
-}
+use super::{OutputFile, OutputOptions, OutputWriter, VehicleSink, write_all};
 
+/// Trailing `metadata` member of the canonical output, which holds
+/// `schema_version`, `generated_at`, `vehicle_count` and `vehicles` before it.
 #[derive(Serialize)]
 pub struct OutputMetadata {
     pub etl_version: String,
     pub processing_time_ms: u64,
 }
 
-pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
-    let start = std::time::Instant::now();
-
-    let output = CanonicalOutput {
-        schema_version: "1.0.0".to_string(),
-        generated_at: Utc::now().to_rfc3339(),
-        vehicle_count: vehicles.len(),
-        vehicles: vehicles.to_vec(),
-        metadata: OutputMetadata {
-            etl_version: env!("CARGO_PKG_VERSION").to_string(),
-            processing_time_ms: start.elapsed().as_millis() as u64,
-        },
-    };
+/// `vehicles.json`, the canonical vehicle array, with a `.sha256` checksum file
+/// next to it.
+pub struct JsonWriter;
+
+impl OutputWriter for JsonWriter {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn default_file_name(&self) -> &'static str {
+        "vehicles.json"
+    }
+
+    fn begin(&self, options: &OutputOptions, vehicle_count: usize) -> Result<Box<dyn VehicleSink>> {
+        let start = std::time::Instant::now();
+        let mut file = OutputFile::create(options)?;
+
+        writeln!(file, "{{")?;
+        writeln!(file, "  \"schema_version\": \"1.0.0\",")?;
+        writeln!(
+            file,
+            "  \"generated_at\": {},",
+            serde_json::to_string(&Utc::now().to_rfc3339())?
+        )?;
+        writeln!(file, "  \"vehicle_count\": {},", vehicle_count)?;
+        write!(file, "  \"vehicles\": [")?;
+
+        Ok(Box::new(JsonSink {
+            file,
+            start,
+            written: 0,
+        }))
+    }
+}
 
-    let json =
-        serde_json::to_string_pretty(&output).context("Failed to serialize vehicles to JSON")?;
+struct JsonSink {
+    file: OutputFile,
+    start: std::time::Instant,
+    written: usize,
+}
+
+impl VehicleSink for JsonSink {
+    fn write(&mut self, vehicle: &Vehicle) -> Result<()> {
+        let json = serde_json::to_string_pretty(vehicle)
+            .context("Failed to serialize vehicles to JSON")?;
+        let separator = if self.written == 0 { "" } else { "," };
+        write!(
+            self.file,
+            "{}\n    {}",
+            separator,
+            json.replace('\n', "\n    ")
+        )?;
+        self.written += 1;
+        Ok(())
+    }
 
-    std::fs::write(output_path, &json)
-        .with_context(|| format!("Failed to write JSON to {:?}", output_path))?;
+    fn finish(self: Box<Self>) -> Result<()> {
+        let mut file = self.file;
+        if self.written > 0 {
+            write!(file, "\n  ")?;
+        }
+        let metadata = OutputMetadata {
+            etl_version: env!("CARGO_PKG_VERSION").to_string(),
+            processing_time_ms: self.start.elapsed().as_millis() as u64,
+        };
+        write!(
+            file,
+            "],\n  \"metadata\": {}\n}}",
+            serde_json::to_string_pretty(&metadata)?.replace('\n', "\n  ")
+        )?;
+
+        let output_path = file.path().to_path_buf();
+        file.finish()?;
+        write_checksum(&output_path)
+    }
+}
 
+/// Writes `<file name>.sha256` next to `output_path`, in `sha256sum` format.
+fn write_checksum(output_path: &Path) -> Result<()> {
     let mut hasher = Sha256::new();
-    hasher.update(json.as_bytes());
+    let mut file = std::fs::File::open(output_path)
+        .with_context(|| format!("Failed to read {:?}", output_path))?;
+    std::io::copy(&mut file, &mut hasher)?;
     let hash = hex::encode(hasher.finalize());
 
-    let checksum_path = output_path.with_extension("json.sha256");
+    let checksum_path = checksum_path(output_path);
     std::fs::write(
         &checksum_path,
         format!(
@@ -58,3 +115,16 @@ pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+/// `vehicles.json.sha256` for `vehicles.json`, `vehicles.json.gz.sha256` for
+/// `vehicles.json.gz`, and `<name>.json.sha256` for a file without extension.
+pub fn checksum_path(output_path: &Path) -> PathBuf {
+    let extension = output_path
+        .extension()
+        .map_or("json".into(), |ext| ext.to_string_lossy());
+    output_path.with_extension(format!("{}.sha256", extension))
+}
+
+pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
+    write_all(&JsonWriter, vehicles, &OutputOptions::new(output_path))
+}
@@ -1,6 +1,211 @@
+//! Output artifacts of the pipeline.
+//!
+//! Every format is an [`OutputWriter`] looked up by name in an
+//! [`OutputRegistry`]. `ev-etl build` uses [`OutputRegistry::default`], which
+//! holds the built-in formats; other crates can register their own writers and
+//! run the pipeline with [`crate::run_pipeline_with`].
+
 pub mod csv;
 pub mod json;
 pub mod postgresql;
+mod relational;
 pub mod sqlite;
 pub mod statistics;
 pub mod xml;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use ev_core::Vehicle;
+use flate2::write::GzEncoder;
+
+/// Writes the vehicles of the pipeline in one artifact format.
+pub trait OutputWriter: Send + Sync {
+    /// Name of the format in `--formats`, e.g. `json`.
+    fn name(&self) -> &'static str;
+
+    /// File name of the artifact in the output directory, e.g. `vehicles.json`.
+    fn default_file_name(&self) -> &'static str;
+
+    /// Whether the artifact can be compressed; true for writers that write
+    /// through an [`OutputFile`].
+    fn supports_compression(&self) -> bool {
+        true
+    }
+
+    /// Starts an artifact of `vehicle_count` vehicles as described by `options`.
+    fn begin(&self, options: &OutputOptions, vehicle_count: usize) -> Result<Box<dyn VehicleSink>>;
+}
+
+/// An artifact being written, one vehicle at a time.
+pub trait VehicleSink {
+    fn write(&mut self, vehicle: &Vehicle) -> Result<()>;
+
+    /// Completes the artifact; one dropped before this may be left incomplete.
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// Where and how a writer writes its artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputOptions {
+    pub path: PathBuf,
+    pub compression: Compression,
+}
+
+impl OutputOptions {
+    /// Uncompressed output to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            compression: Compression::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl Compression {
+    /// Suffix added to the default file name, e.g. `.gz`.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+        }
+    }
+}
+
+/// Writes `vehicles` as one artifact.
+pub fn write_all(
+    writer: &dyn OutputWriter,
+    vehicles: &[Vehicle],
+    options: &OutputOptions,
+) -> Result<()> {
+    let mut sink = writer.begin(options, vehicles.len())?;
+    for vehicle in vehicles {
+        sink.write(vehicle)?;
+    }
+    sink.finish()
+}
+
+/// A buffered artifact file, compressed as its options ask.
+pub struct OutputFile {
+    inner: Encoder,
+    path: PathBuf,
+}
+
+enum Encoder {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl OutputFile {
+    pub fn create(options: &OutputOptions) -> Result<Self> {
+        let file = File::create(&options.path)
+            .with_context(|| format!("Failed to create {:?}", options.path))?;
+        let file = BufWriter::new(file);
+        let inner = match options.compression {
+            Compression::None => Encoder::Plain(file),
+            Compression::Gzip => {
+                Encoder::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+            }
+        };
+        Ok(Self {
+            inner,
+            path: options.path.clone(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes out buffered data and, for gzip, the stream trailer.
+    pub fn finish(self) -> Result<()> {
+        let file = match self.inner {
+            Encoder::Plain(file) => file,
+            Encoder::Gzip(encoder) => encoder.finish()?,
+        };
+        file.into_inner()
+            .map_err(std::io::IntoInnerError::into_error)
+            .with_context(|| format!("Failed to write {:?}", self.path))?;
+        Ok(())
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            Encoder::Plain(file) => file.write(buf),
+            Encoder::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.inner {
+            Encoder::Plain(file) => file.flush(),
+            Encoder::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Output formats by name.
+#[derive(Clone)]
+pub struct OutputRegistry {
+    writers: BTreeMap<&'static str, Arc<dyn OutputWriter>>,
+}
+
+impl OutputRegistry {
+    /// A registry without any format.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            writers: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `writer`, returning the writer it replaces, if one had the same name.
+    pub fn register(
+        &mut self,
+        writer: impl OutputWriter + 'static,
+    ) -> Option<Arc<dyn OutputWriter>> {
+        self.writers.insert(writer.name(), Arc::new(writer))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn OutputWriter>> {
+        self.writers.get(name)
+    }
+
+    /// Names of the registered formats, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.writers.keys().copied()
+    }
+}
+
+/// The built-in formats: json, sqlite, postgresql, csv and xml.
+impl Default for OutputRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(json::JsonWriter);
+        registry.register(sqlite::SqliteWriter);
+        registry.register(postgresql::PostgresqlWriter);
+        registry.register(csv::CsvWriter);
+        registry.register(xml::XmlWriter);
+        registry
+    }
+}
+
+impl std::fmt::Debug for OutputRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.names()).finish()
+    }
+}
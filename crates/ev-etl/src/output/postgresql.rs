@@ -4,19 +4,63 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use ev_core::Vehicle;
 
-pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
-    let mut file = std::fs::File::create(output_path)
-        .with_context(|| format!("Failed to create SQL file at {:?}", output_path))?;
+use super::relational::{charging_time_rows, v2x_rows};
+use super::{OutputFile, OutputOptions, OutputWriter, VehicleSink, write_all};
 
-    write_schema(&mut file)?;
-    write_data(&mut file, vehicles)?;
-    write_indexes(&mut file)?;
-    write_views(&mut file)?;
+/// `vehicles.sql`, a PostgreSQL script with the schema, one `INSERT` per row,
+/// the indexes and the summary views.
+pub struct PostgresqlWriter;
 
-    Ok(())
+impl OutputWriter for PostgresqlWriter {
+    fn name(&self) -> &'static str {
+        "postgresql"
+    }
+
+    fn default_file_name(&self) -> &'static str {
+        "vehicles.sql"
+    }
+
+    fn begin(
+        &self,
+        options: &OutputOptions,
+        _vehicle_count: usize,
+    ) -> Result<Box<dyn VehicleSink>> {
+        let mut file = OutputFile::create(options)
+            .with_context(|| format!("Failed to create SQL file at {:?}", options.path))?;
+
+        write_schema(&mut file)?;
+        writeln!(file)?;
+        writeln!(file, "-- Vehicle Data")?;
+
+        Ok(Box::new(PostgresqlSink { file }))
+    }
+}
+
+struct PostgresqlSink {
+    file: OutputFile,
 }
 
-fn write_schema(file: &mut std::fs::File) -> Result<()> {
+impl VehicleSink for PostgresqlSink {
+    fn write(&mut self, vehicle: &Vehicle) -> Result<()> {
+        write_vehicle(&mut self.file, vehicle)
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        write_indexes(&mut self.file)?;
+        write_views(&mut self.file)?;
+        self.file.finish()
+    }
+}
+
+pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
+    write_all(
+        &PostgresqlWriter,
+        vehicles,
+        &OutputOptions::new(output_path),
+    )
+}
+
+fn write_schema(file: &mut OutputFile) -> Result<()> {
     writeln!(file, "-- OpenEV Data PostgreSQL Schema")?;
     writeln!(
         file,
@@ -37,7 +81,7 @@ CREATE TABLE IF NOT EXISTS vehicles (
     make_name VARCHAR(255) NOT NULL,
     model_slug VARCHAR(100) NOT NULL,
     model_name VARCHAR(255) NOT NULL,
-    year INTEGER NOT NULL,
+    year INTEGER NOT NULL CHECK (year >= 1900 AND year <= 2100),
     trim_slug VARCHAR(100) NOT NULL,
     trim_name VARCHAR(255) NOT NULL,
     variant_slug VARCHAR(100),
@@ -55,7 +99,13 @@ CREATE TABLE IF NOT EXISTS vehicles (
     range_epa_km DECIMAL(10,2),
     acceleration_0_100_s DECIMAL(5,2),
     top_speed_kmh DECIMAL(10,2),
+    heat_pump BOOLEAN,
+    v2l_supported BOOLEAN,
+    v2h_supported BOOLEAN,
+    v2g_supported BOOLEAN,
+    availability_status VARCHAR(50),
     json_data JSONB NOT NULL,
+    search_vector tsvector,
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -68,6 +118,37 @@ CREATE TABLE IF NOT EXISTS charge_ports (
     location_position VARCHAR(50)
 );
 
+CREATE TABLE IF NOT EXISTS motors (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    position VARCHAR(50) NOT NULL,
+    motor_type VARCHAR(100),
+    power_kw DECIMAL(10,2),
+    torque_nm DECIMAL(10,2),
+    cooling VARCHAR(100)
+);
+
+CREATE TABLE IF NOT EXISTS charge_curve_points (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    curve_type VARCHAR(50) NOT NULL,
+    soc_percent DECIMAL(5,2) NOT NULL,
+    power_kw DECIMAL(10,2),
+    current_a DECIMAL(10,2),
+    voltage_v DECIMAL(10,2)
+);
+
+CREATE TABLE IF NOT EXISTS charging_times (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    current VARCHAR(2) NOT NULL,
+    power_kw DECIMAL(10,2) NOT NULL,
+    from_soc_percent DECIMAL(5,2) NOT NULL,
+    to_soc_percent DECIMAL(5,2) NOT NULL,
+    time_min DECIMAL(10,2) NOT NULL,
+    notes TEXT
+);
+
 CREATE TABLE IF NOT EXISTS range_ratings (
     id SERIAL PRIMARY KEY,
     vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
@@ -76,6 +157,61 @@ CREATE TABLE IF NOT EXISTS range_ratings (
     notes TEXT
 );
 
+CREATE TABLE IF NOT EXISTS real_world_ranges (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    profile VARCHAR(50) NOT NULL,
+    range_km DECIMAL(10,2) NOT NULL,
+    weather VARCHAR(100),
+    speed_kmh DECIMAL(10,2),
+    notes TEXT
+);
+
+CREATE TABLE IF NOT EXISTS v2x_capabilities (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    mode VARCHAR(3) NOT NULL,
+    supported BOOLEAN NOT NULL,
+    max_power_kw DECIMAL(10,2),
+    connector VARCHAR(100),
+    protocols TEXT,
+    notes TEXT
+);
+
+CREATE TABLE IF NOT EXISTS dimensions (
+    vehicle_id INTEGER PRIMARY KEY REFERENCES vehicles(id) ON DELETE CASCADE,
+    length_mm DECIMAL(10,2),
+    width_mm DECIMAL(10,2),
+    width_with_mirrors_mm DECIMAL(10,2),
+    height_mm DECIMAL(10,2),
+    wheelbase_mm DECIMAL(10,2),
+    ground_clearance_mm DECIMAL(10,2),
+    turning_circle_m DECIMAL(10,2)
+);
+
+CREATE TABLE IF NOT EXISTS weights (
+    vehicle_id INTEGER PRIMARY KEY REFERENCES vehicles(id) ON DELETE CASCADE,
+    curb_weight_kg DECIMAL(10,2),
+    gross_vehicle_weight_kg DECIMAL(10,2),
+    max_payload_kg DECIMAL(10,2),
+    roof_load_kg DECIMAL(10,2)
+);
+
+CREATE TABLE IF NOT EXISTS markets (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    country CHAR(2) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS prices (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    currency CHAR(3) NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    country CHAR(2),
+    year INTEGER
+);
+
 CREATE TABLE IF NOT EXISTS sources (
     id SERIAL PRIMARY KEY,
     vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
@@ -85,158 +221,343 @@ CREATE TABLE IF NOT EXISTS sources (
     accessed_at TIMESTAMP WITH TIME ZONE NOT NULL,
     publisher VARCHAR(255)
 );
+
+CREATE OR REPLACE FUNCTION vehicles_search_trigger() RETURNS trigger AS $$
+BEGIN
+    NEW.search_vector :=
+        setweight(to_tsvector('english', COALESCE(NEW.make_name, '')), 'A') ||
+        setweight(to_tsvector('english', COALESCE(NEW.model_name, '')), 'A') ||
+        setweight(to_tsvector('english', COALESCE(NEW.trim_name, '')), 'B');
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS vehicles_search_update ON vehicles;
+CREATE TRIGGER vehicles_search_update
+    BEFORE INSERT OR UPDATE ON vehicles
+    FOR EACH ROW EXECUTE FUNCTION vehicles_search_trigger();
 "#
     )?;
 
     Ok(())
 }
 
-fn write_data(file: &mut std::fs::File, vehicles: &[Vehicle]) -> Result<()> {
-    writeln!(file)?;
-    writeln!(file, "-- Vehicle Data")?;
-
-    for vehicle in vehicles {
-        let unique_code = vehicle
-            .unique_code
-            .clone()
-            .expect("unique_code must be set by ETL merge");
-        let json_data = serde_json::to_string(vehicle)?;
-        let escaped_json = json_data.replace('\'', "''");
-
-        let acceleration = vehicle
-            .performance
+fn write_vehicle(file: &mut OutputFile, vehicle: &Vehicle) -> Result<()> {
+    let unique_code = vehicle
+        .unique_code
+        .clone()
+        .unwrap_or_else(|| vehicle.id().to_string());
+    let json_data = serde_json::to_string(vehicle)?;
+    let escaped_json = json_data.replace('\'', "''");
+
+    let acceleration = vehicle
+        .performance
+        .as_ref()
+        .and_then(|p| p.acceleration_0_100_kmh_s);
+    let top_speed = vehicle.performance.as_ref().and_then(|p| p.top_speed_kmh);
+
+    writeln!(
+        file,
+        "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_slug, trim_name, variant_slug, variant_name, vehicle_type, drivetrain, system_power_kw, system_torque_nm, battery_capacity_gross_kwh, battery_capacity_net_kwh, battery_chemistry, dc_max_power_kw, ac_max_power_kw, range_wltp_km, range_epa_km, acceleration_0_100_s, top_speed_kmh, heat_pump, v2l_supported, v2h_supported, v2g_supported, availability_status, json_data) VALUES ('{}', '{}', '{}', '{}', '{}', {}, '{}', '{}', {}, {}, '{}', '{}', {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, '{}');",
+        escape_sql(&unique_code),
+        escape_sql(&vehicle.make.slug),
+        escape_sql(&vehicle.make.name),
+        escape_sql(&vehicle.model.slug),
+        escape_sql(&vehicle.model.name),
+        vehicle.year,
+        escape_sql(&vehicle.trim.slug),
+        escape_sql(&vehicle.trim.name),
+        vehicle
+            .variant
+            .as_ref()
+            .map(|v| format!("'{}'", escape_sql(&v.slug)))
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .variant
+            .as_ref()
+            .map(|v| format!("'{}'", escape_sql(&v.name)))
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle.vehicle_type.as_str(),
+        vehicle.powertrain.drivetrain.as_str(),
+        vehicle
+            .powertrain
+            .system_power_kw
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .powertrain
+            .system_torque_nm
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .battery
+            .pack_capacity_kwh_gross
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .battery
+            .pack_capacity_kwh_net
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .battery
+            .chemistry
+            .as_ref()
+            .map(|v| format!("'{}'", escape_sql(v)))
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .charging
+            .dc
+            .as_ref()
+            .map(|dc| dc.max_power_kw.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .charging
+            .ac
             .as_ref()
-            .and_then(|p| p.acceleration_0_100_kmh_s);
-        let top_speed = vehicle.performance.as_ref().and_then(|p| p.top_speed_kmh);
+            .map(|ac| ac.max_power_kw.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .range
+            .wltp_range_km()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        vehicle
+            .range
+            .epa_range_km()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        acceleration
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        top_speed
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "NULL".to_string()),
+        sql_bool(vehicle.battery.heat_pump),
+        sql_bool(vehicle.v2l_supported()),
+        sql_bool(vehicle.v2h_supported()),
+        sql_bool(vehicle.v2g_supported()),
+        vehicle
+            .availability
+            .as_ref()
+            .map(|a| format!("'{}'", a.status.as_str()))
+            .unwrap_or_else(|| "NULL".to_string()),
+        escaped_json,
+    )?;
+
+    write_children(file, &unique_code, vehicle)
+}
+
+fn write_children(file: &mut OutputFile, unique_code: &str, vehicle: &Vehicle) -> Result<()> {
+    let vehicle_id = format!(
+        "(SELECT id FROM vehicles WHERE unique_code = '{}')",
+        escape_sql(unique_code)
+    );
 
+    for port in &vehicle.charge_ports {
+        let location = port.location.as_ref();
         writeln!(
             file,
-            "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_slug, trim_name, variant_slug, variant_name, vehicle_type, drivetrain, system_power_kw, system_torque_nm, battery_capacity_gross_kwh, battery_capacity_net_kwh, battery_chemistry, dc_max_power_kw, ac_max_power_kw, range_wltp_km, range_epa_km, acceleration_0_100_s, top_speed_kmh, json_data) VALUES ('{}', '{}', '{}', '{}', '{}', {}, '{}', '{}', {}, {}, '{}', '{}', {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, '{}');",
-            escape_sql(&unique_code),
-            escape_sql(&vehicle.make.slug),
-            escape_sql(&vehicle.make.name),
-            escape_sql(&vehicle.model.slug),
-            escape_sql(&vehicle.model.name),
-            vehicle.year,
-            escape_sql(&vehicle.trim.slug),
-            escape_sql(&vehicle.trim.name),
-            vehicle
-                .variant
-                .as_ref()
-                .map(|v| format!("'{}'", escape_sql(&v.slug)))
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .variant
-                .as_ref()
-                .map(|v| format!("'{}'", escape_sql(&v.name)))
-                .unwrap_or_else(|| "NULL".to_string()),
-            format!("{:?}", vehicle.vehicle_type),
-            format!("{:?}", vehicle.powertrain.drivetrain),
-            vehicle
-                .powertrain
-                .system_power_kw
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .powertrain
-                .system_torque_nm
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .battery
-                .pack_capacity_kwh_gross
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .battery
-                .pack_capacity_kwh_net
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .battery
-                .chemistry
-                .as_ref()
-                .map(|v| format!("'{}'", escape_sql(v)))
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .charging
-                .dc
-                .as_ref()
-                .map(|dc| dc.max_power_kw.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .charging
-                .ac
-                .as_ref()
-                .map(|ac| ac.max_power_kw.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .range
-                .wltp_range_km()
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            vehicle
-                .range
-                .epa_range_km()
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            acceleration
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            top_speed
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "NULL".to_string()),
-            escaped_json,
+            "INSERT INTO charge_ports (vehicle_id, kind, connector, location_side, location_position) VALUES ({}, '{}', '{}', {}, {});",
+            vehicle_id,
+            port.kind.as_str(),
+            port.connector.as_str(),
+            sql_text(location.and_then(|l| l.side.as_ref()).map(|s| s.as_str())),
+            sql_text(
+                location
+                    .and_then(|l| l.position.as_ref())
+                    .map(|p| p.as_str())
+            ),
+        )?;
+    }
+
+    for motor in vehicle.powertrain.motors.iter().flatten() {
+        writeln!(
+            file,
+            "INSERT INTO motors (vehicle_id, position, motor_type, power_kw, torque_nm, cooling) VALUES ({}, '{}', {}, {}, {}, {});",
+            vehicle_id,
+            motor.position.as_str(),
+            sql_text(motor.motor_type.as_deref()),
+            sql_number(motor.power_kw),
+            sql_number(motor.torque_nm),
+            sql_text(motor.cooling.as_deref()),
+        )?;
+    }
+
+    if let Some(ref curve) = vehicle.charging.dc_charge_curve {
+        for point in &curve.points {
+            writeln!(
+                file,
+                "INSERT INTO charge_curve_points (vehicle_id, curve_type, soc_percent, power_kw, current_a, voltage_v) VALUES ({}, '{}', {}, {}, {}, {});",
+                vehicle_id,
+                curve.curve_type.as_str(),
+                point.soc_percent,
+                sql_number(point.power_kw),
+                sql_number(point.current_a),
+                sql_number(point.voltage_v),
+            )?;
+        }
+    }
+
+    for row in charging_time_rows(vehicle) {
+        writeln!(
+            file,
+            "INSERT INTO charging_times (vehicle_id, current, power_kw, from_soc_percent, to_soc_percent, time_min, notes) VALUES ({}, '{}', {}, {}, {}, {}, {});",
+            vehicle_id,
+            row.current,
+            row.power_kw,
+            row.from_soc_percent,
+            row.to_soc_percent,
+            row.time_min,
+            sql_text(row.notes),
+        )?;
+    }
+
+    for rating in &vehicle.range.rated {
+        writeln!(
+            file,
+            "INSERT INTO range_ratings (vehicle_id, cycle, range_km, notes) VALUES ({}, '{}', {}, {});",
+            vehicle_id,
+            rating.cycle.as_str(),
+            rating.range_km,
+            sql_text(rating.notes.as_deref()),
+        )?;
+    }
+
+    for real_world in vehicle.range.real_world.iter().flatten() {
+        let conditions = real_world.conditions.as_ref();
+        writeln!(
+            file,
+            "INSERT INTO real_world_ranges (vehicle_id, profile, range_km, weather, speed_kmh, notes) VALUES ({}, '{}', {}, {}, {}, {});",
+            vehicle_id,
+            real_world.profile.as_str(),
+            real_world.range_km,
+            sql_text(conditions.and_then(|c| c.weather.as_deref())),
+            sql_number(conditions.and_then(|c| c.speed_kmh)),
+            sql_text(real_world.notes.as_deref()),
+        )?;
+    }
+
+    for row in v2x_rows(vehicle) {
+        writeln!(
+            file,
+            "INSERT INTO v2x_capabilities (vehicle_id, mode, supported, max_power_kw, connector, protocols, notes) VALUES ({}, '{}', {}, {}, {}, {}, {});",
+            vehicle_id,
+            row.mode,
+            sql_bool(Some(row.supported)),
+            sql_number(row.max_power_kw),
+            sql_text(row.connector),
+            sql_text(row.protocols.as_deref()),
+            sql_text(row.notes),
+        )?;
+    }
+
+    if let Some(ref dimensions) = vehicle.dimensions {
+        writeln!(
+            file,
+            "INSERT INTO dimensions (vehicle_id, length_mm, width_mm, width_with_mirrors_mm, height_mm, wheelbase_mm, ground_clearance_mm, turning_circle_m) VALUES ({}, {}, {}, {}, {}, {}, {}, {});",
+            vehicle_id,
+            sql_number(dimensions.length_mm),
+            sql_number(dimensions.width_mm),
+            sql_number(dimensions.width_with_mirrors_mm),
+            sql_number(dimensions.height_mm),
+            sql_number(dimensions.wheelbase_mm),
+            sql_number(dimensions.ground_clearance_mm),
+            sql_number(dimensions.turning_circle_m),
+        )?;
+    }
+
+    if let Some(ref weights) = vehicle.weights {
+        writeln!(
+            file,
+            "INSERT INTO weights (vehicle_id, curb_weight_kg, gross_vehicle_weight_kg, max_payload_kg, roof_load_kg) VALUES ({}, {}, {}, {}, {});",
+            vehicle_id,
+            sql_number(weights.curb_weight_kg),
+            sql_number(weights.gross_vehicle_weight_kg),
+            sql_number(weights.max_payload_kg),
+            sql_number(weights.roof_load_kg),
+        )?;
+    }
+
+    for country in vehicle.markets.iter().flatten() {
+        writeln!(
+            file,
+            "INSERT INTO markets (vehicle_id, country) VALUES ({}, '{}');",
+            vehicle_id,
+            escape_sql(country)
+        )?;
+    }
+
+    for msrp in vehicle.pricing.iter().flat_map(|p| p.msrp.iter().flatten()) {
+        writeln!(
+            file,
+            "INSERT INTO prices (vehicle_id, currency, amount, country, year) VALUES ({}, '{}', {}, {}, {});",
+            vehicle_id,
+            escape_sql(&msrp.currency),
+            msrp.amount,
+            sql_text(msrp.country.as_deref()),
+            sql_number(msrp.year),
+        )?;
+    }
+
+    for source in &vehicle.sources {
+        writeln!(
+            file,
+            "INSERT INTO sources (vehicle_id, source_type, title, url, accessed_at, publisher) VALUES ({}, '{}', '{}', '{}', '{}', {});",
+            vehicle_id,
+            source.source_type.as_str(),
+            escape_sql(&source.title),
+            escape_sql(&source.url),
+            escape_sql(&source.accessed_at),
+            sql_text(source.publisher.as_deref()),
         )?;
     }
 
     Ok(())
 }
 
-fn write_indexes(file: &mut std::fs::File) -> Result<()> {
+fn write_indexes(file: &mut OutputFile) -> Result<()> {
     writeln!(file)?;
     writeln!(file, "-- Indexes")?;
     writeln!(
         file,
-        "CREATE INDEX IF NOT EXISTS idx_vehicles_make ON vehicles(make_slug);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_vehicles_model ON vehicles(model_slug);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_vehicles_year ON vehicles(year);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_vehicles_composite ON vehicles(make_slug, model_slug, year, trim_slug);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_vehicles_type ON vehicles(vehicle_type);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_vehicles_json ON vehicles USING GIN (json_data);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_charge_ports_vehicle ON charge_ports(vehicle_id);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_range_ratings_vehicle ON range_ratings(vehicle_id);"
-    )?;
-    writeln!(
-        file,
-        "CREATE INDEX IF NOT EXISTS idx_sources_vehicle ON sources(vehicle_id);"
+        r#"
+CREATE INDEX IF NOT EXISTS idx_vehicles_make ON vehicles(make_slug);
+CREATE INDEX IF NOT EXISTS idx_vehicles_model ON vehicles(model_slug);
+CREATE INDEX IF NOT EXISTS idx_vehicles_year ON vehicles(year);
+CREATE INDEX IF NOT EXISTS idx_vehicles_composite ON vehicles(make_slug, model_slug, year, trim_slug);
+CREATE INDEX IF NOT EXISTS idx_vehicles_type ON vehicles(vehicle_type);
+CREATE INDEX IF NOT EXISTS idx_vehicles_unique_code ON vehicles(unique_code);
+CREATE INDEX IF NOT EXISTS idx_vehicles_drivetrain ON vehicles(drivetrain);
+CREATE INDEX IF NOT EXISTS idx_vehicles_dc_power ON vehicles(dc_max_power_kw);
+CREATE INDEX IF NOT EXISTS idx_vehicles_ac_power ON vehicles(ac_max_power_kw);
+CREATE INDEX IF NOT EXISTS idx_vehicles_battery ON vehicles(battery_capacity_net_kwh);
+CREATE INDEX IF NOT EXISTS idx_vehicles_acceleration ON vehicles(acceleration_0_100_s);
+CREATE INDEX IF NOT EXISTS idx_vehicles_availability ON vehicles(availability_status);
+CREATE INDEX IF NOT EXISTS idx_charge_ports_connector ON charge_ports(connector, vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_markets_country ON markets(country, vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_prices_currency ON prices(currency, amount);
+CREATE INDEX IF NOT EXISTS idx_vehicles_json ON vehicles USING GIN (json_data);
+CREATE INDEX IF NOT EXISTS idx_vehicles_search ON vehicles USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_vehicles_recent ON vehicles(year) WHERE year >= 2020;
+CREATE INDEX IF NOT EXISTS idx_vehicles_electric ON vehicles(battery_capacity_net_kwh) WHERE battery_capacity_net_kwh IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_charge_ports_vehicle ON charge_ports(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_motors_vehicle ON motors(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_charge_curve_points_vehicle ON charge_curve_points(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_charging_times_vehicle ON charging_times(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_range_ratings_vehicle ON range_ratings(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_real_world_ranges_vehicle ON real_world_ranges(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_v2x_capabilities_vehicle ON v2x_capabilities(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_markets_vehicle ON markets(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_prices_vehicle ON prices(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_sources_vehicle ON sources(vehicle_id);
+"#
     )?;
 
     Ok(())
 }
 
-fn write_views(file: &mut std::fs::File) -> Result<()> {
+fn write_views(file: &mut OutputFile) -> Result<()> {
     writeln!(file)?;
     writeln!(file, "-- Views")?;
     writeln!(
@@ -246,7 +567,7 @@ CREATE OR REPLACE VIEW vehicle_summary AS
 SELECT
     v.id,
     v.unique_code,
-    v.make_name || ' ' || v.model_name || ' ' || v.year AS display_name,
+    v.make_name || ' ' || v.model_name || ' ' || v.year::text AS display_name,
     v.make_slug,
     v.model_slug,
     v.year,
@@ -256,8 +577,49 @@ SELECT
     v.battery_capacity_net_kwh,
     v.dc_max_power_kw,
     v.range_wltp_km,
-    v.range_epa_km
+    v.range_epa_km,
+    v.acceleration_0_100_s,
+    v.top_speed_kmh
 FROM vehicles v;
+
+CREATE OR REPLACE VIEW make_summary AS
+SELECT
+    make_slug,
+    make_name,
+    COUNT(*) as vehicle_count,
+    MIN(year) as first_year,
+    MAX(year) as latest_year
+FROM vehicles
+GROUP BY make_slug, make_name
+ORDER BY make_name;
+
+CREATE OR REPLACE VIEW model_summary AS
+SELECT
+    make_slug,
+    make_name,
+    model_slug,
+    model_name,
+    COUNT(*) as variant_count,
+    MIN(year) as first_year,
+    MAX(year) as latest_year,
+    MAX(range_wltp_km) as max_range_km,
+    MAX(dc_max_power_kw) as max_charging_kw
+FROM vehicles
+GROUP BY make_slug, make_name, model_slug, model_name
+ORDER BY make_name, model_name;
+
+CREATE OR REPLACE FUNCTION search_vehicles(query TEXT, limit_count INTEGER DEFAULT 20)
+RETURNS SETOF vehicle_summary AS $$
+BEGIN
+    RETURN QUERY
+    SELECT vs.*
+    FROM vehicle_summary vs
+    JOIN vehicles v ON v.id = vs.id
+    WHERE v.search_vector @@ plainto_tsquery('english', query)
+    ORDER BY ts_rank(v.search_vector, plainto_tsquery('english', query)) DESC
+    LIMIT limit_count;
+END;
+$$ LANGUAGE plpgsql;
 "#
     )?;
 
@@ -267,6 +629,22 @@ FROM vehicles v;
     Ok(())
 }
 
+fn sql_bool(value: Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "TRUE",
+        Some(false) => "FALSE",
+        None => "NULL",
+    }
+}
+
+fn sql_number(value: Option<impl std::fmt::Display>) -> String {
+    value.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+}
+
+fn sql_text(value: Option<&str>) -> String {
+    value.map_or_else(|| "NULL".to_string(), |v| format!("'{}'", escape_sql(v)))
+}
+
 fn escape_sql(s: &str) -> String {
     s.replace('\'', "''")
 }
@@ -0,0 +1,90 @@
+//! Row shapes shared by the SQLite and PostgreSQL outputs for nested data that
+//! maps to a single child table.
+
+use ev_core::Vehicle;
+
+/// One `v2x_capabilities` row.
+pub(crate) struct V2xRow<'a> {
+    pub mode: &'static str,
+    pub supported: bool,
+    pub max_power_kw: Option<f64>,
+    pub connector: Option<&'a str>,
+    /// Comma-separated protocol names.
+    pub protocols: Option<String>,
+    pub notes: Option<&'a str>,
+}
+
+/// One `charging_times` row; `current` tells AC entries from DC ones.
+pub(crate) struct ChargingTimeRow<'a> {
+    pub current: &'static str,
+    pub power_kw: f64,
+    pub from_soc_percent: f64,
+    pub to_soc_percent: f64,
+    pub time_min: f64,
+    pub notes: Option<&'a str>,
+}
+
+pub(crate) fn v2x_rows(vehicle: &Vehicle) -> Vec<V2xRow<'_>> {
+    let Some(ref v2x) = vehicle.v2x else {
+        return Vec::new();
+    };
+
+    let protocols = |protocols: &Option<Vec<String>>| protocols.as_ref().map(|p| p.join(","));
+
+    let mut rows = Vec::new();
+    if let Some(ref v2l) = v2x.v2l {
+        rows.push(V2xRow {
+            mode: "v2l",
+            supported: v2l.supported,
+            max_power_kw: v2l.max_power_kw,
+            connector: None,
+            protocols: None,
+            notes: v2l.notes.as_deref(),
+        });
+    }
+    if let Some(ref v2h) = v2x.v2h {
+        rows.push(V2xRow {
+            mode: "v2h",
+            supported: v2h.supported,
+            max_power_kw: v2h.max_power_kw,
+            connector: v2h.connector.as_deref(),
+            protocols: protocols(&v2h.protocols),
+            notes: v2h.notes.as_deref(),
+        });
+    }
+    if let Some(ref v2g) = v2x.v2g {
+        rows.push(V2xRow {
+            mode: "v2g",
+            supported: v2g.supported,
+            max_power_kw: v2g.max_power_kw,
+            connector: v2g.connector.as_deref(),
+            protocols: protocols(&v2g.protocols),
+            notes: v2g.notes.as_deref(),
+        });
+    }
+    rows
+}
+
+pub(crate) fn charging_time_rows(vehicle: &Vehicle) -> Vec<ChargingTimeRow<'_>> {
+    let Some(ref times) = vehicle.charging.charging_time else {
+        return Vec::new();
+    };
+
+    let ac = times.ac.iter().flatten().map(|entry| ChargingTimeRow {
+        current: "ac",
+        power_kw: entry.power_kw,
+        from_soc_percent: entry.from_soc_percent,
+        to_soc_percent: entry.to_soc_percent,
+        time_min: entry.time_min,
+        notes: entry.notes.as_deref(),
+    });
+    let dc = times.dc.iter().flatten().map(|entry| ChargingTimeRow {
+        current: "dc",
+        power_kw: entry.charger_power_kw,
+        from_soc_percent: entry.from_soc_percent,
+        to_soc_percent: entry.to_soc_percent,
+        time_min: entry.time_min,
+        notes: entry.notes.as_deref(),
+    });
+    ac.chain(dc).collect()
+}
@@ -4,28 +4,118 @@ use anyhow::{Context, Result};
 use ev_core::Vehicle;
 use rusqlite::{Connection, params};
 
-pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
-    if output_path.exists() {
-        std::fs::remove_file(output_path)?;
+use super::relational::{charging_time_rows, v2x_rows};
+use super::{OutputOptions, OutputWriter, VehicleSink, write_all};
+
+/// `vehicles.db`, the normalized SQLite database `ev-server` reads.
+///
+/// SQLite needs a seekable file, so the database cannot be compressed.
+pub struct SqliteWriter;
+
+impl OutputWriter for SqliteWriter {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn default_file_name(&self) -> &'static str {
+        "vehicles.db"
+    }
+
+    fn supports_compression(&self) -> bool {
+        false
+    }
+
+    fn begin(
+        &self,
+        options: &OutputOptions,
+        _vehicle_count: usize,
+    ) -> Result<Box<dyn VehicleSink>> {
+        let output_path = &options.path;
+        if output_path.exists() {
+            std::fs::remove_file(output_path)?;
+        }
+
+        let conn = open(output_path)
+            .with_context(|| format!("Failed to create SQLite database at {:?}", output_path))?;
+
+        create_schema(&conn)?;
+        conn.execute_batch("BEGIN")?;
+
+        Ok(Box::new(SqliteSink { conn }))
+    }
+}
+
+struct SqliteSink {
+    conn: Connection,
+}
+
+impl VehicleSink for SqliteSink {
+    fn write(&mut self, vehicle: &Vehicle) -> Result<()> {
+        insert_vehicle(&self.conn, vehicle)
     }
 
-    let conn = Connection::open(output_path)
-        .with_context(|| format!("Failed to create SQLite database at {:?}", output_path))?;
+    fn finish(self: Box<Self>) -> Result<()> {
+        self.conn
+            .execute_batch("COMMIT")
+            .context("Failed to write SQLite database")?;
+        create_indexes(&self.conn)?;
+        optimize_database(&self.conn)?;
+
+        Ok(())
+    }
+}
 
+pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
+    write_all(&SqliteWriter, vehicles, &OutputOptions::new(output_path))
+}
+
+/// Replaces the vehicles of an existing database in one transaction, so open
+/// connections (e.g. a running ev-server) see the new data on their next query.
+///
+/// Creates the database with [`generate`] when `output_path` does not exist.
+pub fn refresh(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
+    if !output_path.exists() {
+        return generate(vehicles, output_path);
+    }
+
+    let mut conn = open(output_path)
+        .with_context(|| format!("Failed to open SQLite database at {:?}", output_path))?;
     create_schema(&conn)?;
-    insert_vehicles(&conn, vehicles)?;
+
+    let tx = conn.transaction()?;
+    let tables: Vec<String> = tx
+        .prepare(
+            r"SELECT name FROM sqlite_master
+            WHERE type = 'table' AND name NOT LIKE 'sqlite\_%' ESCAPE '\'
+                AND name NOT LIKE 'vehicles\_fts\_%' ESCAPE '\'",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for table in tables {
+        tx.execute(&format!("DELETE FROM \"{}\"", table), [])?;
+    }
+    insert_vehicles(&tx, vehicles)?;
+    tx.commit().context("Failed to refresh SQLite database")?;
+
     create_indexes(&conn)?;
-    optimize_database(&conn)?;
 
     Ok(())
 }
 
+/// Opens `path` with foreign keys enforced, so deleting a vehicle cascades to its
+/// child rows.
+fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    Ok(conn)
+}
+
 fn create_schema(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         r"
         CREATE TABLE IF NOT EXISTS vehicles (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
-            unique_code TEXT UNIQUE,
+            unique_code TEXT UNIQUE NOT NULL,
             make_slug TEXT NOT NULL,
             make_name TEXT NOT NULL,
             model_slug TEXT NOT NULL,
@@ -48,6 +138,11 @@ fn create_schema(conn: &Connection) -> Result<()> {
             range_epa_km REAL,
             acceleration_0_100_s REAL,
             top_speed_kmh REAL,
+            heat_pump INTEGER,
+            v2l_supported INTEGER,
+            v2h_supported INTEGER,
+            v2g_supported INTEGER,
+            availability_status TEXT,
             json_data TEXT NOT NULL,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
         );
@@ -59,7 +154,41 @@ fn create_schema(conn: &Connection) -> Result<()> {
             connector TEXT NOT NULL,
             location_side TEXT,
             location_position TEXT,
-            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id)
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS motors (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            position TEXT NOT NULL,
+            motor_type TEXT,
+            power_kw REAL,
+            torque_nm REAL,
+            cooling TEXT,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS charge_curve_points (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            curve_type TEXT NOT NULL,
+            soc_percent REAL NOT NULL,
+            power_kw REAL,
+            current_a REAL,
+            voltage_v REAL,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS charging_times (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            current TEXT NOT NULL,
+            power_kw REAL NOT NULL,
+            from_soc_percent REAL NOT NULL,
+            to_soc_percent REAL NOT NULL,
+            time_min REAL NOT NULL,
+            notes TEXT,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
         );
 
         CREATE TABLE IF NOT EXISTS range_ratings (
@@ -68,7 +197,68 @@ fn create_schema(conn: &Connection) -> Result<()> {
             cycle TEXT NOT NULL,
             range_km REAL NOT NULL,
             notes TEXT,
-            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id)
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS real_world_ranges (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            profile TEXT NOT NULL,
+            range_km REAL NOT NULL,
+            weather TEXT,
+            speed_kmh REAL,
+            notes TEXT,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS v2x_capabilities (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            mode TEXT NOT NULL,
+            supported INTEGER NOT NULL,
+            max_power_kw REAL,
+            connector TEXT,
+            protocols TEXT,
+            notes TEXT,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS dimensions (
+            vehicle_id INTEGER PRIMARY KEY,
+            length_mm REAL,
+            width_mm REAL,
+            width_with_mirrors_mm REAL,
+            height_mm REAL,
+            wheelbase_mm REAL,
+            ground_clearance_mm REAL,
+            turning_circle_m REAL,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS weights (
+            vehicle_id INTEGER PRIMARY KEY,
+            curb_weight_kg REAL,
+            gross_vehicle_weight_kg REAL,
+            max_payload_kg REAL,
+            roof_load_kg REAL,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS markets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            country TEXT NOT NULL,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS prices (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vehicle_id INTEGER NOT NULL,
+            currency TEXT NOT NULL,
+            amount REAL NOT NULL,
+            country TEXT,
+            year INTEGER,
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
         );
 
         CREATE TABLE IF NOT EXISTS sources (
@@ -79,7 +269,20 @@ fn create_schema(conn: &Connection) -> Result<()> {
             url TEXT NOT NULL,
             accessed_at TEXT NOT NULL,
             publisher TEXT,
-            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id)
+            FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS vehicles_fts USING fts5(
+            make_name,
+            model_name,
+            trim_name,
+            variant_name,
+            body_style,
+            platform,
+            battery_chemistry,
+            markets,
+            tokenize = 'unicode61 remove_diacritics 2',
+            prefix = '2 3'
         );
         ",
     )
@@ -89,113 +292,262 @@ fn create_schema(conn: &Connection) -> Result<()> {
 }
 
 fn insert_vehicles(conn: &Connection, vehicles: &[Vehicle]) -> Result<()> {
-    let mut stmt = conn.prepare(
+    for vehicle in vehicles {
+        insert_vehicle(conn, vehicle)?;
+    }
+
+    Ok(())
+}
+
+fn insert_vehicle(conn: &Connection, vehicle: &Vehicle) -> Result<()> {
+    let mut stmt = conn.prepare_cached(
         r"INSERT INTO vehicles (
             unique_code, make_slug, make_name, model_slug, model_name,
             year, trim_slug, trim_name, variant_slug, variant_name,
             vehicle_type, drivetrain, system_power_kw, system_torque_nm,
             battery_capacity_gross_kwh, battery_capacity_net_kwh, battery_chemistry,
             dc_max_power_kw, ac_max_power_kw, range_wltp_km, range_epa_km,
-            acceleration_0_100_s, top_speed_kmh, json_data
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+            acceleration_0_100_s, top_speed_kmh, heat_pump, v2l_supported, v2h_supported,
+            v2g_supported, availability_status, json_data
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29)",
     )?;
 
-    let mut port_stmt = conn.prepare(
-        "INSERT INTO charge_ports (vehicle_id, kind, connector, location_side, location_position) VALUES (?1, ?2, ?3, ?4, ?5)",
-    )?;
+    let json_data = serde_json::to_string(vehicle)?;
+    let unique_code = vehicle
+        .unique_code
+        .clone()
+        .unwrap_or_else(|| vehicle.id().to_string());
 
-    let mut range_stmt = conn.prepare(
-        "INSERT INTO range_ratings (vehicle_id, cycle, range_km, notes) VALUES (?1, ?2, ?3, ?4)",
-    )?;
+    let acceleration = vehicle
+        .performance
+        .as_ref()
+        .and_then(|p| p.acceleration_0_100_kmh_s);
+    let top_speed = vehicle.performance.as_ref().and_then(|p| p.top_speed_kmh);
 
-    let mut source_stmt = conn.prepare(
-        "INSERT INTO sources (vehicle_id, source_type, title, url, accessed_at, publisher) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    )?;
+    stmt.execute(params![
+        unique_code,
+        vehicle.make.slug,
+        vehicle.make.name,
+        vehicle.model.slug,
+        vehicle.model.name,
+        vehicle.year,
+        vehicle.trim.slug,
+        vehicle.trim.name,
+        vehicle.variant.as_ref().map(|v| &v.slug),
+        vehicle.variant.as_ref().map(|v| &v.name),
+        vehicle.vehicle_type.as_str(),
+        vehicle.powertrain.drivetrain.as_str(),
+        vehicle.powertrain.system_power_kw,
+        vehicle.powertrain.system_torque_nm,
+        vehicle.battery.pack_capacity_kwh_gross,
+        vehicle.battery.pack_capacity_kwh_net,
+        vehicle.battery.chemistry,
+        vehicle.charging.dc.as_ref().map(|dc| dc.max_power_kw),
+        vehicle.charging.ac.as_ref().map(|ac| ac.max_power_kw),
+        vehicle.range.wltp_range_km(),
+        vehicle.range.epa_range_km(),
+        acceleration,
+        top_speed,
+        vehicle.battery.heat_pump,
+        vehicle.v2l_supported(),
+        vehicle.v2h_supported(),
+        vehicle.v2g_supported(),
+        vehicle.availability.as_ref().map(|a| a.status.as_str()),
+        json_data,
+    ])?;
 
-    for vehicle in vehicles {
-        let json_data = serde_json::to_string(vehicle)?;
-        let unique_code = vehicle
-            .unique_code
-            .clone()
-            .expect("unique_code must be set by ETL merge");
-
-        let acceleration = vehicle
-            .performance
-            .as_ref()
-            .and_then(|p| p.acceleration_0_100_kmh_s);
-        let top_speed = vehicle.performance.as_ref().and_then(|p| p.top_speed_kmh);
-
-        stmt.execute(params![
-            unique_code,
-            vehicle.make.slug,
-            vehicle.make.name,
-            vehicle.model.slug,
-            vehicle.model.name,
-            vehicle.year,
-            vehicle.trim.slug,
-            vehicle.trim.name,
-            vehicle.variant.as_ref().map(|v| &v.slug),
-            vehicle.variant.as_ref().map(|v| &v.name),
-            format!("{:?}", vehicle.vehicle_type),
-            format!("{:?}", vehicle.powertrain.drivetrain),
-            vehicle.powertrain.system_power_kw,
-            vehicle.powertrain.system_torque_nm,
-            vehicle.battery.pack_capacity_kwh_gross,
-            vehicle.battery.pack_capacity_kwh_net,
-            vehicle.battery.chemistry,
-            vehicle.charging.dc.as_ref().map(|dc| dc.max_power_kw),
-            vehicle.charging.ac.as_ref().map(|ac| ac.max_power_kw),
-            vehicle.range.wltp_range_km(),
-            vehicle.range.epa_range_km(),
-            acceleration,
-            top_speed,
-            json_data,
+    let vehicle_id = conn.last_insert_rowid();
+    insert_children(conn, vehicle_id, vehicle)?;
+    insert_search_row(conn, vehicle_id, vehicle)
+}
+
+fn insert_children(conn: &Connection, vehicle_id: i64, vehicle: &Vehicle) -> Result<()> {
+    for port in &vehicle.charge_ports {
+        let location = port.location.as_ref();
+        conn.prepare_cached(
+            "INSERT INTO charge_ports (vehicle_id, kind, connector, location_side, location_position) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?
+        .execute(params![
+            vehicle_id,
+            port.kind.as_str(),
+            port.connector.as_str(),
+            location.and_then(|l| l.side.as_ref()).map(|s| s.as_str()),
+            location.and_then(|l| l.position.as_ref()).map(|p| p.as_str()),
         ])?;
+    }
 
-        let vehicle_id = conn.last_insert_rowid();
-
-        for port in &vehicle.charge_ports {
-            let location_side = port
-                .location
-                .as_ref()
-                .and_then(|l| l.side.as_ref())
-                .map(|s| format!("{:?}", s));
-            let location_pos = port
-                .location
-                .as_ref()
-                .and_then(|l| l.position.as_ref())
-                .map(|p| format!("{:?}", p));
-
-            port_stmt.execute(params![
-                vehicle_id,
-                format!("{:?}", port.kind),
-                format!("{:?}", port.connector),
-                location_side,
-                location_pos,
-            ])?;
-        }
+    for motor in vehicle.powertrain.motors.iter().flatten() {
+        conn.prepare_cached(
+            "INSERT INTO motors (vehicle_id, position, motor_type, power_kw, torque_nm, cooling) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?
+        .execute(params![
+            vehicle_id,
+            motor.position.as_str(),
+            motor.motor_type,
+            motor.power_kw,
+            motor.torque_nm,
+            motor.cooling,
+        ])?;
+    }
 
-        for rating in &vehicle.range.rated {
-            range_stmt.execute(params![
+    if let Some(ref curve) = vehicle.charging.dc_charge_curve {
+        for point in &curve.points {
+            conn.prepare_cached(
+                "INSERT INTO charge_curve_points (vehicle_id, curve_type, soc_percent, power_kw, current_a, voltage_v) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?
+            .execute(params![
                 vehicle_id,
-                format!("{:?}", rating.cycle),
-                rating.range_km,
-                rating.notes,
+                curve.curve_type.as_str(),
+                point.soc_percent,
+                point.power_kw,
+                point.current_a,
+                point.voltage_v,
             ])?;
         }
+    }
 
-        for source in &vehicle.sources {
-            source_stmt.execute(params![
-                vehicle_id,
-                format!("{:?}", source.source_type),
-                source.title,
-                source.url,
-                source.accessed_at,
-                source.publisher,
-            ])?;
-        }
+    for row in charging_time_rows(vehicle) {
+        conn.prepare_cached(
+            "INSERT INTO charging_times (vehicle_id, current, power_kw, from_soc_percent, to_soc_percent, time_min, notes) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?
+        .execute(params![
+            vehicle_id,
+            row.current,
+            row.power_kw,
+            row.from_soc_percent,
+            row.to_soc_percent,
+            row.time_min,
+            row.notes,
+        ])?;
+    }
+
+    for rating in &vehicle.range.rated {
+        conn.prepare_cached(
+            "INSERT INTO range_ratings (vehicle_id, cycle, range_km, notes) VALUES (?1, ?2, ?3, ?4)",
+        )?
+        .execute(params![
+            vehicle_id,
+            rating.cycle.as_str(),
+            rating.range_km,
+            rating.notes,
+        ])?;
+    }
+
+    for real_world in vehicle.range.real_world.iter().flatten() {
+        let conditions = real_world.conditions.as_ref();
+        conn.prepare_cached(
+            "INSERT INTO real_world_ranges (vehicle_id, profile, range_km, weather, speed_kmh, notes) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?
+        .execute(params![
+            vehicle_id,
+            real_world.profile.as_str(),
+            real_world.range_km,
+            conditions.and_then(|c| c.weather.as_deref()),
+            conditions.and_then(|c| c.speed_kmh),
+            real_world.notes,
+        ])?;
     }
 
+    for row in v2x_rows(vehicle) {
+        conn.prepare_cached(
+            "INSERT INTO v2x_capabilities (vehicle_id, mode, supported, max_power_kw, connector, protocols, notes) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?
+        .execute(params![
+            vehicle_id,
+            row.mode,
+            row.supported,
+            row.max_power_kw,
+            row.connector,
+            row.protocols,
+            row.notes,
+        ])?;
+    }
+
+    if let Some(ref dimensions) = vehicle.dimensions {
+        conn.prepare_cached(
+            "INSERT INTO dimensions (vehicle_id, length_mm, width_mm, width_with_mirrors_mm, height_mm, wheelbase_mm, ground_clearance_mm, turning_circle_m) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?
+        .execute(params![
+            vehicle_id,
+            dimensions.length_mm,
+            dimensions.width_mm,
+            dimensions.width_with_mirrors_mm,
+            dimensions.height_mm,
+            dimensions.wheelbase_mm,
+            dimensions.ground_clearance_mm,
+            dimensions.turning_circle_m,
+        ])?;
+    }
+
+    if let Some(ref weights) = vehicle.weights {
+        conn.prepare_cached(
+            "INSERT INTO weights (vehicle_id, curb_weight_kg, gross_vehicle_weight_kg, max_payload_kg, roof_load_kg) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?
+        .execute(params![
+            vehicle_id,
+            weights.curb_weight_kg,
+            weights.gross_vehicle_weight_kg,
+            weights.max_payload_kg,
+            weights.roof_load_kg,
+        ])?;
+    }
+
+    for country in vehicle.markets.iter().flatten() {
+        conn.prepare_cached("INSERT INTO markets (vehicle_id, country) VALUES (?1, ?2)")?
+            .execute(params![vehicle_id, country])?;
+    }
+
+    for msrp in vehicle.pricing.iter().flat_map(|p| p.msrp.iter().flatten()) {
+        conn.prepare_cached(
+            "INSERT INTO prices (vehicle_id, currency, amount, country, year) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?
+        .execute(params![
+            vehicle_id,
+            msrp.currency,
+            msrp.amount,
+            msrp.country,
+            msrp.year,
+        ])?;
+    }
+
+    for source in &vehicle.sources {
+        conn.prepare_cached(
+            "INSERT INTO sources (vehicle_id, source_type, title, url, accessed_at, publisher) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?
+        .execute(params![
+            vehicle_id,
+            source.source_type.as_str(),
+            source.title,
+            source.url,
+            source.accessed_at,
+            source.publisher,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Indexes the searchable text of a vehicle in `vehicles_fts` under the vehicle's row id.
+fn insert_search_row(conn: &Connection, vehicle_id: i64, vehicle: &Vehicle) -> Result<()> {
+    let body = vehicle.body.as_ref();
+    conn.prepare_cached(
+        r"INSERT INTO vehicles_fts (
+            rowid, make_name, model_name, trim_name, variant_name, body_style,
+            platform, battery_chemistry, markets
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?
+    .execute(params![
+        vehicle_id,
+        vehicle.make.name,
+        vehicle.model.name,
+        vehicle.trim.name,
+        vehicle.variant.as_ref().map(|v| &v.name),
+        body.and_then(|b| b.style.as_deref()),
+        body.and_then(|b| b.platform.as_deref()),
+        vehicle.battery.chemistry,
+        vehicle.markets.as_ref().map(|markets| markets.join(" ")),
+    ])?;
+
     Ok(())
 }
 
@@ -207,8 +559,25 @@ fn create_indexes(conn: &Connection) -> Result<()> {
         CREATE INDEX IF NOT EXISTS idx_vehicles_year ON vehicles(year);
         CREATE INDEX IF NOT EXISTS idx_vehicles_composite ON vehicles(make_slug, model_slug, year, trim_slug);
         CREATE INDEX IF NOT EXISTS idx_vehicles_type ON vehicles(vehicle_type);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_unique_code ON vehicles(unique_code);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_drivetrain ON vehicles(drivetrain);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_dc_power ON vehicles(dc_max_power_kw);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_ac_power ON vehicles(ac_max_power_kw);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_battery ON vehicles(battery_capacity_net_kwh);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_acceleration ON vehicles(acceleration_0_100_s);
+        CREATE INDEX IF NOT EXISTS idx_vehicles_availability ON vehicles(availability_status);
+        CREATE INDEX IF NOT EXISTS idx_charge_ports_connector ON charge_ports(connector, vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_markets_country ON markets(country, vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_prices_currency ON prices(currency, amount);
         CREATE INDEX IF NOT EXISTS idx_charge_ports_vehicle ON charge_ports(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_motors_vehicle ON motors(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_charge_curve_points_vehicle ON charge_curve_points(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_charging_times_vehicle ON charging_times(vehicle_id);
         CREATE INDEX IF NOT EXISTS idx_range_ratings_vehicle ON range_ratings(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_real_world_ranges_vehicle ON real_world_ranges(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_v2x_capabilities_vehicle ON v2x_capabilities(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_markets_vehicle ON markets(vehicle_id);
+        CREATE INDEX IF NOT EXISTS idx_prices_vehicle ON prices(vehicle_id);
         CREATE INDEX IF NOT EXISTS idx_sources_vehicle ON sources(vehicle_id);
         ",
     )
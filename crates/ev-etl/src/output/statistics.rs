@@ -34,7 +34,7 @@ pub fn generate(vehicles: &[Vehicle], processing_time: Duration) -> Statistics {
         makes.insert(vehicle.make.slug.clone());
         models.insert((vehicle.make.slug.clone(), vehicle.model.slug.clone()));
 
-        let vehicle_type = format!("{:?}", vehicle.vehicle_type);
+        let vehicle_type = vehicle.vehicle_type.as_str().to_string();
         *vehicles_by_type.entry(vehicle_type).or_insert(0) += 1;
 
         *vehicles_by_make
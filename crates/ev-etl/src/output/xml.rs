@@ -4,31 +4,59 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use ev_core::Vehicle;
 
-pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
-    let mut file = std::fs::File::create(output_path)
-        .with_context(|| format!("Failed to create XML file at {:?}", output_path))?;
+use super::{OutputFile, OutputOptions, OutputWriter, VehicleSink, write_all};
 
-    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
-    writeln!(
-        file,
-        r#"<vehicles xmlns="https://openevdata.org/schema/1.0" count="{}">"#,
-        vehicles.len()
-    )?;
+/// `vehicles.xml`, one `<vehicle>` element per vehicle.
+pub struct XmlWriter;
 
-    for vehicle in vehicles {
-        write_vehicle(&mut file, vehicle)?;
+impl OutputWriter for XmlWriter {
+    fn name(&self) -> &'static str {
+        "xml"
     }
 
-    writeln!(file, "</vehicles>")?;
+    fn default_file_name(&self) -> &'static str {
+        "vehicles.xml"
+    }
 
-    Ok(())
+    fn begin(&self, options: &OutputOptions, vehicle_count: usize) -> Result<Box<dyn VehicleSink>> {
+        let mut file = OutputFile::create(options)
+            .with_context(|| format!("Failed to create XML file at {:?}", options.path))?;
+
+        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            file,
+            r#"<vehicles xmlns="https://openevdata.org/schema/1.0" count="{}">"#,
+            vehicle_count
+        )?;
+
+        Ok(Box::new(XmlSink { file }))
+    }
+}
+
+struct XmlSink {
+    file: OutputFile,
+}
+
+impl VehicleSink for XmlSink {
+    fn write(&mut self, vehicle: &Vehicle) -> Result<()> {
+        write_vehicle(&mut self.file, vehicle)
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        writeln!(self.file, "</vehicles>")?;
+        self.file.finish()
+    }
+}
+
+pub fn generate(vehicles: &[Vehicle], output_path: &Path) -> Result<()> {
+    write_all(&XmlWriter, vehicles, &OutputOptions::new(output_path))
 }
 
-fn write_vehicle(file: &mut std::fs::File, vehicle: &Vehicle) -> Result<()> {
+fn write_vehicle(file: &mut OutputFile, vehicle: &Vehicle) -> Result<()> {
     let unique_code = vehicle
         .unique_code
         .clone()
-        .expect("unique_code must be set by ETL merge");
+        .unwrap_or_else(|| vehicle.id().to_string());
 
     writeln!(file, r#"  <vehicle id="{}">"#, escape_xml(&unique_code))?;
     writeln!(
@@ -52,15 +80,15 @@ fn write_vehicle(file: &mut std::fs::File, vehicle: &Vehicle) -> Result<()> {
     )?;
     writeln!(
         file,
-        "    <vehicleType>{:?}</vehicleType>",
-        vehicle.vehicle_type
+        "    <vehicleType>{}</vehicleType>",
+        vehicle.vehicle_type.as_str()
     )?;
 
     writeln!(file, "    <powertrain>")?;
     writeln!(
         file,
-        "      <drivetrain>{:?}</drivetrain>",
-        vehicle.powertrain.drivetrain
+        "      <drivetrain>{}</drivetrain>",
+        vehicle.powertrain.drivetrain.as_str()
     )?;
     if let Some(power) = vehicle.powertrain.system_power_kw {
         writeln!(file, "      <systemPowerKw>{}</systemPowerKw>", power)?;
@@ -94,8 +122,9 @@ fn write_vehicle(file: &mut std::fs::File, vehicle: &Vehicle) -> Result<()> {
     for port in &vehicle.charge_ports {
         writeln!(
             file,
-            "      <port kind=\"{:?}\" connector=\"{:?}\"/>",
-            port.kind, port.connector
+            "      <port kind=\"{}\" connector=\"{}\"/>",
+            port.kind.as_str(),
+            port.connector.as_str()
         )?;
     }
     writeln!(file, "    </chargePorts>")?;
@@ -121,8 +150,9 @@ fn write_vehicle(file: &mut std::fs::File, vehicle: &Vehicle) -> Result<()> {
     for rating in &vehicle.range.rated {
         writeln!(
             file,
-            "      <rated cycle=\"{:?}\" km=\"{}\"/>",
-            rating.cycle, rating.range_km
+            "      <rated cycle=\"{}\" km=\"{}\"/>",
+            rating.cycle.as_str(),
+            rating.range_km
         )?;
     }
     writeln!(file, "    </range>")?;
@@ -131,8 +161,8 @@ fn write_vehicle(file: &mut std::fs::File, vehicle: &Vehicle) -> Result<()> {
     for source in &vehicle.sources {
         writeln!(
             file,
-            "      <source type=\"{:?}\" url=\"{}\"/>",
-            source.source_type,
+            "      <source type=\"{}\" url=\"{}\"/>",
+            source.source_type.as_str(),
             escape_xml(&source.url)
         )?;
     }
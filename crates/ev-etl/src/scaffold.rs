@@ -0,0 +1,112 @@
+//! Layer file templates for a new vehicle.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use ev_core::SlugName;
+use serde::Serialize;
+
+use crate::format::format_json;
+
+#[derive(Serialize)]
+struct ModelBase {
+    schema_version: &'static str,
+    make: SlugName,
+    model: SlugName,
+}
+
+#[derive(Serialize)]
+struct YearBase {
+    year: u16,
+    trim: SlugName,
+}
+
+#[derive(Serialize)]
+struct Variant {
+    variant: SlugName,
+}
+
+/// Creates the layer files of a vehicle under `input_dir`, returning their paths.
+///
+/// The model base and year base files are only created when missing, so a new
+/// year or variant can be added to an existing model. The variant file, when
+/// `variant` is given, must not exist yet.
+pub fn new_vehicle(
+    input_dir: &Path,
+    make_slug: &str,
+    model_slug: &str,
+    year: u16,
+    variant: Option<&str>,
+) -> Result<Vec<PathBuf>> {
+    let model_dir = input_dir.join(make_slug).join(model_slug);
+    let year_dir = model_dir.join(year.to_string());
+    let mut created = Vec::new();
+
+    let base = ModelBase {
+        schema_version: "1.0.0",
+        make: slug_name(make_slug)?,
+        model: slug_name(model_slug)?,
+    };
+    create(&model_dir.join("base.json"), &base, &mut created, false)?;
+
+    let year_base = YearBase {
+        year,
+        trim: slug_name("base")?,
+    };
+    let year_base_path = year_dir.join(format!("{}.json", model_slug));
+    create(&year_base_path, &year_base, &mut created, false)?;
+
+    if let Some(variant) = variant {
+        let path = year_dir.join(format!("{}_{}.json", model_slug, variant));
+        let variant = Variant {
+            variant: slug_name(variant)?,
+        };
+        create(&path, &variant, &mut created, true)?;
+    }
+
+    if created.is_empty() {
+        bail!("{:?} already exists", year_base_path);
+    }
+    Ok(created)
+}
+
+/// Writes `content` to `path` unless the file exists, which is an error when `required`.
+fn create(
+    path: &Path,
+    content: &impl Serialize,
+    created: &mut Vec<PathBuf>,
+    required: bool,
+) -> Result<()> {
+    if path.exists() {
+        if required {
+            bail!("{:?} already exists", path);
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+    std::fs::write(path, format_json(&serde_json::to_string(content)?)?)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    created.push(path.to_path_buf());
+    Ok(())
+}
+
+/// A slug with a display name derived from it, e.g. `model_3` → `Model 3`.
+fn slug_name(slug: &str) -> Result<SlugName> {
+    let name = slug
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    SlugName::new(slug, name).with_context(|| format!("Invalid slug {:?}", slug))
+}
@@ -1,19 +1,69 @@
 use anyhow::Result;
-use ev_core::{Validate, Vehicle};
+use ev_core::{Finding, Severity, Validate, Vehicle};
+use rayon::prelude::*;
 use serde_json::Value;
 use std::path::Path;
 
+use crate::ingest::VehicleFile;
+use crate::merge::{MergedDocument, VehicleOrigin};
+
 pub fn validate_all(vehicles: &[Vehicle]) -> Vec<Result<()>> {
     vehicles
-        .iter()
+        .par_iter()
         .map(|vehicle| {
-            vehicle
-                .validate()
-                .map_err(|e| anyhow::anyhow!("Validation failed for {}: {}", vehicle.id(), e))
+            let errors: Vec<String> = vehicle
+                .findings()
+                .iter()
+                .filter(|finding| finding.is_error())
+                .map(ToString::to_string)
+                .collect();
+
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!(
+                    "Validation failed for {}: {}",
+                    vehicle.id(),
+                    errors.join("; ")
+                ))
+            }
         })
         .collect()
 }
 
+/// Validates every vehicle, keeping those without error-severity findings.
+///
+/// Returns the retained vehicles together with a report of all findings, each
+/// naming the file of `origin` that set the flagged value.
+pub fn validate_with_report(
+    vehicles: Vec<(Vehicle, VehicleOrigin)>,
+) -> (Vec<Vehicle>, ValidationReport) {
+    let mut report = ValidationReport::new();
+
+    // Findings are computed in parallel and recorded in vehicle order.
+    let findings: Vec<Vec<Finding>> = vehicles
+        .par_iter()
+        .map(|(vehicle, _)| vehicle.findings())
+        .collect();
+    let valid = vehicles
+        .into_iter()
+        .zip(findings)
+        .filter(|((vehicle, origin), findings)| {
+            report.record(&vehicle_label(vehicle), origin, findings)
+        })
+        .map(|((vehicle, _), _)| vehicle)
+        .collect();
+
+    (valid, report)
+}
+
+fn vehicle_label(vehicle: &Vehicle) -> String {
+    vehicle
+        .unique_code
+        .clone()
+        .unwrap_or_else(|| vehicle.id().to_string())
+}
+
 #[allow(dead_code)]
 pub fn validate_vehicle(vehicle: &Vehicle) -> Result<()> {
     vehicle
@@ -21,7 +71,6 @@ pub fn validate_vehicle(vehicle: &Vehicle) -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Validation failed: {}", e))
 }
 
-#[allow(dead_code)]
 pub fn validate_with_json_schema(vehicle_json: &Value, schema: &Value) -> Result<Vec<String>> {
     let compiled =
         jsonschema::validator_for(schema).map_err(|e| anyhow::anyhow!("Invalid schema: {}", e))?;
@@ -34,7 +83,6 @@ pub fn validate_with_json_schema(vehicle_json: &Value, schema: &Value) -> Result
     Ok(errors)
 }
 
-#[allow(dead_code)]
 pub fn load_schema(schema_path: &Path) -> Result<Value> {
     let content = std::fs::read_to_string(schema_path)
         .map_err(|e| anyhow::anyhow!("Failed to read schema file: {}", e))?;
@@ -42,45 +90,269 @@ pub fn load_schema(schema_path: &Path) -> Result<Value> {
         .map_err(|e| anyhow::anyhow!("Failed to parse schema JSON: {}", e))
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-#[allow(dead_code)]
+/// A JSON Schema violation in a single dataset file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub file: String,
+    pub instance_path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let instance_path = if self.instance_path.is_empty() {
+            "/"
+        } else {
+            &self.instance_path
+        };
+        write!(f, "{} at {}: {}", self.file, instance_path, self.message)
+    }
+}
+
+/// Checks raw layer files against the schema with every `required` constraint removed,
+/// since a single layer only needs to be complete once merged.
+pub fn validate_layer_files(
+    files: &[VehicleFile],
+    schema: &Value,
+    input_dir: &Path,
+) -> Result<Vec<SchemaViolation>> {
+    let validator = compile_schema(&layer_schema(schema))?;
+
+    Ok(files
+        .par_iter()
+        .flat_map_iter(|file| {
+            let label = file
+                .path
+                .strip_prefix(input_dir)
+                .unwrap_or(&file.path)
+                .display()
+                .to_string();
+            schema_violations(&validator, &label, &file.content)
+        })
+        .collect())
+}
+
+/// Checks fully merged vehicle documents against the schema.
+pub fn validate_merged_documents(
+    documents: &[MergedDocument],
+    schema: &Value,
+) -> Result<Vec<SchemaViolation>> {
+    let validator = compile_schema(schema)?;
+
+    Ok(documents
+        .par_iter()
+        .flat_map_iter(|document| {
+            let label = format!("{} (merged)", document.label);
+            schema_violations(&validator, &label, &document.content)
+        })
+        .collect())
+}
+
+/// Returns a copy of `schema` for a single layer file: without `required` keywords,
+/// and with every property also accepting the [merge directives](crate::merge::Directive).
+pub fn layer_schema(schema: &Value) -> Value {
+    relax(schema, false)
+}
+
+fn relax(value: &Value, is_property_map: bool) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| is_property_map || key.as_str() != "required")
+                .map(|(key, child)| {
+                    let child_is_property_map = !is_property_map
+                        && matches!(key.as_str(), "properties" | "patternProperties");
+                    let child = relax(child, child_is_property_map);
+                    if is_property_map {
+                        (key.clone(), accept_directives(child))
+                    } else {
+                        (key.clone(), child)
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(|item| relax(item, false)).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Lets a property hold `null`, `{"$delete": true}`, or an array directive whose
+/// items match `schema`. Other values are checked against `schema` alone, so its
+/// errors are reported unchanged.
+fn accept_directives(schema: Value) -> Value {
+    serde_json::json!({
+        "if": {
+            "type": ["object", "null"],
+            "minProperties": 1,
+            "propertyNames": {"pattern": "^\\$"}
+        },
+        "then": {
+            "anyOf": [
+                {"type": "null"},
+                {
+                    "type": "object",
+                    "properties": {"$delete": {"const": true}},
+                    "required": ["$delete"],
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "patternProperties": {"^\\$(append|prepend|merge)$": schema},
+                    "properties": {"$key": {"type": "string"}},
+                    "additionalProperties": false,
+                    "anyOf": [
+                        {"required": ["$append"]},
+                        {"required": ["$prepend"]},
+                        {"required": ["$merge"]}
+                    ]
+                }
+            ]
+        },
+        "else": schema
+    })
+}
+
+fn compile_schema(schema: &Value) -> Result<jsonschema::Validator> {
+    jsonschema::validator_for(schema).map_err(|e| anyhow::anyhow!("Invalid schema: {}", e))
+}
+
+fn schema_violations(
+    validator: &jsonschema::Validator,
+    file: &str,
+    instance: &Value,
+) -> Vec<SchemaViolation> {
+    validator
+        .iter_errors(instance)
+        .map(|error| SchemaViolation {
+            file: file.to_string(),
+            instance_path: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidationReport {
     pub total_vehicles: usize,
     pub valid_count: usize,
     pub error_count: usize,
+    pub warning_count: usize,
     pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationError>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidationError {
     pub vehicle_id: String,
+    /// Dataset-relative layer file that set the value at `path`.
+    pub file: String,
     pub error_type: String,
+    pub severity: Severity,
     pub message: String,
     pub path: String,
 }
 
-#[allow(dead_code)]
 impl ValidationReport {
     pub fn new() -> Self {
         Self {
             total_vehicles: 0,
             valid_count: 0,
             error_count: 0,
+            warning_count: 0,
             errors: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
-    pub fn add_error(&mut self, vehicle_id: &str, error_type: &str, message: &str, path: &str) {
+    pub fn add_error(
+        &mut self,
+        vehicle_id: &str,
+        file: &str,
+        error_type: &str,
+        message: &str,
+        path: &str,
+    ) {
         self.error_count += 1;
         self.errors.push(ValidationError {
             vehicle_id: vehicle_id.to_string(),
+            file: file.to_string(),
+            error_type: error_type.to_string(),
+            severity: Severity::Error,
+            message: message.to_string(),
+            path: path.to_string(),
+        });
+    }
+
+    pub fn add_warning(
+        &mut self,
+        vehicle_id: &str,
+        file: &str,
+        error_type: &str,
+        message: &str,
+        path: &str,
+    ) {
+        self.warning_count += 1;
+        self.warnings.push(ValidationError {
+            vehicle_id: vehicle_id.to_string(),
+            file: file.to_string(),
             error_type: error_type.to_string(),
+            severity: Severity::Warning,
             message: message.to_string(),
             path: path.to_string(),
         });
     }
 
+    pub fn add_finding(&mut self, vehicle_id: &str, file: &str, finding: &Finding) {
+        let message = finding.error.to_string();
+        let (code, pointer) = (finding.code(), finding.pointer.as_str());
+        match finding.severity {
+            Severity::Error => self.add_error(vehicle_id, file, code, &message, pointer),
+            Severity::Warning => self.add_warning(vehicle_id, file, code, &message, pointer),
+        }
+    }
+
+    /// Records a schema violation as an error with rule code `schema`. It belongs to a
+    /// file rather than a vehicle, so `vehicle_id` is left empty.
+    pub fn add_schema_violation(&mut self, violation: &SchemaViolation) {
+        let path = if violation.instance_path.is_empty() {
+            "/"
+        } else {
+            &violation.instance_path
+        };
+        self.add_error("", &violation.file, "schema", &violation.message, path);
+    }
+
+    /// Records the findings of one vehicle, merged from the files of `origin`, and
+    /// returns whether it is valid.
+    pub fn record(
+        &mut self,
+        vehicle_id: &str,
+        origin: &VehicleOrigin,
+        findings: &[Finding],
+    ) -> bool {
+        self.total_vehicles += 1;
+
+        for finding in findings {
+            self.add_finding(vehicle_id, origin.file_of(&finding.pointer), finding);
+        }
+
+        let is_valid = !findings.iter().any(Finding::is_error);
+        if is_valid {
+            self.valid_count += 1;
+        }
+        is_valid
+    }
+
+    /// Appends the counts and findings of `other`.
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.total_vehicles += other.total_vehicles;
+        self.valid_count += other.valid_count;
+        self.error_count += other.error_count;
+        self.warning_count += other.warning_count;
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
         let json = serde_json::to_string_pretty(self)?;
         std::fs::write(path, json)?;
@@ -0,0 +1,164 @@
+//! Rebuilds the make/model groups of a dataset as their layer files change.
+//!
+//! The input directory is polled for added, removed and modified JSON files. Each
+//! affected group is read, merged, schema-checked and validated on its own, so an
+//! error in one model neither stops the watch nor hides the state of the others.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Result, bail};
+use tracing::{error, info};
+
+use crate::cache::ModelBuild;
+use crate::cli::WatchArgs;
+use crate::{ModelBuilds, Schema, ingest, output};
+
+/// Make and model directory of a group of layer files.
+pub type Model = (String, String);
+
+/// Modification time and size of a file.
+type Stamp = (Option<SystemTime>, u64);
+
+/// The latest build of every model of a dataset.
+pub struct Watcher {
+    input_dir: PathBuf,
+    schema: Schema,
+    sqlite: Option<PathBuf>,
+    stamps: BTreeMap<PathBuf, Stamp>,
+    builds: ModelBuilds,
+}
+
+impl Watcher {
+    /// Builds every model of the dataset once.
+    pub fn new(args: &WatchArgs) -> Result<Self> {
+        let mut watcher = Self {
+            input_dir: args.input.clone(),
+            schema: Schema::load(args.schema.as_deref())?,
+            sqlite: args.sqlite.clone(),
+            stamps: BTreeMap::new(),
+            builds: BTreeMap::new(),
+        };
+        watcher.poll()?;
+        Ok(watcher)
+    }
+
+    /// Rebuilds the models whose layer files changed since the last call, and
+    /// returns them.
+    ///
+    /// A model that fails to build keeps its previous build; the errors are logged.
+    pub fn poll(&mut self) -> Result<BTreeSet<Model>> {
+        let stamps = snapshot(&self.input_dir)?;
+        let changed = changed_models(&self.input_dir, &self.stamps, &stamps);
+        self.stamps = stamps;
+
+        for model in &changed {
+            self.rebuild(model);
+        }
+        if !changed.is_empty() {
+            self.write_sqlite();
+        }
+
+        Ok(changed)
+    }
+
+    /// Latest successful build of a model.
+    #[must_use]
+    pub fn build(&self, make_slug: &str, model_slug: &str) -> Option<&ModelBuild> {
+        self.builds
+            .get(&(make_slug.to_string(), model_slug.to_string()))
+    }
+
+    fn rebuild(&mut self, model: &Model) {
+        let (make_slug, model_slug) = (model.0.as_str(), model.1.as_str());
+        let built = ingest::load_model(&self.input_dir, make_slug, model_slug).and_then(|files| {
+            crate::build_model_groups(&files, &self.input_dir, &self.schema, None)
+        });
+
+        match built {
+            Ok(mut builds) => match builds.remove(model) {
+                Some(build) => {
+                    crate::log_findings(&build.report);
+                    info!(
+                        "{}/{}: {} of {} vehicles valid, {} errors, {} warnings",
+                        make_slug,
+                        model_slug,
+                        build.report.valid_count,
+                        build.report.total_vehicles,
+                        build.report.error_count,
+                        build.report.warning_count
+                    );
+                    self.builds.insert(model.clone(), build);
+                }
+                None => {
+                    info!("{}/{}: no vehicles", make_slug, model_slug);
+                    self.builds.remove(model);
+                }
+            },
+            Err(e) => error!("{}/{}: {:#}", make_slug, model_slug, e),
+        }
+    }
+
+    fn write_sqlite(&self) {
+        let Some(ref path) = self.sqlite else {
+            return;
+        };
+
+        let written =
+            crate::combine_builds(self.builds.values().cloned()).and_then(|(vehicles, _)| {
+                output::sqlite::refresh(&vehicles, path).map(|()| vehicles.len())
+            });
+        match written {
+            Ok(count) => info!("Updated {:?} with {} vehicles", path, count),
+            Err(e) => error!("Failed to update {:?}: {:#}", path, e),
+        }
+    }
+}
+
+/// Modification time and size of every JSON file under `input_dir`.
+fn snapshot(input_dir: &Path) -> Result<BTreeMap<PathBuf, Stamp>> {
+    if !input_dir.is_dir() {
+        bail!("Input directory {:?} not found", input_dir);
+    }
+
+    Ok(ingest::reader::json_paths(input_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some((path, (metadata.modified().ok(), metadata.len())))
+        })
+        .collect())
+}
+
+/// Models with a file added, removed or modified between two snapshots.
+fn changed_models(
+    input_dir: &Path,
+    before: &BTreeMap<PathBuf, Stamp>,
+    after: &BTreeMap<PathBuf, Stamp>,
+) -> BTreeSet<Model> {
+    let removed = before.keys().filter(|path| !after.contains_key(*path));
+    let modified = after
+        .iter()
+        .filter(|(path, stamp)| before.get(*path) != Some(*stamp))
+        .map(|(path, _)| path);
+
+    removed
+        .chain(modified)
+        .filter_map(|path| model_of(input_dir, path))
+        .collect()
+}
+
+/// Model directory a file under `input_dir` belongs to, if it is inside one.
+fn model_of(input_dir: &Path, path: &Path) -> Option<Model> {
+    let mut components = path.strip_prefix(input_dir).ok()?.components();
+    let (Component::Normal(make), Component::Normal(model), Some(_)) =
+        (components.next()?, components.next()?, components.next())
+    else {
+        return None;
+    };
+    Some((
+        make.to_string_lossy().into_owned(),
+        model.to_string_lossy().into_owned(),
+    ))
+}
@@ -4,7 +4,7 @@ use axum::extract::State;
 use axum::routing::get;
 use axum::{Json, Router};
 
-use crate::db::Database;
+use crate::db::{Database, VehicleRepository};
 use crate::error::{ApiError, ProblemDetails};
 use crate::models::HealthResponse;
 
@@ -29,6 +29,7 @@ pub async fn health_check(
 ) -> Result<Json<HealthResponse>, ApiError> {
     let vehicle_count = db
         .get_vehicle_count()
+        .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     Ok(Json(HealthResponse {
@@ -4,7 +4,7 @@ use axum::extract::State;
 use axum::routing::get;
 use axum::{Json, Router};
 
-use crate::db::Database;
+use crate::db::{Database, VehicleRepository};
 use crate::error::{ApiError, ProblemDetails};
 use crate::models::MakesListResponse;
 
@@ -28,6 +28,7 @@ pub async fn list_makes(
 ) -> Result<Json<MakesListResponse>, ApiError> {
     let makes = db
         .list_makes()
+        .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     Ok(Json(MakesListResponse { makes }))
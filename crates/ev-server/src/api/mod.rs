@@ -33,6 +33,10 @@ use crate::db::Database;
         vehicles::list_vehicles,
         vehicles::get_vehicle_by_code,
         vehicles::search_vehicles,
+        vehicles::suggest_vehicles,
+        vehicles::compare_vehicles,
+        vehicles::diff_vehicles,
+        vehicles::list_compatible_vehicles,
         makes::list_makes,
     ),
     components(
@@ -45,8 +49,20 @@ use crate::db::Database;
             crate::models::MakesListResponse,
             crate::models::SearchQuery,
             crate::models::SearchResponse,
+            crate::models::SuggestQuery,
+            crate::models::SuggestResponse,
+            crate::models::CompareQuery,
+            crate::models::CompareResponse,
+            crate::models::VehicleDiffResponse,
+            crate::fuzzy::Suggestion,
+            crate::fuzzy::SuggestionKind,
+            crate::models::CompatibleVehiclesQuery,
+            crate::models::CompatibleVehiclesResponse,
             crate::db::VehicleSummary,
             crate::db::MakeSummary,
+            crate::db::SearchHit,
+            crate::db::SearchHighlight,
+            ev_core::Vehicle,
         )
     ),
     tags(
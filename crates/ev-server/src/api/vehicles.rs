@@ -3,19 +3,38 @@ use std::sync::Arc;
 use axum::extract::{Path, Query, State};
 use axum::routing::get;
 use axum::{Json, Router};
-use ev_core::Vehicle;
+use std::str::FromStr;
 
-use crate::db::{Database, ListParams};
+use ev_core::validation::{validate_country_code, validate_currency_code};
+use ev_core::{
+    ADAPTERS, ChargerSpec, CurrentType, ValidationError, Vehicle, VehicleId,
+    candidate_port_connectors, check_compatibility, compare,
+};
+
+use crate::db::{Database, ListParams, PriceRange, VehicleRepository};
 use crate::error::{ApiError, ProblemDetails};
+use crate::fuzzy::{correct_query, suggest};
 use crate::models::{
-    Pagination, SearchQuery, SearchResponse, VehicleListQuery, VehicleListResponse,
+    CompareQuery, CompareResponse, CompatibleVehicle, CompatibleVehiclesQuery,
+    CompatibleVehiclesResponse, Pagination, SearchQuery, SearchResponse, SuggestQuery,
+    SuggestResponse, VehicleDiffResponse, VehicleListQuery, VehicleListResponse,
 };
 
+/// Most vehicles `/vehicles/compare` accepts in one request.
+const MAX_COMPARED_VEHICLES: usize = 10;
+
 pub fn routes() -> Router<Arc<Database>> {
     Router::new()
         .route("/vehicles/list", get(list_vehicles))
         .route("/vehicles/code/{unique_code}", get(get_vehicle_by_code))
         .route("/vehicles/search", get(search_vehicles))
+        .route("/vehicles/suggest", get(suggest_vehicles))
+        .route("/vehicles/compare", get(compare_vehicles))
+        .route(
+            "/vehicles/{unique_code}/diff/{other_code}",
+            get(diff_vehicles),
+        )
+        .route("/vehicles/compatible", get(list_compatible_vehicles))
 }
 
 #[utoipa::path(
@@ -28,9 +47,25 @@ pub fn routes() -> Router<Arc<Database>> {
         ("make" = Option<String>, Query, description = "Filter by manufacturer slug (e.g., 'tesla')"),
         ("model" = Option<String>, Query, description = "Filter by model slug (e.g., 'model_3')"),
         ("year" = Option<u16>, Query, description = "Filter by model year (e.g., 2024)"),
-        ("vehicle_type" = Option<String>, Query, description = "Filter by vehicle type (e.g., 'bev', 'phev')"),
+        ("vehicle_type" = Option<String>, Query, description = "Filter by vehicle type (e.g., 'passenger_car', 'suv')"),
         ("min_range_km" = Option<f64>, Query, description = "Minimum WLTP range in kilometers"),
         ("max_range_km" = Option<f64>, Query, description = "Maximum WLTP range in kilometers"),
+        ("drivetrain" = Option<String>, Query, description = "Filter by drivetrain: 'fwd', 'rwd', 'awd', '4wd'"),
+        ("connector" = Option<String>, Query, description = "Vehicles with a charge port of this connector (e.g., 'ccs2', 'nacs')"),
+        ("min_dc_power_kw" = Option<f64>, Query, description = "Minimum DC charging power in kW"),
+        ("min_ac_power_kw" = Option<f64>, Query, description = "Minimum AC charging power in kW"),
+        ("min_battery_kwh" = Option<f64>, Query, description = "Minimum net battery capacity in kWh"),
+        ("max_battery_kwh" = Option<f64>, Query, description = "Maximum net battery capacity in kWh"),
+        ("max_acceleration_0_100_s" = Option<f64>, Query, description = "Maximum 0-100 km/h time in seconds"),
+        ("v2l" = Option<bool>, Query, description = "Vehicle-to-load support"),
+        ("v2h" = Option<bool>, Query, description = "Vehicle-to-home support"),
+        ("v2g" = Option<bool>, Query, description = "Vehicle-to-grid support"),
+        ("market" = Option<String>, Query, description = "ISO 3166-1 alpha-2 market country (e.g., 'DE')"),
+        ("availability" = Option<String>, Query, description = "Availability status (e.g., 'production', 'discontinued')"),
+        ("min_price" = Option<f64>, Query, description = "Minimum MSRP, requires 'currency'"),
+        ("max_price" = Option<f64>, Query, description = "Maximum MSRP, requires 'currency'"),
+        ("currency" = Option<String>, Query, description = "ISO 4217 currency of the price filter (e.g., 'EUR')"),
+        ("heat_pump" = Option<bool>, Query, description = "Heat pump presence"),
         ("page" = Option<usize>, Query, description = "Page number, starting from 1 (default: 1)"),
         ("per_page" = Option<usize>, Query, description = "Results per page, 1-100 (default: 20)"),
         ("sort_by" = Option<String>, Query, description = "Sort field: 'make', 'model', 'year', 'range'"),
@@ -49,13 +84,60 @@ pub async fn list_vehicles(
     let per_page = query.per_page.min(100).max(1);
     let page = query.page.max(1);
 
+    if let Some(ref market) = query.market {
+        validate_country_code(market).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+
+    let price = match (query.currency, query.min_price, query.max_price) {
+        (None, None, None) => None,
+        (None, _, _) => {
+            return Err(ApiError::BadRequest(
+                "currency is required with min_price or max_price".to_string(),
+            ));
+        }
+        (Some(currency), min, max) => {
+            validate_currency_code(&currency).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            Some(PriceRange { currency, min, max })
+        }
+    };
+
     let params = ListParams {
         make: query.make,
         model: query.model,
         year: query.year,
-        vehicle_type: query.vehicle_type,
+        vehicle_type: query
+            .vehicle_type
+            .as_deref()
+            .map(parse_query_value)
+            .transpose()?,
         min_range_km: query.min_range_km,
         max_range_km: query.max_range_km,
+        drivetrain: query
+            .drivetrain
+            .as_deref()
+            .map(parse_query_value)
+            .transpose()?,
+        connector: query
+            .connector
+            .as_deref()
+            .map(parse_query_value)
+            .transpose()?,
+        min_dc_power_kw: query.min_dc_power_kw,
+        min_ac_power_kw: query.min_ac_power_kw,
+        min_battery_kwh: query.min_battery_kwh,
+        max_battery_kwh: query.max_battery_kwh,
+        max_acceleration_0_100_s: query.max_acceleration_0_100_s,
+        v2l: query.v2l,
+        v2h: query.v2h,
+        v2g: query.v2g,
+        market: query.market,
+        availability: query
+            .availability
+            .as_deref()
+            .map(parse_query_value)
+            .transpose()?,
+        price,
+        heat_pump: query.heat_pump,
         page,
         per_page,
         sort_by: query.sort_by,
@@ -64,6 +146,7 @@ pub async fn list_vehicles(
 
     let (vehicles, total) = db
         .list_vehicles(&params)
+        .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     Ok(Json(VehicleListResponse {
@@ -77,19 +160,30 @@ pub async fn list_vehicles(
     path = "/vehicles/code/{unique_code}",
     tag = "vehicles",
     summary = "Get vehicle by unique code",
-    description = "Retrieve a vehicle using its unique identifier code. The code format is 'make:model:year:filename' (e.g., 'byd:dolphin:2024:dolphin').",
+    description = "Retrieve a vehicle using its unique identifier code. The code format is 'make:model:year:trim[:variant]' (e.g., 'byd:dolphin:2024:base').",
     params(
-        ("unique_code" = String, Path, description = "Vehicle unique code (e.g., 'byd:dolphin:2024:dolphin')")
+        ("unique_code" = String, Path, description = "Vehicle unique code (e.g., 'byd:dolphin:2024:base')")
     ),
     responses(
-        (status = 200, description = "Vehicle found", body = inline(serde_json::Value), example = json!({
+        (status = 200, description = "Vehicle found", body = Vehicle, example = json!({
+            "schema_version": "1.0.0",
             "make": {"slug": "byd", "name": "BYD"},
             "model": {"slug": "dolphin", "name": "Dolphin"},
             "year": 2024,
-            "trim_slug": "standard",
-            "trim_name": "Standard",
-            "vehicle_type": "bev"
+            "trim": {"slug": "standard", "name": "Standard"},
+            "vehicle_type": "passenger_car",
+            "powertrain": {"drivetrain": "fwd", "system_power_kw": 150.0},
+            "battery": {"chemistry": "LFP", "pack_capacity_kwh_net": 60.4},
+            "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+            "charging": {
+                "ac": {"max_power_kw": 11.0, "phases": 3},
+                "dc": {"max_power_kw": 88.0, "architecture_voltage_class": "400v"}
+            },
+            "range": {"rated": [{"cycle": "wltp", "range_km": 427.0}]},
+            "sources": [{"type": "oem", "title": "BYD Dolphin specifications", "url": "https://www.byd.com/", "accessed_at": "2024-06-01"}],
+            "unique_code": "byd:dolphin:2024:standard"
         })),
+        (status = 400, description = "Malformed unique code", body = ProblemDetails),
         (status = 404, description = "Vehicle not found", body = ProblemDetails),
         (status = 500, description = "Internal server error", body = ProblemDetails)
     )
@@ -98,8 +192,13 @@ pub async fn get_vehicle_by_code(
     State(db): State<Arc<Database>>,
     Path(unique_code): Path<String>,
 ) -> Result<Json<Vehicle>, ApiError> {
+    let id: VehicleId = unique_code
+        .parse()
+        .map_err(|e: ev_core::ValidationError| ApiError::BadRequest(e.to_string()))?;
+
     let vehicle = db
-        .get_vehicle_by_code(&unique_code)
+        .get_vehicle_by_code(&id.to_string())
+        .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     match vehicle {
@@ -116,7 +215,7 @@ pub async fn get_vehicle_by_code(
     path = "/vehicles/search",
     tag = "vehicles",
     summary = "Search vehicles by keyword",
-    description = "Full-text search across vehicle makes, models, and trims. Returns paginated results matching the query. The search is case-insensitive and matches partial words. Minimum query length is 2 characters.",
+    description = "Full-text search across vehicle makes, models, trims, variants, body styles, platforms, battery chemistries and markets. Every word of the query must match the start of a word (e.g. 'dolph' finds 'Dolphin'); the search is case- and accent-insensitive. Results are ranked by relevance (BM25), make, model and trim matches first, and carry highlighted snippets of the matched fields. When nothing matches, misspelled words are replaced by the closest make, model or trim word and the search is retried; `corrected_query` then holds the query that was used. Minimum query length is 2 characters.",
     params(
         ("q" = String, Query, description = "Search query (minimum 2 characters)"),
         ("page" = Option<usize>, Query, description = "Page number, starting from 1 (default: 1)"),
@@ -132,27 +231,288 @@ pub async fn search_vehicles(
     State(db): State<Arc<Database>>,
     Query(query): Query<SearchQuery>,
 ) -> Result<Json<SearchResponse>, ApiError> {
-    if query.q.is_empty() {
+    validate_search_text(&query.q)?;
+
+    let per_page = query.per_page.min(100).max(1);
+    let page = query.page.max(1);
+
+    let (mut results, mut total) = db
+        .search(&query.q, page, per_page)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    let mut corrected_query = None;
+    if total == 0 {
+        let names = db
+            .vehicle_names()
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        if let Some(corrected) = correct_query(names, &query.q) {
+            (results, total) = db
+                .search(&corrected, page, per_page)
+                .await
+                .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+            corrected_query = Some(corrected);
+        }
+    }
+
+    Ok(Json(SearchResponse {
+        results,
+        pagination: Pagination::new(page, per_page, total),
+        corrected_query,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/vehicles/suggest",
+    tag = "vehicles",
+    summary = "Autocomplete makes, models and trims",
+    description = "Returns ranked make, model and trim suggestions for a partially typed, possibly misspelled query (e.g. 'tesls modle 3' suggests 'Tesla Model 3'). Words may be prefixes and tolerate one typo up to 5 characters, two beyond; the last word may be an incomplete prefix with typos. Minimum query length is 2 characters.",
+    params(
+        ("q" = String, Query, description = "Partial query (minimum 2 characters)"),
+        ("limit" = Option<usize>, Query, description = "Maximum suggestions, 1-50 (default: 10)")
+    ),
+    responses(
+        (status = 200, description = "Suggestions, best first", body = SuggestResponse),
+        (status = 400, description = "Invalid query (empty or too short)", body = ProblemDetails),
+        (status = 500, description = "Internal server error", body = ProblemDetails)
+    )
+)]
+pub async fn suggest_vehicles(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<SuggestQuery>,
+) -> Result<Json<SuggestResponse>, ApiError> {
+    validate_search_text(&query.q)?;
+
+    let names = db
+        .vehicle_names()
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(SuggestResponse {
+        suggestions: suggest(names, &query.q, query.limit.clamp(1, 50)),
+    }))
+}
+
+fn validate_search_text(q: &str) -> Result<(), ApiError> {
+    if q.is_empty() {
         return Err(ApiError::BadRequest(
             "Search query cannot be empty".to_string(),
         ));
     }
 
-    if query.q.len() < 2 {
+    if q.len() < 2 {
         return Err(ApiError::BadRequest(
             "Search query must be at least 2 characters".to_string(),
         ));
     }
 
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/vehicles/compare",
+    tag = "vehicles",
+    summary = "Compare vehicles side by side",
+    description = "Returns the full vehicles plus a field-by-field alignment of their documents and a comparison of the headline specs: WLTP and EPA range, usable battery capacity, DC and AC charging power, 0-100 km/h, energy consumption and MSRP. Each spec marks the best and worst vehicle and gives each value's difference to the best one and to the first vehicle. The MSRP uses each vehicle's lowest price in `currency`, by default the currency most of the vehicles are priced in.",
+    params(
+        ("codes" = String, Query, description = "2 to 10 comma-separated unique codes (e.g., 'byd:dolphin:2024:base,mg:mg4:2024:standard')"),
+        ("currency" = Option<String>, Query, description = "ISO 4217 currency for the MSRP comparison (e.g., 'EUR')")
+    ),
+    responses(
+        (status = 200, description = "Vehicles and their comparison", body = CompareResponse),
+        (status = 400, description = "Fewer than 2 or more than 10 codes, duplicate or malformed codes, or an invalid currency", body = ProblemDetails),
+        (status = 404, description = "A vehicle was not found", body = ProblemDetails),
+        (status = 500, description = "Internal server error", body = ProblemDetails)
+    )
+)]
+pub async fn compare_vehicles(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<CompareQuery>,
+) -> Result<Json<CompareResponse>, ApiError> {
+    let mut ids: Vec<VehicleId> = Vec::new();
+    for code in query.codes.split(',').map(str::trim) {
+        let id: VehicleId = code
+            .parse()
+            .map_err(|e: ValidationError| ApiError::BadRequest(e.to_string()))?;
+        if ids.contains(&id) {
+            return Err(ApiError::BadRequest(format!(
+                "Duplicate vehicle code: {}",
+                code
+            )));
+        }
+        ids.push(id);
+    }
+
+    if !(2..=MAX_COMPARED_VEHICLES).contains(&ids.len()) {
+        return Err(ApiError::BadRequest(format!(
+            "Compare between 2 and {} vehicles, got {}",
+            MAX_COMPARED_VEHICLES,
+            ids.len()
+        )));
+    }
+
+    if let Some(ref currency) = query.currency {
+        validate_currency_code(currency).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+
+    let mut vehicles = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let code = id.to_string();
+        let vehicle = db
+            .get_vehicle_by_code(&code)
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound(format!("Vehicle not found with code: {}", code)))?;
+        vehicles.push(vehicle);
+    }
+
+    let comparison = compare(&vehicles, query.currency.as_deref());
+
+    Ok(Json(CompareResponse {
+        vehicles,
+        comparison,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/vehicles/{unique_code}/diff/{other_code}",
+    tag = "vehicles",
+    summary = "Diff two vehicles",
+    description = "Lists the fields added, removed or changed from one vehicle to another, e.g. between two model years of the same model. Each change has the JSON pointer of the field and its old and new values. A field present on one side only is reported once with its whole value.",
+    params(
+        ("unique_code" = String, Path, description = "Code of the old vehicle (e.g., 'tesla:model_3:2023:long_range')"),
+        ("other_code" = String, Path, description = "Code of the new vehicle (e.g., 'tesla:model_3:2024:long_range')")
+    ),
+    responses(
+        (status = 200, description = "Changes ordered by path", body = VehicleDiffResponse),
+        (status = 400, description = "Malformed unique code", body = ProblemDetails),
+        (status = 404, description = "Vehicle not found", body = ProblemDetails),
+        (status = 500, description = "Internal server error", body = ProblemDetails)
+    )
+)]
+pub async fn diff_vehicles(
+    State(db): State<Arc<Database>>,
+    Path((unique_code, other_code)): Path<(String, String)>,
+) -> Result<Json<VehicleDiffResponse>, ApiError> {
+    let mut vehicles = Vec::with_capacity(2);
+    let mut codes = Vec::with_capacity(2);
+    for code in [&unique_code, &other_code] {
+        let id: VehicleId = code
+            .parse()
+            .map_err(|e: ValidationError| ApiError::BadRequest(e.to_string()))?;
+        let code = id.to_string();
+        let vehicle = db
+            .get_vehicle_by_code(&code)
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| ApiError::NotFound(format!("Vehicle not found with code: {}", code)))?;
+        vehicles.push(vehicle);
+        codes.push(code);
+    }
+
+    // Canonical codes, which may differ in spelling from the path.
+    let [from, to] = [codes[0].clone(), codes[1].clone()];
+    Ok(Json(VehicleDiffResponse {
+        diff: ev_core::diff_vehicles(&vehicles[0], &vehicles[1]),
+        from,
+        to,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/vehicles/compatible",
+    tag = "vehicles",
+    summary = "List vehicles compatible with a charger",
+    description = "Lists the vehicles that can charge on the described charger, natively or through a common adapter, with the charge port used and the maximum power. DC power is capped by the vehicle's limit for the charger's voltage class, e.g. an 800V vehicle on a 400V charger.",
+    params(
+        ("connector" = String, Query, description = "Charger plug (e.g., 'ccs2', 'nacs', 'chademo', 'type2')"),
+        ("max_power_kw" = f64, Query, description = "Charger maximum power in kW"),
+        ("current" = Option<String>, Query, description = "'ac' or 'dc' (default: the plug's current; 'dc' for NACS)"),
+        ("voltage_class" = Option<String>, Query, description = "DC charger voltage class: '400v' or '800v'"),
+        ("adapters" = Option<bool>, Query, description = "Include connections through common adapters (default: true)"),
+        ("page" = Option<usize>, Query, description = "Page number, starting from 1 (default: 1)"),
+        ("per_page" = Option<usize>, Query, description = "Results per page, 1-100 (default: 20)")
+    ),
+    responses(
+        (status = 200, description = "Compatible vehicles with pagination", body = CompatibleVehiclesResponse),
+        (status = 400, description = "Invalid charger description", body = ProblemDetails),
+        (status = 500, description = "Internal server error", body = ProblemDetails)
+    )
+)]
+pub async fn list_compatible_vehicles(
+    State(db): State<Arc<Database>>,
+    Query(query): Query<CompatibleVehiclesQuery>,
+) -> Result<Json<CompatibleVehiclesResponse>, ApiError> {
+    if query.max_power_kw.is_nan() || query.max_power_kw <= 0.0 {
+        return Err(ApiError::BadRequest(
+            "max_power_kw must be greater than zero".to_string(),
+        ));
+    }
+
+    let connector = parse_query_value(&query.connector)?;
+    let current = match query.current {
+        Some(ref current) => parse_query_value(current)?,
+        None => CurrentType::of_connector(connector).unwrap_or(CurrentType::Dc),
+    };
+    let voltage_class = query
+        .voltage_class
+        .as_deref()
+        .map(parse_query_value)
+        .transpose()?;
+
+    let charger = ChargerSpec {
+        connector,
+        current,
+        max_power_kw: query.max_power_kw,
+        voltage_class,
+    };
+    let adapters = if query.adapters { ADAPTERS } else { &[] };
+
+    let compatible: Vec<CompatibleVehicle> = db
+        .list_vehicles_with_ports(&candidate_port_connectors(&charger, adapters))
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?
+        .into_iter()
+        .filter_map(|vehicle| {
+            let compatibility = check_compatibility(&vehicle, &charger, adapters)?;
+            Some(CompatibleVehicle {
+                unique_code: vehicle
+                    .unique_code
+                    .clone()
+                    .unwrap_or_else(|| vehicle.id().to_string()),
+                make_name: vehicle.make.name,
+                model_name: vehicle.model.name,
+                year: vehicle.year,
+                trim_name: vehicle.trim.name,
+                compatibility,
+            })
+        })
+        .collect();
+
     let per_page = query.per_page.min(100).max(1);
     let page = query.page.max(1);
+    let total = compatible.len();
+    let vehicles = compatible
+        .into_iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .collect();
 
-    let (vehicles, total) = db
-        .search(&query.q, page, per_page)
-        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
-
-    Ok(Json(SearchResponse {
-        results: vehicles,
+    Ok(Json(CompatibleVehiclesResponse {
+        charger,
+        vehicles,
         pagination: Pagination::new(page, per_page, total),
     }))
 }
+
+/// Parses a query value into one of the ev-core enums from its wire string.
+fn parse_query_value<T: FromStr<Err = ValidationError>>(value: &str) -> Result<T, ApiError> {
+    value
+        .parse()
+        .map_err(|e: ValidationError| ApiError::BadRequest(e.to_string()))
+}
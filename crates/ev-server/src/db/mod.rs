@@ -1,46 +1,203 @@
 mod postgresql;
 mod sqlite;
 
+use std::future::Future;
+
 use anyhow::Result;
-use ev_core::Vehicle;
+use ev_core::{AvailabilityStatus, ConnectorType, Drivetrain, Vehicle, VehicleType};
 
 pub use postgresql::PostgresDatabase;
 pub use sqlite::SqliteDatabase;
 
+/// Read-only vehicle storage shared by every database backend.
+///
+/// Handlers depend on this trait instead of a concrete backend, so the same
+/// routes serve the SQLite artifact and the PostgreSQL dump produced by ev-etl.
+pub trait VehicleRepository: Send + Sync {
+    /// Returns the total number of vehicles stored.
+    fn get_vehicle_count(&self) -> impl Future<Output = Result<usize>> + Send;
+
+    /// Returns one page of vehicle summaries matching `params`, plus the total match count.
+    fn list_vehicles(
+        &self,
+        params: &ListParams,
+    ) -> impl Future<Output = Result<(Vec<VehicleSummary>, usize)>> + Send;
+
+    /// Returns every manufacturer with its vehicle count and model names.
+    fn list_makes(&self) -> impl Future<Output = Result<Vec<MakeSummary>>> + Send;
+
+    /// Returns one page of vehicles matching `query`, best matches first, plus the total match count.
+    fn search(
+        &self,
+        query: &str,
+        page: usize,
+        per_page: usize,
+    ) -> impl Future<Output = Result<(Vec<SearchHit>, usize)>> + Send;
+
+    /// Returns the make, model and trim names of every vehicle, ordered by make, model and year.
+    fn list_vehicle_names(&self) -> impl Future<Output = Result<Vec<VehicleName>>> + Send;
+
+    /// Returns the full vehicle document stored under `code`, if any.
+    fn get_vehicle_by_code(
+        &self,
+        code: &str,
+    ) -> impl Future<Output = Result<Option<Vehicle>>> + Send;
+
+    /// Returns the full documents of the vehicles with a charge port of one of
+    /// `connectors`, ordered by make, model, year and code.
+    fn list_vehicles_with_ports(
+        &self,
+        connectors: &[ConnectorType],
+    ) -> impl Future<Output = Result<Vec<Vehicle>>> + Send;
+}
+
+enum Backend {
+    Sqlite(SqliteDatabase),
+    Postgres(PostgresDatabase),
+}
+
+/// Database handle selected from the `DATABASE_URL` scheme.
+///
+/// `postgres://` and `postgresql://` URLs open a pooled PostgreSQL connection;
+/// anything else (optionally prefixed with `sqlite://`) is treated as a SQLite file path.
 pub struct Database {
-    inner: SqliteDatabase,
+    inner: Backend,
+    /// Vocabulary of search corrections and suggestions, see [`Database::vehicle_names`].
+    names: tokio::sync::OnceCell<Vec<VehicleName>>,
 }
 
 impl Database {
     pub fn new(url: &str) -> Result<Self> {
-        let inner = SqliteDatabase::new(url)?;
-        Ok(Self { inner })
+        let inner = if PostgresDatabase::is_postgres_url(url) {
+            Backend::Postgres(PostgresDatabase::new(url)?)
+        } else {
+            let path = url.strip_prefix("sqlite://").unwrap_or(url);
+            Backend::Sqlite(SqliteDatabase::new(path)?)
+        };
+
+        Ok(Self {
+            inner,
+            names: tokio::sync::OnceCell::new(),
+        })
     }
 
-    pub fn get_vehicle_count(&self) -> Result<usize> {
-        self.inner.get_vehicle_count()
+    /// Make, model and trim names of every vehicle, read on the first call (the
+    /// server makes it at startup) and kept for the lifetime of the handle.
+    pub async fn vehicle_names(&self) -> Result<&[VehicleName]> {
+        self.names
+            .get_or_try_init(|| self.list_vehicle_names())
+            .await
+            .map(Vec::as_slice)
     }
 
-    pub fn list_vehicles(&self, params: &ListParams) -> Result<(Vec<VehicleSummary>, usize)> {
-        self.inner.list_vehicles(params)
+    /// Returns the name of the active backend (`sqlite` or `postgresql`).
+    #[must_use]
+    pub fn backend_name(&self) -> &'static str {
+        match self.inner {
+            Backend::Sqlite(_) => "sqlite",
+            Backend::Postgres(_) => "postgresql",
+        }
     }
+}
 
-    pub fn list_makes(&self) -> Result<Vec<MakeSummary>> {
-        self.inner.list_makes()
+impl VehicleRepository for Database {
+    async fn get_vehicle_count(&self) -> Result<usize> {
+        match &self.inner {
+            Backend::Sqlite(db) => VehicleRepository::get_vehicle_count(db).await,
+            Backend::Postgres(db) => VehicleRepository::get_vehicle_count(db).await,
+        }
     }
 
-    pub fn search(
+    async fn list_vehicles(&self, params: &ListParams) -> Result<(Vec<VehicleSummary>, usize)> {
+        match &self.inner {
+            Backend::Sqlite(db) => VehicleRepository::list_vehicles(db, params).await,
+            Backend::Postgres(db) => VehicleRepository::list_vehicles(db, params).await,
+        }
+    }
+
+    async fn list_makes(&self) -> Result<Vec<MakeSummary>> {
+        match &self.inner {
+            Backend::Sqlite(db) => VehicleRepository::list_makes(db).await,
+            Backend::Postgres(db) => VehicleRepository::list_makes(db).await,
+        }
+    }
+
+    async fn search(
         &self,
         query: &str,
         page: usize,
         per_page: usize,
-    ) -> Result<(Vec<VehicleSummary>, usize)> {
-        self.inner.search(query, page, per_page)
+    ) -> Result<(Vec<SearchHit>, usize)> {
+        match &self.inner {
+            Backend::Sqlite(db) => VehicleRepository::search(db, query, page, per_page).await,
+            Backend::Postgres(db) => VehicleRepository::search(db, query, page, per_page).await,
+        }
+    }
+
+    async fn list_vehicle_names(&self) -> Result<Vec<VehicleName>> {
+        match &self.inner {
+            Backend::Sqlite(db) => VehicleRepository::list_vehicle_names(db).await,
+            Backend::Postgres(db) => VehicleRepository::list_vehicle_names(db).await,
+        }
+    }
+
+    async fn get_vehicle_by_code(&self, code: &str) -> Result<Option<Vehicle>> {
+        match &self.inner {
+            Backend::Sqlite(db) => VehicleRepository::get_vehicle_by_code(db, code).await,
+            Backend::Postgres(db) => VehicleRepository::get_vehicle_by_code(db, code).await,
+        }
     }
 
-    pub fn get_vehicle_by_code(&self, code: &str) -> Result<Option<Vehicle>> {
-        self.inner.get_vehicle_by_code(code)
+    async fn list_vehicles_with_ports(&self, connectors: &[ConnectorType]) -> Result<Vec<Vehicle>> {
+        match &self.inner {
+            Backend::Sqlite(db) => {
+                VehicleRepository::list_vehicles_with_ports(db, connectors).await
+            }
+            Backend::Postgres(db) => {
+                VehicleRepository::list_vehicles_with_ports(db, connectors).await
+            }
+        }
+    }
+}
+
+pub(crate) fn build_order_clause(sort_by: &Option<String>, sort_order: &Option<String>) -> String {
+    let valid_columns = [
+        "year",
+        "make_name",
+        "model_name",
+        "range_wltp_km",
+        "range_epa_km",
+        "battery_capacity_net_kwh",
+        "dc_max_power_kw",
+    ];
+    let column = sort_by
+        .as_ref()
+        .filter(|s| valid_columns.contains(&s.as_str()))
+        .map(|s| s.as_str())
+        .unwrap_or("make_slug, model_slug, year");
+
+    let direction = sort_order
+        .as_ref()
+        .filter(|s| s.as_str() == "desc" || s.as_str() == "asc")
+        .map(|s| s.as_str())
+        .unwrap_or("asc");
+
+    format!("ORDER BY {} {}", column, direction.to_uppercase())
+}
+
+/// `LIKE` pattern matching `query` anywhere in a column, with its `%`, `_` and `\`
+/// escaped so they match literally. Use it with `ESCAPE '\'`.
+pub(crate) fn like_pattern(query: &str) -> String {
+    let mut pattern = String::with_capacity(query.len() + 2);
+    pattern.push('%');
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
     }
+    pattern.push('%');
+    pattern
 }
 
 #[derive(Debug, Clone)]
@@ -48,9 +205,26 @@ pub struct ListParams {
     pub make: Option<String>,
     pub model: Option<String>,
     pub year: Option<u16>,
-    pub vehicle_type: Option<String>,
+    pub vehicle_type: Option<VehicleType>,
     pub min_range_km: Option<f64>,
     pub max_range_km: Option<f64>,
+    pub drivetrain: Option<Drivetrain>,
+    /// Matches vehicles with at least one charge port of this connector.
+    pub connector: Option<ConnectorType>,
+    pub min_dc_power_kw: Option<f64>,
+    pub min_ac_power_kw: Option<f64>,
+    pub min_battery_kwh: Option<f64>,
+    pub max_battery_kwh: Option<f64>,
+    pub max_acceleration_0_100_s: Option<f64>,
+    pub v2l: Option<bool>,
+    pub v2h: Option<bool>,
+    pub v2g: Option<bool>,
+    /// ISO 3166-1 alpha-2 country listed in the vehicle's markets.
+    pub market: Option<String>,
+    pub availability: Option<AvailabilityStatus>,
+    /// MSRP bounds; both apply to the same price entry in this currency.
+    pub price: Option<PriceRange>,
+    pub heat_pump: Option<bool>,
     pub page: usize,
     pub per_page: usize,
     pub sort_by: Option<String>,
@@ -66,6 +240,20 @@ impl Default for ListParams {
             vehicle_type: None,
             min_range_km: None,
             max_range_km: None,
+            drivetrain: None,
+            connector: None,
+            min_dc_power_kw: None,
+            min_ac_power_kw: None,
+            min_battery_kwh: None,
+            max_battery_kwh: None,
+            max_acceleration_0_100_s: None,
+            v2l: None,
+            v2h: None,
+            v2g: None,
+            market: None,
+            availability: None,
+            price: None,
+            heat_pump: None,
             page: 1,
             per_page: 20,
             sort_by: None,
@@ -74,10 +262,19 @@ impl Default for ListParams {
     }
 }
 
+/// MSRP filter in a single currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceRange {
+    /// ISO 4217 currency code.
+    pub currency: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 #[schema(example = json!({
     "id": 1,
-    "unique_code": "tesla:model_3:2024:model_3",
+    "unique_code": "tesla:model_3:2024:long_range",
     "make_slug": "tesla",
     "make_name": "Tesla",
     "model_slug": "model_3",
@@ -85,7 +282,7 @@ impl Default for ListParams {
     "year": 2024,
     "trim_name": "Long Range",
     "variant_name": null,
-    "vehicle_type": "bev",
+    "vehicle_type": "passenger_car",
     "battery_capacity_kwh": 82.0,
     "range_wltp_km": 629.0,
     "range_epa_km": 533.0,
@@ -108,6 +305,41 @@ pub struct VehicleSummary {
     pub dc_max_power_kw: Option<f64>,
 }
 
+/// Names of one stored vehicle, the vocabulary for suggestions and query correction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehicleName {
+    pub unique_code: String,
+    pub make_slug: String,
+    pub make_name: String,
+    pub model_slug: String,
+    pub model_name: String,
+    pub year: u16,
+    pub trim_name: String,
+}
+
+/// A search result: the matching vehicle and where the query matched it.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub vehicle: VehicleSummary,
+    /// Matched fields, empty when the backend has no full-text index.
+    pub highlights: Vec<SearchHighlight>,
+}
+
+/// Text of one matched field, HTML-escaped, with the query terms wrapped in
+/// `<mark>` tags.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, utoipa::ToSchema)]
+#[schema(example = json!({
+    "field": "model_name",
+    "snippet": "<mark>Dolphin</mark>"
+}))]
+pub struct SearchHighlight {
+    /// Indexed field: `make_name`, `model_name`, `trim_name`, `variant_name`,
+    /// `body_style`, `platform`, `battery_chemistry` or `markets`.
+    pub field: String,
+    pub snippet: String,
+}
+
 #[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 #[schema(example = json!({
     "slug": "tesla",
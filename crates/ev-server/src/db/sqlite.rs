@@ -1,35 +1,13 @@
 use std::sync::Mutex;
 
 use anyhow::{Context, Result};
-use ev_core::Vehicle;
+use ev_core::{ConnectorType, Vehicle};
 use rusqlite::{Connection, params};
 
-use super::{ListParams, MakeSummary, VehicleSummary};
-
-fn build_order_clause(sort_by: &Option<String>, sort_order: &Option<String>) -> String {
-    let valid_columns = [
-        "year",
-        "make_name",
-        "model_name",
-        "range_wltp_km",
-        "range_epa_km",
-        "battery_capacity_net_kwh",
-        "dc_max_power_kw",
-    ];
-    let column = sort_by
-        .as_ref()
-        .filter(|s| valid_columns.contains(&s.as_str()))
-        .map(|s| s.as_str())
-        .unwrap_or("make_slug, model_slug, year");
-
-    let direction = sort_order
-        .as_ref()
-        .filter(|s| s.as_str() == "desc" || s.as_str() == "asc")
-        .map(|s| s.as_str())
-        .unwrap_or("asc");
-
-    format!("ORDER BY {} {}", column, direction.to_uppercase())
-}
+use super::{
+    ListParams, MakeSummary, SearchHighlight, SearchHit, VehicleName, VehicleRepository,
+    VehicleSummary, build_order_clause, like_pattern,
+};
 
 pub struct SqliteDatabase {
     conn: Mutex<Connection>,
@@ -39,6 +17,9 @@ impl SqliteDatabase {
     pub fn new(path: &str) -> Result<Self> {
         let conn =
             Connection::open(path).with_context(|| format!("Failed to open database: {}", path))?;
+        // SQLite leaves the schema's foreign keys and `ON DELETE CASCADE` unenforced
+        // unless every connection opts in.
+        conn.pragma_update(None, "foreign_keys", true)?;
 
         Ok(Self {
             conn: Mutex::new(conn),
@@ -80,7 +61,7 @@ impl SqliteDatabase {
 
         if let Some(ref vehicle_type) = params.vehicle_type {
             conditions.push("vehicle_type = ?");
-            sql_params.push(Box::new(vehicle_type.clone()));
+            sql_params.push(Box::new(vehicle_type.as_str()));
         }
 
         if let Some(min_range) = params.min_range_km {
@@ -95,6 +76,76 @@ impl SqliteDatabase {
             sql_params.push(Box::new(max_range));
         }
 
+        if let Some(drivetrain) = params.drivetrain {
+            conditions.push("drivetrain = ?");
+            sql_params.push(Box::new(drivetrain.as_str()));
+        }
+
+        if let Some(connector) = params.connector {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM charge_ports WHERE charge_ports.vehicle_id = vehicles.id AND charge_ports.connector = ?)",
+            );
+            sql_params.push(Box::new(connector.as_str()));
+        }
+
+        if let Some(min_dc_power) = params.min_dc_power_kw {
+            conditions.push("dc_max_power_kw >= ?");
+            sql_params.push(Box::new(min_dc_power));
+        }
+
+        if let Some(min_ac_power) = params.min_ac_power_kw {
+            conditions.push("ac_max_power_kw >= ?");
+            sql_params.push(Box::new(min_ac_power));
+        }
+
+        if let Some(min_battery) = params.min_battery_kwh {
+            conditions.push("battery_capacity_net_kwh >= ?");
+            sql_params.push(Box::new(min_battery));
+        }
+
+        if let Some(max_battery) = params.max_battery_kwh {
+            conditions.push("battery_capacity_net_kwh <= ?");
+            sql_params.push(Box::new(max_battery));
+        }
+
+        if let Some(max_acceleration) = params.max_acceleration_0_100_s {
+            conditions.push("acceleration_0_100_s <= ?");
+            sql_params.push(Box::new(max_acceleration));
+        }
+
+        for (column, value) in [
+            ("v2l_supported = ?", params.v2l),
+            ("v2h_supported = ?", params.v2h),
+            ("v2g_supported = ?", params.v2g),
+            ("heat_pump = ?", params.heat_pump),
+        ] {
+            if let Some(value) = value {
+                conditions.push(column);
+                sql_params.push(Box::new(value));
+            }
+        }
+
+        if let Some(ref market) = params.market {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM markets WHERE markets.vehicle_id = vehicles.id AND markets.country = ?)",
+            );
+            sql_params.push(Box::new(market.clone()));
+        }
+
+        if let Some(availability) = params.availability {
+            conditions.push("availability_status = ?");
+            sql_params.push(Box::new(availability.as_str()));
+        }
+
+        if let Some(ref price) = params.price {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM prices WHERE prices.vehicle_id = vehicles.id AND prices.currency = ? AND prices.amount >= ? AND prices.amount <= ?)",
+            );
+            sql_params.push(Box::new(price.currency.clone()));
+            sql_params.push(Box::new(price.min.unwrap_or(0.0)));
+            sql_params.push(Box::new(price.max.unwrap_or(f64::MAX)));
+        }
+
         let where_clause = if conditions.is_empty() {
             String::new()
         } else {
@@ -110,8 +161,8 @@ impl SqliteDatabase {
 
         let order_clause = build_order_clause(&params.sort_by, &params.sort_order);
         let query_sql = format!(
-            "SELECT id, unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, variant_name, vehicle_type, battery_capacity_net_kwh, range_wltp_km, range_epa_km, dc_max_power_kw FROM vehicles {} {} LIMIT ? OFFSET ?",
-            where_clause, order_clause
+            "SELECT {} FROM vehicles {} {} LIMIT ? OFFSET ?",
+            SUMMARY_COLUMNS, where_clause, order_clause
         );
 
         let mut query_params = sql_params;
@@ -123,24 +174,7 @@ impl SqliteDatabase {
         let mut stmt = conn.prepare(&query_sql)?;
 
         let vehicles: Vec<VehicleSummary> = stmt
-            .query_map(params_refs.as_slice(), |row| {
-                Ok(VehicleSummary {
-                    id: row.get(0)?,
-                    unique_code: row.get(1)?,
-                    make_slug: row.get(2)?,
-                    make_name: row.get(3)?,
-                    model_slug: row.get(4)?,
-                    model_name: row.get(5)?,
-                    year: row.get::<_, i32>(6)? as u16,
-                    trim_name: row.get(7)?,
-                    variant_name: row.get(8)?,
-                    vehicle_type: row.get(9)?,
-                    battery_capacity_kwh: row.get(10)?,
-                    range_wltp_km: row.get(11)?,
-                    range_epa_km: row.get(12)?,
-                    dc_max_power_kw: row.get(13)?,
-                })
-            })?
+            .query_map(params_refs.as_slice(), summary_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok((vehicles, total as usize))
@@ -180,62 +214,107 @@ impl SqliteDatabase {
         Ok(makes)
     }
 
+    /// Ranked full-text search over `vehicles_fts`.
+    ///
+    /// Every query token must match the start of a word in the make, model,
+    /// trim, variant, body style, platform, battery chemistry or markets.
+    /// Results are ordered by BM25 with make/model/trim matches weighted
+    /// highest. Databases built without the FTS table fall back to substring
+    /// matching on make, model and trim without highlights.
     pub fn search(
         &self,
         query: &str,
         page: usize,
         per_page: usize,
-    ) -> Result<(Vec<VehicleSummary>, usize)> {
+    ) -> Result<(Vec<SearchHit>, usize)> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
 
-        let search_pattern = format!("%{}%", query.to_lowercase());
+        let offset = (page.saturating_sub(1)) * per_page;
 
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM vehicles WHERE LOWER(make_name) LIKE ? OR LOWER(model_name) LIKE ? OR LOWER(trim_name) LIKE ?",
-            params![&search_pattern, &search_pattern, &search_pattern],
+        let has_fts: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'vehicles_fts')",
+            [],
             |row| row.get(0),
         )?;
+        if !has_fts {
+            return like_search(&conn, query, per_page, offset);
+        }
 
-        let offset = (page.saturating_sub(1)) * per_page;
+        let Some(expression) = fts_match_expression(query) else {
+            return Ok((Vec::new(), 0));
+        };
 
-        let mut stmt = conn.prepare(
-            "SELECT id, unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, variant_name, vehicle_type, battery_capacity_net_kwh, range_wltp_km, range_epa_km, dc_max_power_kw FROM vehicles WHERE LOWER(make_name) LIKE ? OR LOWER(model_name) LIKE ? OR LOWER(trim_name) LIKE ? ORDER BY make_slug, model_slug, year LIMIT ? OFFSET ?",
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM vehicles_fts WHERE vehicles_fts MATCH ?",
+            params![&expression],
+            |row| row.get(0),
         )?;
 
-        let vehicles: Vec<VehicleSummary> = stmt
+        let snippets = (0..FTS_COLUMNS.len())
+            .map(|column| {
+                format!(
+                    "snippet(vehicles_fts, {column}, '{MARK_START}', '{MARK_END}', '…', {SNIPPET_TOKENS})"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SUMMARY_COLUMNS}, {snippets} FROM vehicles_fts JOIN vehicles ON vehicles.id = vehicles_fts.rowid WHERE vehicles_fts MATCH ? ORDER BY bm25(vehicles_fts, {FTS_WEIGHTS}), vehicles.make_slug, vehicles.model_slug, vehicles.year LIMIT ? OFFSET ?"
+        ))?;
+
+        let hits = stmt
             .query_map(
-                params![
-                    &search_pattern,
-                    &search_pattern,
-                    &search_pattern,
-                    per_page as i32,
-                    offset as i32
-                ],
+                params![&expression, per_page as i32, offset as i32],
                 |row| {
-                    Ok(VehicleSummary {
-                        id: row.get(0)?,
-                        unique_code: row.get(1)?,
-                        make_slug: row.get(2)?,
-                        make_name: row.get(3)?,
-                        model_slug: row.get(4)?,
-                        model_name: row.get(5)?,
-                        year: row.get::<_, i32>(6)? as u16,
-                        trim_name: row.get(7)?,
-                        variant_name: row.get(8)?,
-                        vehicle_type: row.get(9)?,
-                        battery_capacity_kwh: row.get(10)?,
-                        range_wltp_km: row.get(11)?,
-                        range_epa_km: row.get(12)?,
-                        dc_max_power_kw: row.get(13)?,
+                    let mut highlights = Vec::new();
+                    for (index, field) in FTS_COLUMNS.iter().enumerate() {
+                        let snippet: Option<String> = row.get(SUMMARY_COLUMN_COUNT + index)?;
+                        if let Some(snippet) = snippet.filter(|s| s.contains(MARK_START)) {
+                            highlights.push(SearchHighlight {
+                                field: (*field).to_string(),
+                                snippet: highlight_html(&snippet),
+                            });
+                        }
+                    }
+                    Ok(SearchHit {
+                        vehicle: summary_from_row(row)?,
+                        highlights,
                     })
                 },
             )?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok((vehicles, count as usize))
+        Ok((hits, count as usize))
+    }
+
+    pub fn list_vehicle_names(&self) -> Result<Vec<VehicleName>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT unique_code, make_slug, make_name, model_slug, model_name, year, trim_name FROM vehicles ORDER BY make_slug, model_slug, year, unique_code",
+        )?;
+
+        let names = stmt
+            .query_map([], |row| {
+                Ok(VehicleName {
+                    unique_code: row.get(0)?,
+                    make_slug: row.get(1)?,
+                    make_name: row.get(2)?,
+                    model_slug: row.get(3)?,
+                    model_name: row.get(4)?,
+                    year: row.get::<_, i32>(5)? as u16,
+                    trim_name: row.get(6)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(names)
     }
 
     pub fn get_vehicle_by_code(&self, code: &str) -> Result<Option<Vehicle>> {
@@ -260,6 +339,186 @@ impl SqliteDatabase {
             None => Ok(None),
         }
     }
+
+    pub fn list_vehicles_with_ports(&self, connectors: &[ConnectorType]) -> Result<Vec<Vehicle>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+
+        let placeholders = vec!["?"; connectors.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT json_data FROM vehicles WHERE id IN (SELECT vehicle_id FROM charge_ports WHERE connector IN ({})) ORDER BY make_slug, model_slug, year, unique_code",
+            placeholders
+        ))?;
+
+        let documents = stmt
+            .query_map(
+                rusqlite::params_from_iter(connectors.iter().map(ConnectorType::as_str)),
+                |row| row.get::<_, String>(0),
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        documents
+            .iter()
+            .map(|json| Ok(serde_json::from_str(json)?))
+            .collect()
+    }
+}
+
+impl VehicleRepository for SqliteDatabase {
+    async fn get_vehicle_count(&self) -> Result<usize> {
+        SqliteDatabase::get_vehicle_count(self)
+    }
+
+    async fn list_vehicles(&self, params: &ListParams) -> Result<(Vec<VehicleSummary>, usize)> {
+        SqliteDatabase::list_vehicles(self, params)
+    }
+
+    async fn list_makes(&self) -> Result<Vec<MakeSummary>> {
+        SqliteDatabase::list_makes(self)
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        page: usize,
+        per_page: usize,
+    ) -> Result<(Vec<SearchHit>, usize)> {
+        SqliteDatabase::search(self, query, page, per_page)
+    }
+
+    async fn list_vehicle_names(&self) -> Result<Vec<VehicleName>> {
+        SqliteDatabase::list_vehicle_names(self)
+    }
+
+    async fn get_vehicle_by_code(&self, code: &str) -> Result<Option<Vehicle>> {
+        SqliteDatabase::get_vehicle_by_code(self, code)
+    }
+
+    async fn list_vehicles_with_ports(&self, connectors: &[ConnectorType]) -> Result<Vec<Vehicle>> {
+        SqliteDatabase::list_vehicles_with_ports(self, connectors)
+    }
+}
+
+/// Columns of `vehicles_fts`, in declaration order.
+const FTS_COLUMNS: [&str; 8] = [
+    "make_name",
+    "model_name",
+    "trim_name",
+    "variant_name",
+    "body_style",
+    "platform",
+    "battery_chemistry",
+    "markets",
+];
+
+/// BM25 weights for [`FTS_COLUMNS`]: identity fields outrank descriptive ones.
+const FTS_WEIGHTS: &str = "10.0, 10.0, 5.0, 5.0, 2.0, 2.0, 1.0, 1.0";
+
+/// Maximum number of tokens in a highlight snippet.
+const SNIPPET_TOKENS: usize = 12;
+
+/// Private-use characters `snippet()` puts around matches, replaced by `<mark>`
+/// tags once the field text is escaped.
+const MARK_START: char = '\u{E000}';
+const MARK_END: char = '\u{E001}';
+
+/// Escapes a `snippet()` result for HTML and turns its match delimiters into
+/// `<mark>` tags.
+fn highlight_html(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len() + 16);
+    for c in snippet.chars() {
+        match c {
+            MARK_START => html.push_str("<mark>"),
+            MARK_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+const SUMMARY_COLUMNS: &str = "vehicles.id, vehicles.unique_code, vehicles.make_slug, vehicles.make_name, vehicles.model_slug, vehicles.model_name, vehicles.year, vehicles.trim_name, vehicles.variant_name, vehicles.vehicle_type, vehicles.battery_capacity_net_kwh, vehicles.range_wltp_km, vehicles.range_epa_km, vehicles.dc_max_power_kw";
+
+const SUMMARY_COLUMN_COUNT: usize = 14;
+
+fn summary_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<VehicleSummary> {
+    Ok(VehicleSummary {
+        id: row.get(0)?,
+        unique_code: row.get(1)?,
+        make_slug: row.get(2)?,
+        make_name: row.get(3)?,
+        model_slug: row.get(4)?,
+        model_name: row.get(5)?,
+        year: row.get::<_, i32>(6)? as u16,
+        trim_name: row.get(7)?,
+        variant_name: row.get(8)?,
+        vehicle_type: row.get(9)?,
+        battery_capacity_kwh: row.get(10)?,
+        range_wltp_km: row.get(11)?,
+        range_epa_km: row.get(12)?,
+        dc_max_power_kw: row.get(13)?,
+    })
+}
+
+/// Turns free text into an FTS5 query where every word must prefix-match.
+///
+/// Words are split the way the `unicode61` tokenizer splits them and quoted,
+/// so FTS5 operators typed by the user are matched literally. Returns `None`
+/// when the query contains no words.
+fn fts_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{word}\"*"))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Substring search on make, model and trim for databases without `vehicles_fts`.
+fn like_search(
+    conn: &Connection,
+    query: &str,
+    per_page: usize,
+    offset: usize,
+) -> Result<(Vec<SearchHit>, usize)> {
+    let search_pattern = like_pattern(&query.to_lowercase());
+
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM vehicles WHERE LOWER(make_name) LIKE ? ESCAPE '\\' OR LOWER(model_name) LIKE ? ESCAPE '\\' OR LOWER(trim_name) LIKE ? ESCAPE '\\'",
+        params![&search_pattern, &search_pattern, &search_pattern],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {SUMMARY_COLUMNS} FROM vehicles WHERE LOWER(make_name) LIKE ? ESCAPE '\\' OR LOWER(model_name) LIKE ? ESCAPE '\\' OR LOWER(trim_name) LIKE ? ESCAPE '\\' ORDER BY make_slug, model_slug, year LIMIT ? OFFSET ?"
+    ))?;
+
+    let hits = stmt
+        .query_map(
+            params![
+                &search_pattern,
+                &search_pattern,
+                &search_pattern,
+                per_page as i32,
+                offset as i32
+            ],
+            |row| {
+                Ok(SearchHit {
+                    vehicle: summary_from_row(row)?,
+                    highlights: Vec::new(),
+                })
+            },
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok((hits, count as usize))
 }
 
 trait OptionalExt<T> {
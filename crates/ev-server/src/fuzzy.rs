@@ -0,0 +1,287 @@
+//! Typo-tolerant matching over the make, model and trim names in the database.
+//!
+//! Names and queries are split into lowercase alphanumeric words. A query word
+//! matches a name word when it is equal, a prefix of it, or within a small edit
+//! distance (optimal string alignment, so swapped letters count once). The
+//! last query word may also fuzzily match the start of a name word, since it
+//! is usually still being typed.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::db::VehicleName;
+
+/// Score of a query word that is a prefix of a name word, relative to an exact match.
+const PREFIX_WEIGHT: f64 = 0.9;
+
+/// Cost of each name word left unmatched by the query, in query words.
+const UNMATCHED_WORD_PENALTY: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    Make,
+    Model,
+    Trim,
+}
+
+/// One autocomplete entry.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[schema(example = json!({
+    "kind": "model",
+    "label": "Tesla Model 3",
+    "make_slug": "tesla",
+    "model_slug": "model_3",
+    "unique_code": null,
+    "score": 0.87
+}))]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    /// Make, make + model, or make + model + trim name.
+    pub label: String,
+    pub make_slug: String,
+    pub model_slug: Option<String>,
+    /// Newest vehicle with this trim; set for trim suggestions only.
+    pub unique_code: Option<String>,
+    /// Match quality between 0 and 1.
+    pub score: f64,
+}
+
+/// Returns up to `limit` make, model and trim suggestions for `query`, best first.
+///
+/// Ties go to makes before models before trims, then to the label.
+#[must_use]
+pub fn suggest(names: &[VehicleName], query: &str, limit: usize) -> Vec<Suggestion> {
+    let query_words = words(query);
+    if query_words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut suggestions: Vec<Suggestion> = candidates(names)
+        .into_iter()
+        .filter_map(|mut candidate| {
+            candidate.score = score(&query_words, &words(&candidate.label))?;
+            Some(candidate)
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.kind.cmp(&b.kind))
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Rewrites `query` with each unknown word replaced by the closest known name word.
+///
+/// Words that equal or start a known word are kept. Returns `None` when no
+/// word could be corrected.
+#[must_use]
+pub fn correct_query(names: &[VehicleName], query: &str) -> Option<String> {
+    let vocabulary: BTreeSet<String> = names
+        .iter()
+        .flat_map(|name| {
+            words(&format!(
+                "{} {} {}",
+                name.make_name, name.model_name, name.trim_name
+            ))
+        })
+        .collect();
+
+    let mut corrected = false;
+    let query_words: Vec<String> = words(query)
+        .into_iter()
+        .map(|word| {
+            if vocabulary.iter().any(|known| known.starts_with(&word)) {
+                return word;
+            }
+            let allowed = max_typos(&word);
+            let closest = vocabulary
+                .iter()
+                .map(|known| (edit_distance(&word, known), known))
+                .filter(|(distance, _)| *distance <= allowed)
+                .min_by_key(|(distance, _)| *distance);
+            match closest {
+                Some((_, known)) => {
+                    corrected = true;
+                    known.clone()
+                }
+                None => word,
+            }
+        })
+        .collect();
+
+    corrected.then(|| query_words.join(" "))
+}
+
+/// Optimal string alignment distance between `a` and `b`, counted in characters.
+///
+/// Like Levenshtein distance, but swapping two adjacent characters costs one edit.
+#[must_use]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Edits tolerated for a query word: none up to 2 characters, one up to 5, then two.
+fn max_typos(word: &str) -> usize {
+    match word.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// One suggestion per distinct make, model and trim; trims point at their newest vehicle.
+fn candidates(names: &[VehicleName]) -> Vec<Suggestion> {
+    let mut makes = BTreeMap::new();
+    let mut models = BTreeMap::new();
+    let mut trims: BTreeMap<(&str, &str, &str), (&VehicleName, Suggestion)> = BTreeMap::new();
+
+    for name in names {
+        makes
+            .entry(name.make_slug.as_str())
+            .or_insert_with(|| Suggestion {
+                kind: SuggestionKind::Make,
+                label: name.make_name.clone(),
+                make_slug: name.make_slug.clone(),
+                model_slug: None,
+                unique_code: None,
+                score: 0.0,
+            });
+
+        models
+            .entry((name.make_slug.as_str(), name.model_slug.as_str()))
+            .or_insert_with(|| Suggestion {
+                kind: SuggestionKind::Model,
+                label: format!("{} {}", name.make_name, name.model_name),
+                make_slug: name.make_slug.clone(),
+                model_slug: Some(name.model_slug.clone()),
+                unique_code: None,
+                score: 0.0,
+            });
+
+        let key = (
+            name.make_slug.as_str(),
+            name.model_slug.as_str(),
+            name.trim_name.as_str(),
+        );
+        let newer = trims.get(&key).is_none_or(|(newest, _)| {
+            (name.year, std::cmp::Reverse(name.unique_code.len()))
+                > (newest.year, std::cmp::Reverse(newest.unique_code.len()))
+        });
+        if newer {
+            let suggestion = Suggestion {
+                kind: SuggestionKind::Trim,
+                label: format!("{} {} {}", name.make_name, name.model_name, name.trim_name),
+                make_slug: name.make_slug.clone(),
+                model_slug: Some(name.model_slug.clone()),
+                unique_code: Some(name.unique_code.clone()),
+                score: 0.0,
+            };
+            trims.insert(key, (name, suggestion));
+        }
+    }
+
+    makes
+        .into_values()
+        .chain(models.into_values())
+        .chain(trims.into_values().map(|(_, suggestion)| suggestion))
+        .collect()
+}
+
+/// Scores `label` against the query, or `None` when a query word matches no label word.
+fn score(query: &[String], label: &[String]) -> Option<f64> {
+    let mut matched = vec![false; label.len()];
+    let mut total = 0.0;
+
+    for (index, word) in query.iter().enumerate() {
+        let typing = index == query.len() - 1;
+        let (position, similarity) = label
+            .iter()
+            .enumerate()
+            .filter_map(|(position, candidate)| {
+                word_similarity(word, candidate, typing).map(|s| (position, s))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+        matched[position] = true;
+        total += similarity;
+    }
+
+    let unmatched = matched.iter().filter(|m| !**m).count() as f64;
+    Some(total / (query.len() as f64 + UNMATCHED_WORD_PENALTY * unmatched))
+}
+
+fn word_similarity(query: &str, candidate: &str, typing: bool) -> Option<f64> {
+    if query == candidate {
+        return Some(1.0);
+    }
+    if candidate.starts_with(query) {
+        return Some(PREFIX_WEIGHT);
+    }
+
+    let allowed = max_typos(query);
+    if allowed == 0 {
+        return None;
+    }
+
+    let query_len = query.chars().count();
+    let candidate_len = candidate.chars().count();
+    let distance = edit_distance(query, candidate);
+    let full =
+        (distance <= allowed).then(|| 1.0 - distance as f64 / query_len.max(candidate_len) as f64);
+
+    let prefix = typing
+        .then(|| {
+            (query_len.saturating_sub(1)..=query_len + 1)
+                .filter(|&len| len > 0 && len < candidate_len)
+                .map(|len| {
+                    let start: String = candidate.chars().take(len).collect();
+                    edit_distance(query, &start)
+                })
+                .min()
+                .filter(|&distance| distance <= allowed)
+                .map(|distance| PREFIX_WEIGHT * (1.0 - distance as f64 / query_len as f64))
+        })
+        .flatten();
+
+    match (full, prefix) {
+        (Some(full), Some(prefix)) => Some(full.max(prefix)),
+        (full, prefix) => full.or(prefix),
+    }
+}
@@ -2,6 +2,7 @@ pub mod api;
 pub mod config;
 pub mod db;
 pub mod error;
+pub mod fuzzy;
 pub mod models;
 
 pub use config::Config;
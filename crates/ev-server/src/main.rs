@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use tokio::net::TcpListener;
-use tracing::{Level, info};
+use tracing::{Level, info, warn};
 use tracing_subscriber::FmtSubscriber;
 
 use ev_server::{api, config::Config, db::Database};
@@ -31,7 +31,16 @@ async fn main() -> Result<()> {
     let database = Database::new(&config.database_url).context("Failed to connect to database")?;
     let db = Arc::new(database);
 
-    info!("Connected to database: {}", config.database_url);
+    info!("Connected to {} database", db.backend_name());
+
+    // Failures are retried by the first search or suggest request.
+    match db.vehicle_names().await {
+        Ok(names) => info!(
+            "Loaded {} vehicle names for search suggestions",
+            names.len()
+        ),
+        Err(e) => warn!("Failed to load vehicle names: {:#}", e),
+    }
 
     let app = api::create_router(db, &config);
 
@@ -1,7 +1,9 @@
+use ev_core::{ChargerSpec, Comparison, Compatibility, Vehicle, VehicleDiff};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::db::VehicleSummary;
+use crate::db::{SearchHit, VehicleSummary};
+use crate::fuzzy::Suggestion;
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[allow(dead_code)]
@@ -49,6 +51,22 @@ pub struct VehicleListQuery {
     pub vehicle_type: Option<String>,
     pub min_range_km: Option<f64>,
     pub max_range_km: Option<f64>,
+    pub drivetrain: Option<String>,
+    pub connector: Option<String>,
+    pub min_dc_power_kw: Option<f64>,
+    pub min_ac_power_kw: Option<f64>,
+    pub min_battery_kwh: Option<f64>,
+    pub max_battery_kwh: Option<f64>,
+    pub max_acceleration_0_100_s: Option<f64>,
+    pub v2l: Option<bool>,
+    pub v2h: Option<bool>,
+    pub v2g: Option<bool>,
+    pub market: Option<String>,
+    pub availability: Option<String>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub currency: Option<String>,
+    pub heat_pump: Option<bool>,
     #[serde(default = "default_page")]
     pub page: usize,
     #[serde(default = "default_per_page")]
@@ -69,7 +87,7 @@ fn default_per_page() -> usize {
 #[schema(example = json!({
     "vehicles": [{
         "id": 1,
-        "unique_code": "tesla:model_3:2024:model_3",
+        "unique_code": "tesla:model_3:2024:long_range",
         "make_slug": "tesla",
         "make_name": "Tesla",
         "model_slug": "model_3",
@@ -77,7 +95,7 @@ fn default_per_page() -> usize {
         "year": 2024,
         "trim_name": "Long Range",
         "variant_name": null,
-        "vehicle_type": "bev",
+        "vehicle_type": "passenger_car",
         "battery_capacity_kwh": 82.0,
         "range_wltp_km": 629.0,
         "range_epa_km": 533.0,
@@ -126,7 +144,7 @@ pub struct SearchQuery {
 #[schema(example = json!({
     "results": [{
         "id": 5,
-        "unique_code": "byd:dolphin:2024:dolphin",
+        "unique_code": "byd:dolphin:2024:standard",
         "make_slug": "byd",
         "make_name": "BYD",
         "model_slug": "dolphin",
@@ -134,11 +152,14 @@ pub struct SearchQuery {
         "year": 2024,
         "trim_name": "Standard",
         "variant_name": null,
-        "vehicle_type": "bev",
+        "vehicle_type": "passenger_car",
         "battery_capacity_kwh": 44.9,
         "range_wltp_km": 340.0,
         "range_epa_km": null,
-        "dc_max_power_kw": 60.0
+        "dc_max_power_kw": 60.0,
+        "highlights": [
+            {"field": "model_name", "snippet": "<mark>Dolphin</mark>"}
+        ]
     }],
     "pagination": {
         "page": 1,
@@ -148,7 +169,146 @@ pub struct SearchQuery {
     }
 }))]
 pub struct SearchResponse {
-    pub results: Vec<VehicleSummary>,
+    pub results: Vec<SearchHit>,
+    pub pagination: Pagination,
+    /// Spelling-corrected query the results were found with, when the original query matched nothing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corrected_query: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SuggestQuery {
+    pub q: String,
+    #[serde(default = "default_suggest_limit")]
+    pub limit: usize,
+}
+
+fn default_suggest_limit() -> usize {
+    10
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "suggestions": [{
+        "kind": "model",
+        "label": "Tesla Model 3",
+        "make_slug": "tesla",
+        "model_slug": "model_3",
+        "unique_code": null,
+        "score": 0.87
+    }, {
+        "kind": "trim",
+        "label": "Tesla Model 3 Long Range",
+        "make_slug": "tesla",
+        "model_slug": "model_3",
+        "unique_code": "tesla:model_3:2024:long_range",
+        "score": 0.8
+    }]
+}))]
+pub struct SuggestResponse {
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CompareQuery {
+    /// Comma-separated unique codes.
+    pub codes: String,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "vehicles": [{"unique_code": "byd:dolphin:2024:comfort"}, {"unique_code": "mg:mg4:2024:standard"}],
+    "metrics": [{
+        "key": "range_wltp_km",
+        "label": "WLTP range",
+        "unit": "km",
+        "better": "higher",
+        "values": [
+            {"value": 427.0, "best": true, "worst": false, "delta_to_best": 0.0, "delta_to_best_percent": 0.0, "delta_to_baseline": 0.0},
+            {"value": 350.0, "best": false, "worst": true, "delta_to_best": -77.0, "delta_to_best_percent": -18.03, "delta_to_baseline": -77.0}
+        ]
+    }],
+    "fields": [{
+        "path": "/battery/chemistry",
+        "values": ["LFP", "LFP"],
+        "differs": false
+    }]
+}))]
+pub struct CompareResponse {
+    /// Full vehicle documents, in the order of `codes`.
+    pub vehicles: Vec<Vehicle>,
+    #[serde(flatten)]
+    pub comparison: Comparison,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "from": "tesla:model_3:2023:long_range",
+    "to": "tesla:model_3:2024:long_range",
+    "changes": [
+        {"path": "/charging/dc/max_power_kw", "kind": "changed", "old": 170.0, "new": 250.0},
+        {"path": "/unique_code", "kind": "changed", "old": "tesla:model_3:2023:long_range", "new": "tesla:model_3:2024:long_range"},
+        {"path": "/v2x", "kind": "added", "old": null, "new": {"v2l": {"supported": true}}},
+        {"path": "/year", "kind": "changed", "old": 2023, "new": 2024}
+    ]
+}))]
+pub struct VehicleDiffResponse {
+    pub from: String,
+    pub to: String,
+    #[serde(flatten)]
+    pub diff: VehicleDiff,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CompatibleVehiclesQuery {
+    pub connector: String,
+    pub max_power_kw: f64,
+    pub current: Option<String>,
+    pub voltage_class: Option<String>,
+    #[serde(default = "default_adapters")]
+    pub adapters: bool,
+    #[serde(default = "default_page")]
+    pub page: usize,
+    #[serde(default = "default_per_page")]
+    pub per_page: usize,
+}
+
+fn default_adapters() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompatibleVehicle {
+    pub unique_code: String,
+    pub make_name: String,
+    pub model_name: String,
+    pub year: u16,
+    pub trim_name: String,
+    pub compatibility: Compatibility,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "charger": {"connector": "ccs2", "current": "dc", "max_power_kw": 350.0, "voltage_class": "400v"},
+    "vehicles": [{
+        "unique_code": "hyundai:ioniq_5:2024:long_range",
+        "make_name": "Hyundai",
+        "model_name": "IONIQ 5",
+        "year": 2024,
+        "trim_name": "Long Range",
+        "compatibility": {"port": "ccs2", "max_power_kw": 125.0, "limited_by": "voltage_class"}
+    }],
+    "pagination": {
+        "page": 1,
+        "per_page": 20,
+        "total": 1,
+        "total_pages": 1
+    }
+}))]
+pub struct CompatibleVehiclesResponse {
+    pub charger: ChargerSpec,
+    pub vehicles: Vec<CompatibleVehicle>,
     pub pagination: Pagination,
 }
 
@@ -45,9 +45,19 @@ CREATE TABLE IF NOT EXISTS vehicles (
     acceleration_0_100_s DECIMAL(5,2),
     top_speed_kmh DECIMAL(10,2),
     
+    -- Features and availability (NULL when unknown)
+    heat_pump BOOLEAN,
+    v2l_supported BOOLEAN,
+    v2h_supported BOOLEAN,
+    v2g_supported BOOLEAN,
+    availability_status VARCHAR(50),
+    
     -- Full JSON data (JSONB for querying)
     json_data JSONB NOT NULL,
     
+    -- Full-text search document, maintained by vehicles_search_trigger
+    search_vector tsvector,
+    
     -- Metadata
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
@@ -62,6 +72,40 @@ CREATE TABLE IF NOT EXISTS charge_ports (
     location_position VARCHAR(50)
 );
 
+-- Motors table
+CREATE TABLE IF NOT EXISTS motors (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    position VARCHAR(50) NOT NULL,
+    motor_type VARCHAR(100),
+    power_kw DECIMAL(10,2),
+    torque_nm DECIMAL(10,2),
+    cooling VARCHAR(100)
+);
+
+-- DC charge curve points table
+CREATE TABLE IF NOT EXISTS charge_curve_points (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    curve_type VARCHAR(50) NOT NULL,
+    soc_percent DECIMAL(5,2) NOT NULL,
+    power_kw DECIMAL(10,2),
+    current_a DECIMAL(10,2),
+    voltage_v DECIMAL(10,2)
+);
+
+-- Charging time entries, AC and DC (power_kw is the charger power for DC)
+CREATE TABLE IF NOT EXISTS charging_times (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    current VARCHAR(2) NOT NULL,
+    power_kw DECIMAL(10,2) NOT NULL,
+    from_soc_percent DECIMAL(5,2) NOT NULL,
+    to_soc_percent DECIMAL(5,2) NOT NULL,
+    time_min DECIMAL(10,2) NOT NULL,
+    notes TEXT
+);
+
 -- Range ratings table
 CREATE TABLE IF NOT EXISTS range_ratings (
     id SERIAL PRIMARY KEY,
@@ -71,6 +115,67 @@ CREATE TABLE IF NOT EXISTS range_ratings (
     notes TEXT
 );
 
+-- Real-world ranges table
+CREATE TABLE IF NOT EXISTS real_world_ranges (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    profile VARCHAR(50) NOT NULL,
+    range_km DECIMAL(10,2) NOT NULL,
+    weather VARCHAR(100),
+    speed_kmh DECIMAL(10,2),
+    notes TEXT
+);
+
+-- V2L/V2H/V2G capabilities, one row per mode (protocols comma-separated)
+CREATE TABLE IF NOT EXISTS v2x_capabilities (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    mode VARCHAR(3) NOT NULL,
+    supported BOOLEAN NOT NULL,
+    max_power_kw DECIMAL(10,2),
+    connector VARCHAR(100),
+    protocols TEXT,
+    notes TEXT
+);
+
+-- Dimensions table (one-to-one)
+CREATE TABLE IF NOT EXISTS dimensions (
+    vehicle_id INTEGER PRIMARY KEY REFERENCES vehicles(id) ON DELETE CASCADE,
+    length_mm DECIMAL(10,2),
+    width_mm DECIMAL(10,2),
+    width_with_mirrors_mm DECIMAL(10,2),
+    height_mm DECIMAL(10,2),
+    wheelbase_mm DECIMAL(10,2),
+    ground_clearance_mm DECIMAL(10,2),
+    turning_circle_m DECIMAL(10,2)
+);
+
+-- Weights table (one-to-one)
+CREATE TABLE IF NOT EXISTS weights (
+    vehicle_id INTEGER PRIMARY KEY REFERENCES vehicles(id) ON DELETE CASCADE,
+    curb_weight_kg DECIMAL(10,2),
+    gross_vehicle_weight_kg DECIMAL(10,2),
+    max_payload_kg DECIMAL(10,2),
+    roof_load_kg DECIMAL(10,2)
+);
+
+-- Markets table
+CREATE TABLE IF NOT EXISTS markets (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    country CHAR(2) NOT NULL
+);
+
+-- MSRP prices table
+CREATE TABLE IF NOT EXISTS prices (
+    id SERIAL PRIMARY KEY,
+    vehicle_id INTEGER NOT NULL REFERENCES vehicles(id) ON DELETE CASCADE,
+    currency CHAR(3) NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    country CHAR(2),
+    year INTEGER
+);
+
 -- Data sources table
 CREATE TABLE IF NOT EXISTS sources (
     id SERIAL PRIMARY KEY,
@@ -90,6 +195,17 @@ CREATE INDEX IF NOT EXISTS idx_vehicles_composite ON vehicles(make_slug, model_s
 CREATE INDEX IF NOT EXISTS idx_vehicles_type ON vehicles(vehicle_type);
 CREATE INDEX IF NOT EXISTS idx_vehicles_unique_code ON vehicles(unique_code);
 
+-- Indexes for /vehicles/list filters
+CREATE INDEX IF NOT EXISTS idx_vehicles_drivetrain ON vehicles(drivetrain);
+CREATE INDEX IF NOT EXISTS idx_vehicles_dc_power ON vehicles(dc_max_power_kw);
+CREATE INDEX IF NOT EXISTS idx_vehicles_ac_power ON vehicles(ac_max_power_kw);
+CREATE INDEX IF NOT EXISTS idx_vehicles_battery ON vehicles(battery_capacity_net_kwh);
+CREATE INDEX IF NOT EXISTS idx_vehicles_acceleration ON vehicles(acceleration_0_100_s);
+CREATE INDEX IF NOT EXISTS idx_vehicles_availability ON vehicles(availability_status);
+CREATE INDEX IF NOT EXISTS idx_charge_ports_connector ON charge_ports(connector, vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_markets_country ON markets(country, vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_prices_currency ON prices(currency, amount);
+
 -- GIN index for JSONB queries
 CREATE INDEX IF NOT EXISTS idx_vehicles_json ON vehicles USING GIN (json_data);
 
@@ -99,12 +215,17 @@ CREATE INDEX IF NOT EXISTS idx_vehicles_electric ON vehicles(battery_capacity_ne
 
 -- Foreign key indexes
 CREATE INDEX IF NOT EXISTS idx_charge_ports_vehicle ON charge_ports(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_motors_vehicle ON motors(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_charge_curve_points_vehicle ON charge_curve_points(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_charging_times_vehicle ON charging_times(vehicle_id);
 CREATE INDEX IF NOT EXISTS idx_range_ratings_vehicle ON range_ratings(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_real_world_ranges_vehicle ON real_world_ranges(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_v2x_capabilities_vehicle ON v2x_capabilities(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_markets_vehicle ON markets(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_prices_vehicle ON prices(vehicle_id);
 CREATE INDEX IF NOT EXISTS idx_sources_vehicle ON sources(vehicle_id);
 
 -- Full-text search using tsvector
-ALTER TABLE vehicles ADD COLUMN IF NOT EXISTS search_vector tsvector;
-
 CREATE OR REPLACE FUNCTION vehicles_search_trigger() RETURNS trigger AS $$
 BEGIN
     NEW.search_vector := 
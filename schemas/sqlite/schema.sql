@@ -1,6 +1,9 @@
 -- OpenEV Data SQLite Schema
 -- Reference schema for SQLite database output
 -- Generated by ev-etl
+--
+-- Foreign keys, and their ON DELETE CASCADE, are only enforced on connections
+-- that run PRAGMA foreign_keys = ON, as ev-etl and ev-server do.
 
 -- Main vehicles table with denormalized key attributes
 CREATE TABLE IF NOT EXISTS vehicles (
@@ -43,6 +46,13 @@ CREATE TABLE IF NOT EXISTS vehicles (
     acceleration_0_100_s REAL,
     top_speed_kmh REAL,
     
+    -- Features and availability (booleans as 0/1, NULL when unknown)
+    heat_pump INTEGER,
+    v2l_supported INTEGER,
+    v2h_supported INTEGER,
+    v2g_supported INTEGER,
+    availability_status TEXT,
+    
     -- Full JSON data
     json_data TEXT NOT NULL,
     
@@ -61,6 +71,43 @@ CREATE TABLE IF NOT EXISTS charge_ports (
     FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
 );
 
+-- Motors (normalized)
+CREATE TABLE IF NOT EXISTS motors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    position TEXT NOT NULL,
+    motor_type TEXT,
+    power_kw REAL,
+    torque_nm REAL,
+    cooling TEXT,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- DC charge curve points (normalized)
+CREATE TABLE IF NOT EXISTS charge_curve_points (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    curve_type TEXT NOT NULL,
+    soc_percent REAL NOT NULL,
+    power_kw REAL,
+    current_a REAL,
+    voltage_v REAL,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- Charging time entries, AC and DC (normalized; power_kw is the charger power for DC)
+CREATE TABLE IF NOT EXISTS charging_times (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    current TEXT NOT NULL,
+    power_kw REAL NOT NULL,
+    from_soc_percent REAL NOT NULL,
+    to_soc_percent REAL NOT NULL,
+    time_min REAL NOT NULL,
+    notes TEXT,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
 -- Range ratings (normalized)
 CREATE TABLE IF NOT EXISTS range_ratings (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -71,6 +118,73 @@ CREATE TABLE IF NOT EXISTS range_ratings (
     FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
 );
 
+-- Real-world ranges (normalized)
+CREATE TABLE IF NOT EXISTS real_world_ranges (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    profile TEXT NOT NULL,
+    range_km REAL NOT NULL,
+    weather TEXT,
+    speed_kmh REAL,
+    notes TEXT,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- V2L/V2H/V2G capabilities, one row per mode (protocols comma-separated)
+CREATE TABLE IF NOT EXISTS v2x_capabilities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    mode TEXT NOT NULL,
+    supported INTEGER NOT NULL,
+    max_power_kw REAL,
+    connector TEXT,
+    protocols TEXT,
+    notes TEXT,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- Dimensions (one-to-one)
+CREATE TABLE IF NOT EXISTS dimensions (
+    vehicle_id INTEGER PRIMARY KEY,
+    length_mm REAL,
+    width_mm REAL,
+    width_with_mirrors_mm REAL,
+    height_mm REAL,
+    wheelbase_mm REAL,
+    ground_clearance_mm REAL,
+    turning_circle_m REAL,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- Weights (one-to-one)
+CREATE TABLE IF NOT EXISTS weights (
+    vehicle_id INTEGER PRIMARY KEY,
+    curb_weight_kg REAL,
+    gross_vehicle_weight_kg REAL,
+    max_payload_kg REAL,
+    roof_load_kg REAL,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- Markets the vehicle is sold in (normalized)
+CREATE TABLE IF NOT EXISTS markets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    country TEXT NOT NULL,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
+-- MSRP entries (normalized)
+CREATE TABLE IF NOT EXISTS prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vehicle_id INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    amount REAL NOT NULL,
+    country TEXT,
+    year INTEGER,
+    FOREIGN KEY (vehicle_id) REFERENCES vehicles(id) ON DELETE CASCADE
+);
+
 -- Data sources (normalized)
 CREATE TABLE IF NOT EXISTS sources (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -91,15 +205,40 @@ CREATE INDEX IF NOT EXISTS idx_vehicles_composite ON vehicles(make_slug, model_s
 CREATE INDEX IF NOT EXISTS idx_vehicles_type ON vehicles(vehicle_type);
 CREATE INDEX IF NOT EXISTS idx_vehicles_unique_code ON vehicles(unique_code);
 
+-- Indexes for /vehicles/list filters
+CREATE INDEX IF NOT EXISTS idx_vehicles_drivetrain ON vehicles(drivetrain);
+CREATE INDEX IF NOT EXISTS idx_vehicles_dc_power ON vehicles(dc_max_power_kw);
+CREATE INDEX IF NOT EXISTS idx_vehicles_ac_power ON vehicles(ac_max_power_kw);
+CREATE INDEX IF NOT EXISTS idx_vehicles_battery ON vehicles(battery_capacity_net_kwh);
+CREATE INDEX IF NOT EXISTS idx_vehicles_acceleration ON vehicles(acceleration_0_100_s);
+CREATE INDEX IF NOT EXISTS idx_vehicles_availability ON vehicles(availability_status);
+CREATE INDEX IF NOT EXISTS idx_charge_ports_connector ON charge_ports(connector, vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_markets_country ON markets(country, vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_prices_currency ON prices(currency, amount);
+
+-- Foreign key indexes
 CREATE INDEX IF NOT EXISTS idx_charge_ports_vehicle ON charge_ports(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_motors_vehicle ON motors(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_charge_curve_points_vehicle ON charge_curve_points(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_charging_times_vehicle ON charging_times(vehicle_id);
 CREATE INDEX IF NOT EXISTS idx_range_ratings_vehicle ON range_ratings(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_real_world_ranges_vehicle ON real_world_ranges(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_v2x_capabilities_vehicle ON v2x_capabilities(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_markets_vehicle ON markets(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_prices_vehicle ON prices(vehicle_id);
 CREATE INDEX IF NOT EXISTS idx_sources_vehicle ON sources(vehicle_id);
 
--- Full-text search (SQLite FTS5)
--- CREATE VIRTUAL TABLE IF NOT EXISTS vehicles_fts USING fts5(
---     make_name,
---     model_name,
---     trim_name,
---     content=vehicles,
---     content_rowid=id
--- );
+-- Full-text search (SQLite FTS5), one row per vehicle keyed by vehicles.id.
+-- markets holds the space-separated ISO country codes.
+CREATE VIRTUAL TABLE IF NOT EXISTS vehicles_fts USING fts5(
+    make_name,
+    model_name,
+    trim_name,
+    variant_name,
+    body_style,
+    platform,
+    battery_chemistry,
+    markets,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
@@ -8,6 +8,7 @@ use ev_core::{
     Powertrain, Range, RangeCycle, RangeRated, SlugName, Source, SourceType, Variant, Vehicle,
     VehicleType,
 };
+use serde_json::Value;
 use std::path::PathBuf;
 
 /// Returns the path to the fixtures directory.
@@ -31,6 +32,7 @@ pub fn sample_vehicle_minimal_json_path() -> PathBuf {
 /// Creates a complete test vehicle with all fields populated.
 pub fn create_complete_vehicle() -> Vehicle {
     Vehicle {
+        schema_url: None,
         schema_version: "1.0.0".to_string(),
         make: SlugName {
             slug: "tesla".to_string(),
@@ -156,6 +158,7 @@ pub fn create_complete_vehicle() -> Vehicle {
 /// Creates a minimal test vehicle with only required fields.
 pub fn create_minimal_vehicle() -> Vehicle {
     Vehicle {
+        schema_url: None,
         schema_version: "1.0.0".to_string(),
         make: SlugName {
             slug: "byd".to_string(),
@@ -250,6 +253,20 @@ pub const SAMPLE_VEHICLE_JSON: &str = r#"{
     "sources": [{"type": "oem", "title": "Tesla", "url": "https://tesla.com", "accessed_at": "2024-01-01"}]
 }"#;
 
+/// [`SAMPLE_VEHICLE_JSON`] with `overrides` replacing its top-level members.
+pub fn sample_vehicle_value(overrides: Value) -> Value {
+    let mut vehicle: Value = serde_json::from_str(SAMPLE_VEHICLE_JSON).unwrap();
+    for (key, value) in overrides.as_object().expect("overrides must be an object") {
+        vehicle[key] = value.clone();
+    }
+    vehicle
+}
+
+/// Creates the vehicle of [`sample_vehicle_value`].
+pub fn sample_vehicle_with(overrides: Value) -> Vehicle {
+    serde_json::from_value(sample_vehicle_value(overrides)).unwrap()
+}
+
 /// Sample invalid vehicle JSON for testing error paths.
 pub const INVALID_VEHICLE_JSON: &str = r#"{
     "schema_version": "1.0.0",
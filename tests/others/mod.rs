@@ -1,2 +1,4 @@
 // Other tests placeholder - benchmarks, utilities, fixtures
 // See docs/TESTING.md for guidelines
+
+pub mod fixtures;
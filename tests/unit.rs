@@ -6,3 +6,8 @@ mod ev_etl;
 
 #[path = "unit/ev-server/mod.rs"]
 mod ev_server;
+
+// Shared fixtures; not every helper is used by this target.
+#[allow(dead_code)]
+#[path = "others/mod.rs"]
+mod others;
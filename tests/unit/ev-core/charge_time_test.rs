@@ -0,0 +1,220 @@
+use ev_core::{
+    AcSupply, ChargingAc, EstimateBasis, ValidationError, Vehicle, VoltageRange, ac_charge_power,
+    estimate_ac_charge, estimate_dc_charge,
+};
+use serde_json::{Value, json};
+
+use crate::others::fixtures::sample_vehicle_with;
+
+fn vehicle_with_charging(charging: Value) -> Vehicle {
+    sample_vehicle_with(json!({"charging": charging}))
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_estimate_dc_charge_flat_curve() {
+    let vehicle = vehicle_with_charging(json!({
+        "dc": {"max_power_kw": 150.0},
+        "dc_charge_curve": {
+            "curve_type": "power_by_soc",
+            "points": [{"soc_percent": 0.0, "power_kw": 120.0}, {"soc_percent": 100.0, "power_kw": 120.0}]
+        }
+    }));
+
+    let estimate = estimate_dc_charge(&vehicle, 350.0, 10.0, 80.0).unwrap();
+
+    assert_eq!(estimate.basis, EstimateBasis::ChargeCurve);
+    assert_close(estimate.energy_kwh, 42.0);
+    assert_close(estimate.duration_min, 21.0);
+    assert_close(estimate.average_power_kw, 120.0);
+}
+
+#[test]
+fn test_estimate_dc_charge_curve_capped_by_charger() {
+    let vehicle = vehicle_with_charging(json!({
+        "dc": {"max_power_kw": 250.0},
+        "dc_charge_curve": {
+            "curve_type": "power_by_soc",
+            "points": [{"soc_percent": 0.0, "power_kw": 250.0}, {"soc_percent": 50.0, "power_kw": 250.0}, {"soc_percent": 100.0, "power_kw": 50.0}]
+        }
+    }));
+
+    let estimate = estimate_dc_charge(&vehicle, 50.0, 20.0, 80.0).unwrap();
+    assert_close(estimate.duration_min, 36.0 / 50.0 * 60.0);
+
+    let fast = estimate_dc_charge(&vehicle, 350.0, 20.0, 80.0).unwrap();
+    assert!(fast.duration_min < estimate.duration_min);
+    assert!(fast.average_power_kw < 250.0);
+}
+
+#[test]
+fn test_estimate_dc_charge_tapering_curve() {
+    let vehicle = vehicle_with_charging(json!({
+        "dc": {"max_power_kw": 200.0},
+        "dc_charge_curve": {
+            "curve_type": "power_by_soc",
+            "points": [{"soc_percent": 0.0, "power_kw": 200.0}, {"soc_percent": 100.0, "power_kw": 100.0}]
+        }
+    }));
+
+    let estimate = estimate_dc_charge(&vehicle, 350.0, 0.0, 100.0).unwrap();
+
+    // 60 kWh over a power falling linearly from 200 to 100 kW: 0.6 h * ln(2).
+    assert!((estimate.duration_min - 0.6 * 2f64.ln() * 60.0).abs() < 0.01);
+}
+
+#[test]
+fn test_estimate_dc_charge_falls_back_to_charging_time() {
+    let vehicle = vehicle_with_charging(json!({
+        "dc": {"max_power_kw": 170.0},
+        "charging_time": {"dc": [
+            {"charger_power_kw": 50.0, "from_soc_percent": 10.0, "to_soc_percent": 80.0, "time_min": 60.0},
+            {"charger_power_kw": 250.0, "from_soc_percent": 10.0, "to_soc_percent": 80.0, "time_min": 30.0}
+        ]}
+    }));
+
+    let estimate = estimate_dc_charge(&vehicle, 300.0, 20.0, 50.0).unwrap();
+
+    assert_eq!(estimate.basis, EstimateBasis::ChargingTime);
+    assert_close(estimate.average_power_kw, 84.0);
+    assert_close(estimate.duration_min, 18.0 / 84.0 * 60.0);
+}
+
+#[test]
+fn test_estimate_dc_charge_falls_back_to_max_power() {
+    let vehicle = vehicle_with_charging(json!({"dc": {"max_power_kw": 100.0}}));
+
+    let estimate = estimate_dc_charge(&vehicle, 50.0, 0.0, 50.0).unwrap();
+
+    assert_eq!(estimate.basis, EstimateBasis::MaxPower);
+    assert_close(estimate.average_power_kw, 50.0);
+    assert_close(estimate.duration_min, 36.0);
+}
+
+#[test]
+fn test_estimate_dc_charge_without_dc() {
+    let vehicle = vehicle_with_charging(json!({"ac": {"max_power_kw": 11.0}}));
+
+    assert_eq!(
+        estimate_dc_charge(&vehicle, 50.0, 10.0, 80.0).unwrap_err(),
+        ValidationError::missing_field("charging.dc")
+    );
+}
+
+#[test]
+fn test_estimate_dc_charge_invalid_session() {
+    let vehicle = vehicle_with_charging(json!({"dc": {"max_power_kw": 100.0}}));
+
+    let error = estimate_dc_charge(&vehicle, 50.0, 80.0, 20.0).unwrap_err();
+    assert_eq!(error.code(), "invalid_soc_interval");
+
+    let error = estimate_dc_charge(&vehicle, 0.0, 10.0, 120.0).unwrap_err();
+    assert!(matches!(error, ValidationError::Multiple(ref errors) if errors.len() == 2));
+}
+
+fn ac(max_power_kw: f64, phases: Option<u8>, max_current_a: Option<f64>) -> ChargingAc {
+    ChargingAc {
+        max_power_kw,
+        supported_power_steps_kw: None,
+        phases,
+        voltage_range_v: None,
+        frequency_hz: None,
+        max_current_a,
+        onboard_charger_count: None,
+        notes: None,
+    }
+}
+
+fn supply(voltage_v: f64, phases: u8, current_a: f64) -> AcSupply {
+    AcSupply {
+        voltage_v,
+        phases,
+        current_a,
+    }
+}
+
+#[test]
+fn test_ac_charge_power_three_phase() {
+    let power = ac_charge_power(&ac(11.0, Some(3), Some(16.0)), &supply(230.0, 3, 32.0)).unwrap();
+
+    assert_eq!(power.phases, 3);
+    assert_close(power.power_kw, 11.0);
+    assert_close(power.current_a, 11000.0 / 690.0);
+}
+
+#[test]
+fn test_ac_charge_power_single_phase_supply() {
+    let power = ac_charge_power(&ac(11.0, Some(3), Some(16.0)), &supply(230.0, 1, 32.0)).unwrap();
+
+    assert_eq!(power.phases, 1);
+    assert_close(power.power_kw, 3.68);
+    assert_close(power.current_a, 16.0);
+}
+
+#[test]
+fn test_ac_charge_power_single_phase_charger() {
+    let power = ac_charge_power(&ac(7.4, Some(1), None), &supply(230.0, 3, 16.0)).unwrap();
+
+    assert_eq!(power.phases, 1);
+    assert_close(power.power_kw, 3.68);
+}
+
+#[test]
+fn test_ac_charge_power_steps() {
+    let mut charger = ac(22.0, Some(3), None);
+    charger.supported_power_steps_kw = Some(vec![3.7, 7.4, 11.0, 22.0]);
+
+    let power = ac_charge_power(&charger, &supply(230.0, 3, 16.0)).unwrap();
+    assert_close(power.power_kw, 11.0);
+
+    let power = ac_charge_power(&charger, &supply(230.0, 3, 13.0)).unwrap();
+    assert_close(power.power_kw, 7.4);
+
+    let error = ac_charge_power(&charger, &supply(230.0, 1, 10.0)).unwrap_err();
+    assert_eq!(error.code(), "exceeds_limit");
+}
+
+#[test]
+fn test_ac_charge_power_voltage_outside_range() {
+    let mut charger = ac(11.0, Some(3), None);
+    charger.voltage_range_v = Some(VoltageRange {
+        min_v: Some(200.0),
+        max_v: Some(250.0),
+    });
+
+    let error = ac_charge_power(&charger, &supply(120.0, 1, 12.0)).unwrap_err();
+    assert_eq!(error.code(), "out_of_range");
+}
+
+#[test]
+fn test_ac_charge_power_invalid_supply() {
+    let error = ac_charge_power(&ac(11.0, None, None), &supply(0.0, 4, 16.0)).unwrap_err();
+    assert!(matches!(error, ValidationError::Multiple(ref errors) if errors.len() == 2));
+}
+
+#[test]
+fn test_estimate_ac_charge() {
+    let vehicle = vehicle_with_charging(json!({"ac": {"max_power_kw": 11.0, "phases": 3}}));
+
+    let estimate = estimate_ac_charge(&vehicle, &supply(230.0, 1, 16.0), 20.0, 80.0).unwrap();
+
+    assert_close(estimate.energy_kwh, 36.0);
+    assert_close(estimate.power.power_kw, 3.68);
+    assert_close(estimate.duration_min, 36.0 / 3.68 * 60.0);
+}
+
+#[test]
+fn test_estimate_ac_charge_without_ac() {
+    let vehicle = vehicle_with_charging(json!({"dc": {"max_power_kw": 50.0}}));
+
+    assert_eq!(
+        estimate_ac_charge(&vehicle, &supply(230.0, 1, 16.0), 20.0, 80.0).unwrap_err(),
+        ValidationError::missing_field("charging.ac")
+    );
+}
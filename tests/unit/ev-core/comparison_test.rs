@@ -0,0 +1,182 @@
+use ev_core::{Better, Comparison, MetricComparison, Vehicle, compare, lowest_msrp};
+use serde_json::{Value, json};
+
+use crate::others::fixtures::sample_vehicle_with;
+
+fn vehicle(model: &str, mut extra: Value) -> Vehicle {
+    extra["model"] = json!({"slug": model, "name": model.to_uppercase()});
+    sample_vehicle_with(extra)
+}
+
+fn metric<'a>(comparison: &'a Comparison, key: &str) -> &'a MetricComparison {
+    comparison
+        .metrics
+        .iter()
+        .find(|metric| metric.key == key)
+        .unwrap()
+}
+
+#[test]
+fn test_compare_marks_best_and_worst() {
+    let vehicles = [
+        vehicle(
+            "ev3",
+            json!({"range": {"rated": [{"cycle": "wltp", "range_km": 400.0}]}}),
+        ),
+        vehicle(
+            "ev6",
+            json!({"range": {"rated": [{"cycle": "wltp", "range_km": 500.0}]}}),
+        ),
+        vehicle(
+            "ev9",
+            json!({"range": {"rated": [{"cycle": "wltp", "range_km": 450.0}]}}),
+        ),
+    ];
+
+    let comparison = compare(&vehicles, None);
+    let range = metric(&comparison, "range_wltp_km");
+
+    assert_eq!(range.better, Better::Higher);
+    assert_eq!(range.unit, "km");
+    let markers: Vec<_> = range.values.iter().map(|v| (v.best, v.worst)).collect();
+    assert_eq!(markers, vec![(false, true), (true, false), (false, false)]);
+    assert_eq!(range.values[0].delta_to_best, Some(-100.0));
+    assert_eq!(range.values[0].delta_to_best_percent, Some(-20.0));
+    assert_eq!(range.values[1].delta_to_best, Some(0.0));
+    assert_eq!(range.values[1].delta_to_baseline, Some(100.0));
+    assert_eq!(range.values[2].delta_to_baseline, Some(50.0));
+}
+
+#[test]
+fn test_compare_lower_is_better() {
+    let vehicles = [
+        vehicle(
+            "ev3",
+            json!({"performance": {"acceleration_0_100_kmh_s": 7.5}}),
+        ),
+        vehicle(
+            "ev6",
+            json!({"performance": {"acceleration_0_100_kmh_s": 3.5}}),
+        ),
+    ];
+
+    let comparison = compare(&vehicles, None);
+    let acceleration = metric(&comparison, "acceleration_0_100_s");
+
+    assert_eq!(acceleration.better, Better::Lower);
+    assert!(acceleration.values[1].best);
+    assert!(acceleration.values[0].worst);
+    assert_eq!(acceleration.values[0].delta_to_best, Some(4.0));
+}
+
+#[test]
+fn test_compare_missing_and_tied_values() {
+    let vehicles = [
+        vehicle("ev3", json!({})),
+        vehicle(
+            "ev6",
+            json!({"efficiency": {"energy_consumption_wh_per_km": 165.0}}),
+        ),
+        vehicle("ev9", json!({})),
+    ];
+
+    let comparison = compare(&vehicles, None);
+
+    let consumption = metric(&comparison, "energy_consumption_wh_per_km");
+    assert!(consumption.values.iter().all(|v| !v.best && !v.worst));
+    assert_eq!(consumption.values[1].value, Some(165.0));
+    assert_eq!(consumption.values[1].delta_to_best, None);
+
+    let battery = metric(&comparison, "battery_capacity_kwh");
+    assert!(battery.values.iter().all(|v| v.best && !v.worst));
+    assert!(battery.values.iter().all(|v| v.delta_to_best == Some(0.0)));
+}
+
+#[test]
+fn test_compare_msrp_currency() {
+    let priced = |model: &str, prices: Value| vehicle(model, json!({"pricing": {"msrp": prices}}));
+    let vehicles = [
+        priced(
+            "ev3",
+            json!([
+                {"currency": "EUR", "amount": 39990.0, "country": "DE"},
+                {"currency": "EUR", "amount": 37990.0, "country": "FR"}
+            ]),
+        ),
+        priced(
+            "ev6",
+            json!([
+                {"currency": "EUR", "amount": 49990.0},
+                {"currency": "USD", "amount": 42600.0}
+            ]),
+        ),
+        vehicle("ev9", json!({})),
+    ];
+
+    assert_eq!(lowest_msrp(&vehicles[0], "EUR"), Some(37990.0));
+    assert_eq!(lowest_msrp(&vehicles[0], "USD"), None);
+
+    let comparison = compare(&vehicles, None);
+    let msrp = metric(&comparison, "msrp");
+    assert_eq!(msrp.unit, "EUR");
+    assert_eq!(msrp.better, Better::Lower);
+    assert!(msrp.values[0].best);
+    assert!(msrp.values[1].worst);
+    assert_eq!(msrp.values[1].delta_to_best, Some(12000.0));
+    assert_eq!(msrp.values[2].value, None);
+
+    let comparison = compare(&vehicles, Some("USD"));
+    let msrp = metric(&comparison, "msrp");
+    assert_eq!(msrp.unit, "USD");
+    assert_eq!(msrp.values[1].value, Some(42600.0));
+    assert!(!msrp.values[1].best);
+
+    let unpriced = compare(
+        &[vehicle("ev3", json!({})), vehicle("ev6", json!({}))],
+        None,
+    );
+    assert!(unpriced.metrics.iter().all(|metric| metric.key != "msrp"));
+}
+
+#[test]
+fn test_compare_aligns_fields() {
+    let vehicles = [
+        vehicle(
+            "ev3",
+            json!({"battery": {"pack_capacity_kwh_net": 58.3, "chemistry": "NMC"}}),
+        ),
+        vehicle("ev6", json!({"battery": {"pack_capacity_kwh_net": 84.0}})),
+    ];
+
+    let comparison = compare(&vehicles, None);
+    let field = |path: &str| {
+        comparison
+            .fields
+            .iter()
+            .find(|field| field.path == path)
+            .unwrap()
+    };
+
+    let chemistry = field("/battery/chemistry");
+    assert_eq!(chemistry.values, vec![Some(json!("NMC")), None]);
+    assert!(chemistry.differs);
+
+    let capacity = field("/battery/pack_capacity_kwh_net");
+    assert_eq!(capacity.values, vec![Some(json!(58.3)), Some(json!(84.0))]);
+
+    let make = field("/make/name");
+    assert_eq!(
+        make.values,
+        vec![Some(json!("Tesla")), Some(json!("Tesla"))]
+    );
+    assert!(!make.differs);
+
+    assert!(
+        field("/charge_ports/0/connector")
+            .values
+            .iter()
+            .all(Option::is_some)
+    );
+    let paths: Vec<_> = comparison.fields.iter().map(|f| f.path.as_str()).collect();
+    assert!(paths.windows(2).all(|w| w[0] < w[1]));
+}
@@ -0,0 +1,234 @@
+use ev_core::{
+    ADAPTERS, ChargerSpec, ChargerVoltageClass, ConnectorType, CurrentType, PowerLimit, Vehicle,
+    candidate_port_connectors, check_compatibility,
+};
+use serde_json::{Value, json};
+
+use crate::others::fixtures::sample_vehicle_with;
+
+fn vehicle(charge_ports: Value, charging: Value) -> Vehicle {
+    sample_vehicle_with(json!({"charge_ports": charge_ports, "charging": charging}))
+}
+
+fn charger(
+    connector: ConnectorType,
+    current: CurrentType,
+    max_power_kw: f64,
+    voltage_class: Option<ChargerVoltageClass>,
+) -> ChargerSpec {
+    ChargerSpec {
+        connector,
+        current,
+        max_power_kw,
+        voltage_class,
+    }
+}
+
+fn ccs2_800v() -> Vehicle {
+    vehicle(
+        json!([{"kind": "combo", "connector": "ccs2"}]),
+        json!({
+            "ac": {"max_power_kw": 11.0},
+            "dc": {
+                "max_power_kw": 233.0,
+                "architecture_voltage_class": "800v",
+                "power_limits_by_voltage": [{"voltage_class": "400v", "max_power_kw": 125.0}]
+            }
+        }),
+    )
+}
+
+#[test]
+fn test_check_compatibility_native_dc() {
+    let result = check_compatibility(
+        &ccs2_800v(),
+        &charger(
+            ConnectorType::Ccs2,
+            CurrentType::Dc,
+            350.0,
+            Some(ChargerVoltageClass::V800),
+        ),
+        ADAPTERS,
+    )
+    .unwrap();
+
+    assert_eq!(result.port, ConnectorType::Ccs2);
+    assert!(result.adapter.is_none());
+    assert_eq!(result.max_power_kw, 233.0);
+    assert_eq!(result.limited_by, PowerLimit::Vehicle);
+}
+
+#[test]
+fn test_check_compatibility_800v_vehicle_on_400v_charger() {
+    let result = check_compatibility(
+        &ccs2_800v(),
+        &charger(
+            ConnectorType::Ccs2,
+            CurrentType::Dc,
+            350.0,
+            Some(ChargerVoltageClass::V400),
+        ),
+        ADAPTERS,
+    )
+    .unwrap();
+
+    assert_eq!(result.max_power_kw, 125.0);
+    assert_eq!(result.limited_by, PowerLimit::VoltageClass);
+}
+
+#[test]
+fn test_check_compatibility_limited_by_charger() {
+    let result = check_compatibility(
+        &ccs2_800v(),
+        &charger(
+            ConnectorType::Ccs2,
+            CurrentType::Dc,
+            50.0,
+            Some(ChargerVoltageClass::V400),
+        ),
+        ADAPTERS,
+    )
+    .unwrap();
+
+    assert_eq!(result.max_power_kw, 50.0);
+    assert_eq!(result.limited_by, PowerLimit::Charger);
+}
+
+#[test]
+fn test_check_compatibility_ac_through_combo_port() {
+    let result = check_compatibility(
+        &ccs2_800v(),
+        &charger(ConnectorType::Type2, CurrentType::Ac, 22.0, None),
+        ADAPTERS,
+    )
+    .unwrap();
+
+    assert_eq!(result.port, ConnectorType::Ccs2);
+    assert_eq!(result.max_power_kw, 11.0);
+    assert_eq!(result.limited_by, PowerLimit::Vehicle);
+}
+
+#[test]
+fn test_check_compatibility_incompatible() {
+    let vehicle = ccs2_800v();
+
+    for spec in [
+        charger(ConnectorType::Chademo, CurrentType::Dc, 50.0, None),
+        charger(ConnectorType::Ccs1, CurrentType::Dc, 150.0, None),
+        charger(ConnectorType::Type2, CurrentType::Dc, 150.0, None),
+    ] {
+        assert!(check_compatibility(&vehicle, &spec, ADAPTERS).is_none());
+    }
+}
+
+#[test]
+fn test_check_compatibility_with_adapter() {
+    let vehicle = vehicle(
+        json!([{"kind": "combo", "connector": "nacs"}]),
+        json!({"dc": {"max_power_kw": 250.0}}),
+    );
+
+    let result = check_compatibility(
+        &vehicle,
+        &charger(ConnectorType::Ccs1, CurrentType::Dc, 350.0, None),
+        ADAPTERS,
+    )
+    .unwrap();
+    let adapter = result.adapter.unwrap();
+    assert_eq!(adapter.charger, ConnectorType::Ccs1);
+    assert_eq!(adapter.vehicle, ConnectorType::Nacs);
+    assert_eq!(result.max_power_kw, 250.0);
+
+    let result = check_compatibility(
+        &vehicle,
+        &charger(ConnectorType::Chademo, CurrentType::Dc, 100.0, None),
+        ADAPTERS,
+    )
+    .unwrap();
+    assert_eq!(result.max_power_kw, 50.0);
+    assert_eq!(result.limited_by, PowerLimit::Adapter);
+
+    assert!(
+        check_compatibility(
+            &vehicle,
+            &charger(ConnectorType::Ccs1, CurrentType::Dc, 350.0, None),
+            &[]
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn test_check_compatibility_prefers_native_port() {
+    let vehicle = vehicle(
+        json!([
+            {"kind": "combo", "connector": "ccs1"},
+            {"kind": "combo", "connector": "nacs"}
+        ]),
+        json!({"dc": {"max_power_kw": 150.0}}),
+    );
+
+    let result = check_compatibility(
+        &vehicle,
+        &charger(ConnectorType::Nacs, CurrentType::Dc, 250.0, None),
+        ADAPTERS,
+    )
+    .unwrap();
+
+    assert_eq!(result.port, ConnectorType::Nacs);
+    assert!(result.adapter.is_none());
+}
+
+#[test]
+fn test_check_compatibility_port_kind() {
+    let vehicle = vehicle(
+        json!([{"kind": "ac_only", "connector": "type2"}]),
+        json!({"ac": {"max_power_kw": 7.4}}),
+    );
+
+    assert!(
+        check_compatibility(
+            &vehicle,
+            &charger(ConnectorType::Type2, CurrentType::Ac, 22.0, None),
+            ADAPTERS
+        )
+        .is_some()
+    );
+    assert!(
+        check_compatibility(
+            &vehicle,
+            &charger(ConnectorType::Ccs2, CurrentType::Dc, 50.0, None),
+            ADAPTERS
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn test_candidate_port_connectors() {
+    assert_eq!(
+        candidate_port_connectors(
+            &charger(ConnectorType::Type2, CurrentType::Ac, 22.0, None),
+            ADAPTERS
+        ),
+        vec![
+            ConnectorType::Type2,
+            ConnectorType::Ccs2,
+            ConnectorType::TeslaType2
+        ]
+    );
+    assert_eq!(
+        candidate_port_connectors(
+            &charger(ConnectorType::Ccs1, CurrentType::Dc, 150.0, None),
+            ADAPTERS
+        ),
+        vec![ConnectorType::Ccs1, ConnectorType::Nacs]
+    );
+    assert_eq!(
+        candidate_port_connectors(
+            &charger(ConnectorType::Ccs1, CurrentType::Dc, 150.0, None),
+            &[]
+        ),
+        vec![ConnectorType::Ccs1]
+    );
+}
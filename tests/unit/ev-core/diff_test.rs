@@ -0,0 +1,105 @@
+use ev_core::{ChangeKind, FieldChange, Vehicle, diff_vehicles};
+use serde_json::{Value, json};
+
+use crate::others::fixtures::sample_vehicle_with;
+
+fn vehicle(year: u16, mut extra: Value) -> Vehicle {
+    extra["year"] = json!(year);
+    sample_vehicle_with(extra)
+}
+
+#[test]
+fn test_diff_vehicles_identical() {
+    let diff = diff_vehicles(&vehicle(2024, json!({})), &vehicle(2024, json!({})));
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn test_diff_vehicles_model_years() {
+    let old = vehicle(
+        2023,
+        json!({
+            "battery": {"pack_capacity_kwh_net": 60.0, "chemistry": "NCA"},
+            "charging": {"dc": {"max_power_kw": 170.0}},
+            "range": {"rated": [{"cycle": "wltp", "range_km": 602.0}]}
+        }),
+    );
+    let new = vehicle(
+        2024,
+        json!({
+            "charging": {"dc": {"max_power_kw": 250.0}},
+            "range": {"rated": [{"cycle": "wltp", "range_km": 629.0}]},
+            "v2x": {"v2l": {"supported": true}}
+        }),
+    );
+
+    let diff = diff_vehicles(&old, &new);
+
+    assert_eq!(
+        diff.changes,
+        vec![
+            FieldChange {
+                path: "/battery/chemistry".to_string(),
+                kind: ChangeKind::Removed,
+                old: Some(json!("NCA")),
+                new: None,
+            },
+            FieldChange {
+                path: "/charging/dc/max_power_kw".to_string(),
+                kind: ChangeKind::Changed,
+                old: Some(json!(170.0)),
+                new: Some(json!(250.0)),
+            },
+            FieldChange {
+                path: "/range/rated/0/range_km".to_string(),
+                kind: ChangeKind::Changed,
+                old: Some(json!(602.0)),
+                new: Some(json!(629.0)),
+            },
+            FieldChange {
+                path: "/v2x".to_string(),
+                kind: ChangeKind::Added,
+                old: None,
+                new: Some(json!({"v2l": {"supported": true}})),
+            },
+            FieldChange {
+                path: "/year".to_string(),
+                kind: ChangeKind::Changed,
+                old: Some(json!(2023)),
+                new: Some(json!(2024)),
+            },
+        ]
+    );
+    assert_eq!(diff.of_kind(ChangeKind::Changed).count(), 3);
+}
+
+#[test]
+fn test_diff_vehicles_arrays_by_index() {
+    let old = vehicle(2024, json!({}));
+    let new = vehicle(
+        2024,
+        json!({
+            "charge_ports": [
+                {"kind": "combo", "connector": "nacs"},
+                {"kind": "ac_only", "connector": "type2"}
+            ]
+        }),
+    );
+
+    let paths: Vec<_> = diff_vehicles(&old, &new)
+        .changes
+        .into_iter()
+        .map(|change| (change.path, change.kind))
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            ("/charge_ports/0/connector".to_string(), ChangeKind::Changed),
+            ("/charge_ports/1".to_string(), ChangeKind::Added),
+        ]
+    );
+
+    let reverse = diff_vehicles(&new, &old);
+    assert_eq!(reverse.of_kind(ChangeKind::Removed).count(), 1);
+}
@@ -1,4 +1,4 @@
-use ev_core::{Battery, Validate, ValidationError};
+use ev_core::{Battery, UsableSocWindow, Validate, ValidationError};
 
 #[test]
 fn test_battery_validation_success() {
@@ -122,3 +122,62 @@ fn test_battery_default() {
     assert_eq!(battery.chemistry, None);
     assert!(!battery.has_capacity());
 }
+
+#[test]
+fn test_battery_validation_net_exceeds_gross() {
+    let battery = Battery {
+        pack_capacity_kwh_net: Some(80.0),
+        pack_capacity_kwh_gross: Some(75.0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        battery.validate(),
+        Err(ValidationError::ExceedsLimit { ref field, .. }) if field == "pack_capacity_kwh_net"
+    ));
+}
+
+#[test]
+fn test_battery_validation_non_positive_capacity() {
+    let battery = Battery {
+        pack_capacity_kwh_net: Some(0.0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        battery.validate(),
+        Err(ValidationError::NonPositive { .. })
+    ));
+}
+
+#[test]
+fn test_battery_validation_voltage_order() {
+    let battery = Battery {
+        pack_capacity_kwh_net: Some(60.0),
+        pack_voltage_min_v: Some(450.0),
+        pack_voltage_nominal_v: Some(400.0),
+        pack_voltage_max_v: Some(470.0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        battery.validate(),
+        Err(ValidationError::InvalidBounds { .. })
+    ));
+}
+
+#[test]
+fn test_battery_validation_usable_soc_window() {
+    let battery = Battery {
+        pack_capacity_kwh_net: Some(60.0),
+        usable_soc_window_percent: Some(UsableSocWindow {
+            min_percent: Some(95.0),
+            max_percent: Some(-5.0),
+            notes: None,
+        }),
+        ..Default::default()
+    };
+    let Err(ValidationError::Multiple(errors)) = battery.validate() else {
+        panic!("expected multiple errors");
+    };
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0], ValidationError::OutOfRange { .. }));
+    assert!(matches!(errors[1], ValidationError::InvalidBounds { .. }));
+}
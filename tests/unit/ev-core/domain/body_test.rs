@@ -1,4 +1,4 @@
-use ev_core::{Body, Dimensions, Weights};
+use ev_core::{Body, Dimensions, Validate, ValidationError, Weights};
 
 #[test]
 fn test_body_serialization() {
@@ -33,3 +33,28 @@ fn test_weights_serialization() {
     let json = serde_json::to_value(&weights).expect("failed to serialize");
     assert_eq!(json["curb_weight_kg"], 2000.0);
 }
+
+#[test]
+fn test_weights_validation_curb_exceeds_gross() {
+    let weights = Weights {
+        curb_weight_kg: Some(2200.0),
+        gross_vehicle_weight_kg: Some(2100.0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        weights.validate(),
+        Err(ValidationError::ExceedsLimit { .. })
+    ));
+}
+
+#[test]
+fn test_body_validation_drag_coefficient() {
+    let body = Body {
+        drag_coefficient_cd: Some(1.5),
+        ..Default::default()
+    };
+    assert!(matches!(
+        body.validate(),
+        Err(ValidationError::OutOfRange { .. })
+    ));
+}
@@ -1,4 +1,8 @@
-use ev_core::{ChargePort, Charging, ChargingAc, ConnectorType, PortKind};
+use ev_core::{
+    ChargeCurve, ChargeCurvePoint, ChargeCurveType, ChargePort, Charging, ChargingAc, ChargingDc,
+    ChargingTime, ChargingTimeEntry, ConnectorType, DcChargingTimeEntry, PortKind, Validate,
+    ValidationError, VoltageRange,
+};
 
 #[test]
 fn test_charge_port_serialization() {
@@ -40,3 +44,188 @@ fn test_charging_ac() {
     assert_eq!(ac.max_power_kw, 11.0);
     assert_eq!(ac.phases, Some(3));
 }
+
+fn curve(points: &[(f64, f64)]) -> ChargeCurve {
+    ChargeCurve {
+        curve_type: ChargeCurveType::PowerBySoc,
+        points: points
+            .iter()
+            .map(|&(soc_percent, power_kw)| ChargeCurvePoint {
+                soc_percent,
+                power_kw: Some(power_kw),
+                current_a: None,
+                voltage_v: None,
+            })
+            .collect(),
+        conditions: None,
+        source_url: None,
+        notes: None,
+    }
+}
+
+fn dc(max_power_kw: f64) -> ChargingDc {
+    ChargingDc {
+        max_power_kw,
+        voltage_range_v: None,
+        max_current_a: None,
+        architecture_voltage_class: None,
+        power_limits_by_voltage: None,
+        notes: None,
+    }
+}
+
+#[test]
+fn test_charging_validation_success() {
+    let charging = Charging {
+        dc: Some(dc(250.0)),
+        dc_charge_curve: Some(curve(&[(10.0, 250.0), (50.0, 150.0), (80.0, 60.0)])),
+        ..Default::default()
+    };
+    assert!(charging.validate().is_ok());
+}
+
+#[test]
+fn test_charging_validation_curve_exceeds_dc_max_power() {
+    let charging = Charging {
+        dc: Some(dc(150.0)),
+        dc_charge_curve: Some(curve(&[(10.0, 175.0), (80.0, 60.0)])),
+        ..Default::default()
+    };
+    assert!(matches!(
+        charging.validate(),
+        Err(ValidationError::ExceedsLimit { ref limit_field, .. }) if limit_field == "dc.max_power_kw"
+    ));
+}
+
+#[test]
+fn test_charge_curve_validation_unsorted() {
+    let curve = curve(&[(10.0, 100.0), (50.0, 90.0), (40.0, 80.0)]);
+    assert_eq!(
+        curve.validate(),
+        Err(ValidationError::UnsortedChargeCurve { index: 2 })
+    );
+}
+
+#[test]
+fn test_charge_curve_validation_soc_out_of_range() {
+    let curve = curve(&[(10.0, 100.0), (120.0, 20.0)]);
+    assert!(matches!(
+        curve.validate(),
+        Err(ValidationError::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn test_charge_curve_validation_empty_points() {
+    let curve = curve(&[]);
+    assert!(matches!(
+        curve.validate(),
+        Err(ValidationError::MissingField { .. })
+    ));
+}
+
+#[test]
+fn test_voltage_range_validation() {
+    let valid = VoltageRange {
+        min_v: Some(200.0),
+        max_v: Some(920.0),
+    };
+    assert!(valid.validate().is_ok());
+
+    let inverted = VoltageRange {
+        min_v: Some(920.0),
+        max_v: Some(200.0),
+    };
+    assert!(matches!(
+        inverted.validate(),
+        Err(ValidationError::InvalidBounds { .. })
+    ));
+}
+
+#[test]
+fn test_charging_ac_validation_phases() {
+    let ac = ChargingAc {
+        max_power_kw: 11.0,
+        supported_power_steps_kw: None,
+        phases: Some(4),
+        voltage_range_v: None,
+        frequency_hz: None,
+        max_current_a: None,
+        onboard_charger_count: None,
+        notes: None,
+    };
+    assert!(matches!(
+        ac.validate(),
+        Err(ValidationError::OutOfRange { ref field, .. }) if field == "ac.phases"
+    ));
+}
+
+#[test]
+fn test_charging_time_validation_soc_interval() {
+    let charging_time = ChargingTime {
+        ac: Some(vec![ChargingTimeEntry {
+            power_kw: 11.0,
+            from_soc_percent: 80.0,
+            to_soc_percent: 10.0,
+            time_min: 300.0,
+            conditions: None,
+            notes: None,
+        }]),
+        dc: Some(vec![DcChargingTimeEntry {
+            charger_power_kw: 150.0,
+            from_soc_percent: 10.0,
+            to_soc_percent: 80.0,
+            time_min: 25.0,
+            conditions: None,
+            notes: None,
+        }]),
+    };
+    assert!(matches!(
+        charging_time.validate(),
+        Err(ValidationError::InvalidSocInterval { ref field, .. }) if field == "charging_time.ac"
+    ));
+}
+
+#[test]
+fn test_charge_curve_power_at_interpolates() {
+    let curve = curve(&[(10.0, 100.0), (50.0, 200.0), (80.0, 50.0)]);
+
+    assert_eq!(curve.power_at(30.0), Some(150.0));
+    assert_eq!(curve.power_at(50.0), Some(200.0));
+    assert_eq!(curve.power_at(65.0), Some(125.0));
+    assert_eq!(curve.power_at(0.0), Some(100.0));
+    assert_eq!(curve.power_at(95.0), Some(50.0));
+}
+
+#[test]
+fn test_charge_curve_power_at_uses_current_and_voltage() {
+    let curve = ChargeCurve {
+        curve_type: ChargeCurveType::CurrentBySoc,
+        points: vec![
+            ChargeCurvePoint {
+                soc_percent: 10.0,
+                power_kw: None,
+                current_a: Some(250.0),
+                voltage_v: Some(400.0),
+            },
+            ChargeCurvePoint {
+                soc_percent: 50.0,
+                power_kw: None,
+                current_a: Some(200.0),
+                voltage_v: None,
+            },
+        ],
+        conditions: None,
+        source_url: None,
+        notes: None,
+    };
+
+    assert_eq!(curve.points[0].effective_power_kw(), Some(100.0));
+    assert_eq!(curve.points[1].effective_power_kw(), None);
+    assert_eq!(curve.power_at(50.0), Some(100.0));
+}
+
+#[test]
+fn test_charge_curve_power_at_without_power() {
+    assert_eq!(curve(&[]).power_at(50.0), None);
+}
@@ -439,3 +439,57 @@ fn test_enum_debug() {
     assert_eq!(format!("{:?}", Drivetrain::Awd), "Awd");
     assert_eq!(format!("{:?}", ConnectorType::Ccs2), "Ccs2");
 }
+
+fn assert_wire_strings<T>(all: &[T], as_str: fn(&T) -> &'static str)
+where
+    T: std::fmt::Debug + PartialEq + serde::Serialize + std::str::FromStr,
+    T::Err: std::fmt::Debug,
+{
+    for variant in all {
+        let wire = as_str(variant);
+        assert_eq!(serde_json::to_value(variant).unwrap(), wire, "{variant:?}");
+        assert_eq!(&wire.parse::<T>().unwrap(), variant);
+    }
+}
+
+#[test]
+fn test_as_str_matches_serde_and_round_trips() {
+    assert_wire_strings(VehicleType::ALL, VehicleType::as_str);
+    assert_wire_strings(Drivetrain::ALL, Drivetrain::as_str);
+    assert_wire_strings(MotorPosition::ALL, MotorPosition::as_str);
+    assert_wire_strings(ThermalManagement::ALL, ThermalManagement::as_str);
+    assert_wire_strings(PortKind::ALL, PortKind::as_str);
+    assert_wire_strings(ConnectorType::ALL, ConnectorType::as_str);
+    assert_wire_strings(PortLocation::ALL, PortLocation::as_str);
+    assert_wire_strings(PortPosition::ALL, PortPosition::as_str);
+    assert_wire_strings(ChargerVoltageClass::ALL, ChargerVoltageClass::as_str);
+    assert_wire_strings(ChargeCurveType::ALL, ChargeCurveType::as_str);
+    assert_wire_strings(RangeCycle::ALL, RangeCycle::as_str);
+    assert_wire_strings(RealWorldProfile::ALL, RealWorldProfile::as_str);
+    assert_wire_strings(AvailabilityStatus::ALL, AvailabilityStatus::as_str);
+    assert_wire_strings(SourceType::ALL, SourceType::as_str);
+}
+
+#[test]
+fn test_as_str_canonical_values() {
+    assert_eq!(VehicleType::PassengerCar.as_str(), "passenger_car");
+    assert_eq!(Drivetrain::FourWd.as_str(), "4wd");
+    assert_eq!(ConnectorType::GbTAc.as_str(), "gb_t_ac");
+    assert_eq!(ChargerVoltageClass::V800.as_str(), "800v");
+}
+
+#[test]
+fn test_from_str_unknown_value_lists_allowed() {
+    let err = "bev".parse::<Drivetrain>().unwrap_err();
+    assert_eq!(err.code(), "invalid_enum_value");
+    assert_eq!(
+        err.to_string(),
+        "Invalid drivetrain 'bev', expected one of: fwd, rwd, awd, 4wd"
+    );
+}
+
+#[test]
+fn test_from_str_is_case_sensitive() {
+    assert!("CCS2".parse::<ConnectorType>().is_err());
+    assert!("PassengerCar".parse::<VehicleType>().is_err());
+}
@@ -1,5 +1,5 @@
 use ev_core::domain::enums::AvailabilityStatus;
-use ev_core::{Metadata, Msrp, Pricing, VehicleAvailability};
+use ev_core::{Metadata, Msrp, Pricing, Validate, ValidationError, VehicleAvailability};
 
 #[test]
 fn test_metadata_serialization() {
@@ -37,3 +37,63 @@ fn test_availability_serialization() {
     let json = serde_json::to_value(&avail).unwrap();
     assert_eq!(json["status"], "production");
 }
+
+#[test]
+fn test_vehicle_availability_validation_year_order() {
+    let availability = VehicleAvailability {
+        status: AvailabilityStatus::Discontinued,
+        start_year: Some(2022),
+        end_year: Some(2020),
+        notes: None,
+    };
+    assert!(matches!(
+        availability.validate(),
+        Err(ValidationError::InvalidBounds { .. })
+    ));
+
+    let same_year = VehicleAvailability {
+        end_year: Some(2022),
+        ..availability
+    };
+    assert!(same_year.validate().is_ok());
+}
+
+#[test]
+fn test_msrp_validation() {
+    let msrp = Msrp {
+        currency: "EUR".to_string(),
+        amount: 45_990.0,
+        country: Some("DE".to_string()),
+        year: Some(2024),
+        notes: None,
+    };
+    assert!(msrp.validate().is_ok());
+
+    let invalid = Msrp {
+        currency: "eur".to_string(),
+        ..msrp
+    };
+    assert_eq!(
+        invalid.validate(),
+        Err(ValidationError::InvalidCurrencyCode {
+            code: "eur".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_pricing_validation_collects_errors() {
+    let pricing = Pricing {
+        msrp: Some(vec![Msrp {
+            currency: "US".to_string(),
+            amount: -1.0,
+            country: Some("usa".to_string()),
+            year: None,
+            notes: None,
+        }]),
+    };
+    let Err(ValidationError::Multiple(errors)) = pricing.validate() else {
+        panic!("expected multiple errors");
+    };
+    assert_eq!(errors.len(), 3);
+}
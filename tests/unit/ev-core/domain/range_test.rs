@@ -1,4 +1,7 @@
-use ev_core::{Efficiency, Range, RangeCycle, RangeRated, RangeRealWorld, RealWorldProfile};
+use ev_core::{
+    Efficiency, Range, RangeCycle, RangeRated, RangeRealWorld, RealWorldProfile, Validate,
+    ValidationError,
+};
 
 #[test]
 fn test_range_wltp_range_km() {
@@ -382,3 +385,42 @@ fn test_all_real_world_profiles() {
 
     assert_eq!(range.real_world.as_ref().unwrap().len(), 6);
 }
+
+#[test]
+fn test_range_validation_duplicate_cycle() {
+    let range = Range {
+        rated: vec![
+            RangeRated {
+                cycle: RangeCycle::Wltp,
+                range_km: 500.0,
+                notes: None,
+            },
+            RangeRated {
+                cycle: RangeCycle::Wltp,
+                range_km: 480.0,
+                notes: None,
+            },
+        ],
+        real_world: None,
+    };
+    assert!(matches!(
+        range.validate(),
+        Err(ValidationError::DuplicateEntry { .. })
+    ));
+}
+
+#[test]
+fn test_range_validation_non_positive_range() {
+    let range = Range {
+        rated: vec![RangeRated {
+            cycle: RangeCycle::Epa,
+            range_km: 0.0,
+            notes: None,
+        }],
+        real_world: None,
+    };
+    assert!(matches!(
+        range.validate(),
+        Err(ValidationError::NonPositive { .. })
+    ));
+}
@@ -1,4 +1,4 @@
-use ev_core::{Source, SourceType};
+use ev_core::{Source, SourceType, Validate, ValidationError};
 
 #[test]
 fn test_source_is_official_oem() {
@@ -126,3 +126,28 @@ fn test_source_with_all_optional_fields() {
     assert_eq!(json["license"], "MIT");
     assert_eq!(json["notes"], "Detailed notes");
 }
+
+#[test]
+fn test_source_validation() {
+    let source = Source {
+        source_type: SourceType::Oem,
+        title: "Spec sheet".to_string(),
+        url: "https://example.com/spec".to_string(),
+        accessed_at: "2024-12-26T00:00:00Z".to_string(),
+        publisher: None,
+        license: None,
+        notes: None,
+    };
+    assert!(source.validate().is_ok());
+
+    let invalid = Source {
+        url: "example.com".to_string(),
+        accessed_at: "yesterday".to_string(),
+        ..source
+    };
+    let Err(ValidationError::Multiple(errors)) = invalid.validate() else {
+        panic!("expected multiple errors");
+    };
+    assert!(matches!(errors[0], ValidationError::InvalidUrl { .. }));
+    assert!(matches!(errors[1], ValidationError::InvalidDateTime { .. }));
+}
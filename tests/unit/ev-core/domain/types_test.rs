@@ -61,3 +61,60 @@ fn test_vehicle_id_struct_fields() {
     };
     assert_eq!(id.to_string(), "tesla:model_3:2024:long_range:perf");
 }
+
+#[test]
+fn test_vehicle_id_from_str_round_trip() {
+    for code in [
+        "tesla:model_3:2024:base",
+        "tesla:model_3:2024:long_range:perf",
+    ] {
+        let id: VehicleId = code.parse().unwrap();
+        assert_eq!(id.to_string(), code);
+    }
+
+    let id: VehicleId = "byd:dolphin:2024:base".parse().unwrap();
+    assert_eq!(
+        id,
+        VehicleId::new("byd", "dolphin", 2024, "base", None).unwrap()
+    );
+}
+
+#[test]
+fn test_vehicle_id_from_str_invalid() {
+    for code in [
+        "",
+        "tesla:model_3:2024",
+        "tesla:model_3:2024:base:perf:extra",
+        "tesla:model_3:twenty:base",
+        "tesla:model_3:1850:base",
+        "Tesla:model_3:2024:base",
+        "tesla:model_3:2024:",
+    ] {
+        let error = code.parse::<VehicleId>().unwrap_err();
+        assert_eq!(error.code(), "invalid_vehicle_id", "{code}");
+    }
+
+    let error = "tesla:model_3:2024".parse::<VehicleId>().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid vehicle id 'tesla:model_3:2024': expected make:model:year:trim[:variant], got 3 segment(s)"
+    );
+}
+
+#[test]
+fn test_vehicle_id_from_document() {
+    let document = serde_json::json!({
+        "make": {"slug": "tesla", "name": "Tesla"},
+        "model": {"slug": "model_3", "name": "Model 3"},
+        "year": 2024,
+        "trim": {"slug": "long_range", "name": "Long Range"},
+        "variant": {"slug": "awd", "name": "AWD"},
+        "battery": "not an object"
+    });
+    let id = VehicleId::from_document(&document).unwrap();
+    assert_eq!(id.to_string(), "tesla:model_3:2024:long_range:awd");
+
+    let mut without_trim = document.clone();
+    without_trim.as_object_mut().unwrap().remove("trim");
+    assert!(VehicleId::from_document(&without_trim).is_none());
+}
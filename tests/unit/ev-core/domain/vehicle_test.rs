@@ -1,7 +1,8 @@
 use ev_core::{
-    Battery, ChargePort, Charging, ChargingAc, ChargingDc, ConnectorType, Drivetrain, PortKind,
-    Powertrain, Range, RangeCycle, RangeRated, SlugName, Source, SourceType, Validate,
-    ValidationError, Variant, Vehicle, VehicleType,
+    AvailabilityStatus, Battery, ChargePort, Charging, ChargingAc, ChargingDc, ConnectorType,
+    Drivetrain, Msrp, PortKind, Powertrain, Pricing, Range, RangeCycle, RangeRated, Severity,
+    SlugName, Source, SourceType, Validate, ValidationError, Variant, Vehicle, VehicleAvailability,
+    VehicleType,
 };
 
 fn create_test_vehicle() -> Vehicle {
@@ -261,3 +262,66 @@ fn test_vehicle_invalid_year() {
     vehicle.year = 1800;
     assert!(vehicle.validate().is_err());
 }
+
+#[test]
+fn test_vehicle_validates_nested_rules() {
+    let mut vehicle = create_test_vehicle();
+    vehicle.battery.pack_capacity_kwh_gross = Some(55.0);
+    vehicle.availability = Some(VehicleAvailability {
+        status: AvailabilityStatus::Discontinued,
+        start_year: Some(2024),
+        end_year: Some(2021),
+        notes: None,
+    });
+    vehicle.pricing = Some(Pricing {
+        msrp: Some(vec![Msrp {
+            currency: "EURO".to_string(),
+            amount: 39_990.0,
+            country: None,
+            year: None,
+            notes: None,
+        }]),
+    });
+
+    let Err(ValidationError::Multiple(errors)) = vehicle.validate() else {
+        panic!("expected multiple errors");
+    };
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(errors[0], ValidationError::ExceedsLimit { .. }));
+    assert!(matches!(errors[1], ValidationError::InvalidBounds { .. }));
+    assert!(matches!(
+        errors[2],
+        ValidationError::InvalidCurrencyCode { .. }
+    ));
+}
+
+#[test]
+fn test_vehicle_invalid_market_code() {
+    let mut vehicle = create_test_vehicle();
+    vehicle.markets = Some(vec!["DE".to_string(), "Europe".to_string()]);
+    assert_eq!(
+        vehicle.validate(),
+        Err(ValidationError::InvalidCountryCode {
+            code: "Europe".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_vehicle_findings_carry_json_pointers() {
+    let mut vehicle = create_test_vehicle();
+    vehicle.charging.dc = Some(ChargingDc {
+        max_power_kw: 0.0,
+        voltage_range_v: None,
+        max_current_a: None,
+        architecture_voltage_class: None,
+        power_limits_by_voltage: None,
+        notes: None,
+    });
+
+    let findings = vehicle.findings();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].pointer, "/charging/dc/max_power_kw");
+    assert_eq!(findings[0].code(), "non_positive");
+    assert_eq!(findings[0].severity, Severity::Error);
+}
@@ -30,3 +30,29 @@ fn test_core_error_conversion() {
         _ => panic!("Expected Validation error variant"),
     }
 }
+
+#[test]
+fn test_validation_error_code() {
+    let err = ValidationError::InvalidCurrencyCode {
+        code: "eur".to_string(),
+    };
+    assert_eq!(err.code(), "invalid_currency_code");
+
+    let duplicate = ValidationError::DuplicateEntry {
+        field: "range.rated.cycle".to_string(),
+        value: "wltp".to_string(),
+    };
+    assert_eq!(duplicate.code(), "duplicate_entry");
+}
+
+#[test]
+fn test_multiple_error_display() {
+    let err = ValidationError::Multiple(vec![
+        ValidationError::MissingChargePort,
+        ValidationError::MissingSource,
+    ]);
+    assert_eq!(
+        err.to_string(),
+        "2 validation errors: At least one charge port is required; At least one source is required"
+    );
+}
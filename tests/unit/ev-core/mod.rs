@@ -1,7 +1,17 @@
+#[path = "charge_time_test.rs"]
+mod charge_time_test;
+#[path = "comparison_test.rs"]
+mod comparison_test;
+#[path = "compatibility_test.rs"]
+mod compatibility_test;
+#[path = "diff_test.rs"]
+mod diff_test;
 mod domain;
 #[path = "error_test.rs"]
 mod error_test;
 #[path = "lib_test.rs"]
 mod lib_test;
+#[path = "schema_test.rs"]
+mod schema_test;
 #[path = "validation_test.rs"]
 mod validation_test;
@@ -0,0 +1,41 @@
+use ev_core::schema::vehicle_schema;
+
+#[test]
+fn test_vehicle_schema_root() {
+    let schema = vehicle_schema();
+
+    assert_eq!(schema["title"], "Vehicle");
+    assert_eq!(schema["additionalProperties"], false);
+
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect();
+    assert!(required.contains(&"battery"));
+    assert!(required.contains(&"sources"));
+    assert!(!required.contains(&"variant"));
+    assert!(schema["properties"]["$schema"].is_object());
+}
+
+#[test]
+fn test_vehicle_schema_uses_serde_names() {
+    let schema = vehicle_schema();
+    let defs = &schema["$defs"];
+
+    assert!(
+        defs["Drivetrain"]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&"4wd".into())
+    );
+    assert!(
+        defs["ChargerVoltageClass"]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&"800v".into())
+    );
+    assert!(defs["Source"]["properties"]["type"].is_object());
+    assert!(defs["Charging"]["properties"]["dc_charge_curve"].is_object());
+}
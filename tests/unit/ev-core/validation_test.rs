@@ -1,4 +1,4 @@
-use ev_core::{SlugName, Validate, ValidationError, Year};
+use ev_core::{Finding, Severity, SlugName, Validate, ValidationError, Year};
 
 #[test]
 fn test_slug_name_validation() {
@@ -396,3 +396,115 @@ fn test_collect_errors_multiple_errors() {
         assert!(matches!(e, ValidationError::Multiple(_)));
     }
 }
+
+#[test]
+fn test_validate_date() {
+    assert!(ev_core::validation::validate_date("2024-01-01").is_ok());
+    assert!(ev_core::validation::validate_date("2024-12-26T00:00:00Z").is_ok());
+    assert!(ev_core::validation::validate_date("2024-13-01").is_err());
+    assert!(ev_core::validation::validate_date("01/01/2024").is_err());
+}
+
+#[test]
+fn test_validate_positive() {
+    assert!(ev_core::validation::validate_positive("power", 1.0).is_ok());
+    assert!(ev_core::validation::validate_positive("power", 0.0).is_err());
+    assert!(ev_core::validation::validate_positive("power", f64::NAN).is_err());
+}
+
+#[test]
+fn test_validate_percent() {
+    assert!(ev_core::validation::validate_percent("soc", 0.0).is_ok());
+    assert!(ev_core::validation::validate_percent("soc", 100.0).is_ok());
+    assert!(ev_core::validation::validate_percent("soc", 100.5).is_err());
+    assert!(ev_core::validation::validate_percent("soc", -1.0).is_err());
+}
+
+#[test]
+fn test_validate_bounds() {
+    assert!(ev_core::validation::validate_bounds("years", 2020, 2024).is_ok());
+    assert!(ev_core::validation::validate_bounds("years", 2024, 2024).is_ok());
+    assert_eq!(
+        ev_core::validation::validate_bounds("years", 2024, 2020),
+        Err(ValidationError::InvalidBounds {
+            field: "years".to_string(),
+            lower: "2024".to_string(),
+            upper: "2020".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_push_error_flattens_multiple() {
+    let mut findings = Vec::new();
+    ev_core::validation::push_error(&mut findings, "/a", Ok(()));
+    ev_core::validation::push_error(&mut findings, "/b", Err(ValidationError::MissingSource));
+    ev_core::validation::push_error(
+        &mut findings,
+        "/c",
+        Err(ValidationError::Multiple(vec![
+            ValidationError::MissingChargePort,
+            ValidationError::MissingRatedRange,
+        ])),
+    );
+    assert_eq!(findings.len(), 3);
+    assert_eq!(findings[0].pointer, "/b");
+    assert_eq!(findings[2].pointer, "/c");
+    assert_eq!(findings[2].error, ValidationError::MissingRatedRange);
+}
+
+#[test]
+fn test_push_nested_prefixes_pointers() {
+    let mut findings = Vec::new();
+    ev_core::validation::push_nested(
+        &mut findings,
+        "/sources/0",
+        vec![Finding::new("/url", ValidationError::MissingSource)],
+    );
+    assert_eq!(findings[0].pointer, "/sources/0/url");
+}
+
+#[test]
+fn test_into_result() {
+    assert!(ev_core::validation::into_result(Vec::new()).is_ok());
+    assert_eq!(
+        ev_core::validation::into_result(vec![ValidationError::MissingSource]),
+        Err(ValidationError::MissingSource)
+    );
+    assert!(matches!(
+        ev_core::validation::into_result(vec![
+            ValidationError::MissingSource,
+            ValidationError::MissingChargePort,
+        ]),
+        Err(ValidationError::Multiple(_))
+    ));
+}
+
+#[test]
+fn test_finding_display() {
+    let finding = Finding::new(
+        "/charging/dc/max_power_kw",
+        ValidationError::NonPositive {
+            field: "dc.max_power_kw".to_string(),
+            value: "0".to_string(),
+        },
+    );
+    assert_eq!(finding.severity, Severity::Error);
+    assert_eq!(finding.code(), "non_positive");
+    assert_eq!(
+        finding.to_string(),
+        "error[non_positive] /charging/dc/max_power_kw: Value for dc.max_power_kw must be greater than zero, got 0"
+    );
+}
+
+#[test]
+fn test_slug_name_findings_pointer() {
+    let slug_name = SlugName {
+        slug: "tesla".to_string(),
+        name: "".to_string(),
+    };
+    let findings = slug_name.findings();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].pointer, "/name");
+    assert!(slug_name.validate().is_err());
+}
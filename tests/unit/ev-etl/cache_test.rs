@@ -0,0 +1,131 @@
+use ev_etl::cache::{BuildCache, ModelBuild, model_key, output_key, schema_hash};
+use ev_etl::ingest::{FileType, VehicleFile};
+use ev_etl::validate::ValidationReport;
+use serde_json::json;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn layer_file(file_slug: &str, sha256: &str) -> VehicleFile {
+    VehicleFile {
+        path: PathBuf::from(format!("tesla/model_3/2024/{file_slug}.json")),
+        make_slug: "tesla".to_string(),
+        model_slug: "model_3".to_string(),
+        year: Some(2024),
+        file_type: FileType::YearBase,
+        file_slug: file_slug.to_string(),
+        content: json!({}),
+        sha256: sha256.to_string(),
+    }
+}
+
+fn model_build(key: &str) -> ModelBuild {
+    ModelBuild {
+        key: key.to_string(),
+        vehicles: Vec::new(),
+        report: ValidationReport::new(),
+    }
+}
+
+#[test]
+fn test_model_key_changes_with_layer_content() {
+    let schema = schema_hash(&json!({"type": "object"}));
+    let before = layer_file("model_3", "aa");
+    let after = layer_file("model_3", "bb");
+
+    assert_eq!(
+        model_key(&[&before], &schema),
+        model_key(&[&before], &schema)
+    );
+    assert_ne!(
+        model_key(&[&before], &schema),
+        model_key(&[&after], &schema)
+    );
+}
+
+#[test]
+fn test_model_key_changes_with_file_set() {
+    let schema = schema_hash(&json!({"type": "object"}));
+    let base = layer_file("model_3", "aa");
+    let variant = layer_file("model_3_lr", "aa");
+
+    assert_ne!(
+        model_key(&[&base], &schema),
+        model_key(&[&base, &variant], &schema)
+    );
+}
+
+#[test]
+fn test_model_key_changes_with_schema() {
+    let file = layer_file("model_3", "aa");
+
+    assert_ne!(
+        model_key(&[&file], &schema_hash(&json!({"type": "object"}))),
+        model_key(&[&file], &schema_hash(&json!({"type": "array"})))
+    );
+}
+
+#[test]
+fn test_load_model_returns_stored_build() {
+    let dir = TempDir::new().unwrap();
+    let cache = BuildCache::open(dir.path(), true).unwrap();
+
+    cache
+        .store_model("tesla", "model_3", &model_build("k1"))
+        .unwrap();
+
+    let build = cache
+        .load_model("tesla", "model_3", "k1")
+        .expect("cache hit");
+    assert_eq!(build.key, "k1");
+    assert!(cache.load_model("tesla", "model_y", "k1").is_none());
+}
+
+#[test]
+fn test_load_model_misses_on_other_key() {
+    let dir = TempDir::new().unwrap();
+    let cache = BuildCache::open(dir.path(), true).unwrap();
+
+    cache
+        .store_model("tesla", "model_3", &model_build("k1"))
+        .unwrap();
+
+    assert!(cache.load_model("tesla", "model_3", "k2").is_none());
+}
+
+#[test]
+fn test_load_model_without_reuse() {
+    let dir = TempDir::new().unwrap();
+    let cache = BuildCache::open(dir.path(), false).unwrap();
+
+    cache
+        .store_model("tesla", "model_3", &model_build("k1"))
+        .unwrap();
+
+    assert!(cache.load_model("tesla", "model_3", "k1").is_none());
+    assert!(
+        BuildCache::open(dir.path(), true)
+            .unwrap()
+            .load_model("tesla", "model_3", "k1")
+            .is_some()
+    );
+}
+
+#[test]
+fn test_is_output_current_after_save() {
+    let dir = TempDir::new().unwrap();
+    let artifact = dir.path().join("vehicles.json");
+    std::fs::write(&artifact, "[]").unwrap();
+    let key = output_key("json", &[]).unwrap();
+
+    let mut cache = BuildCache::open(&dir.path().join("cache"), true).unwrap();
+    assert!(!cache.is_output_current(&artifact, &key));
+    cache.record_output(&artifact, key.clone());
+    cache.save().unwrap();
+
+    let cache = BuildCache::open(&dir.path().join("cache"), true).unwrap();
+    assert!(cache.is_output_current(&artifact, &key));
+    assert!(!cache.is_output_current(&artifact, &output_key("csv", &[]).unwrap()));
+
+    std::fs::remove_file(&artifact).unwrap();
+    assert!(!cache.is_output_current(&artifact, &key));
+}
@@ -1,15 +1,18 @@
 use clap::Parser;
-use ev_etl::cli::Cli;
+use ev_etl::cli::{Cli, Command, DiffFormat, ExplainFormat};
 
 #[test]
 fn test_cli_parsing_defaults() {
     let args = vec!["ev-etl", "--input", "./data"];
     let cli = Cli::parse_from(args);
 
-    assert_eq!(cli.input.to_str().unwrap(), "./data");
-    assert_eq!(cli.output.to_str().unwrap(), "./output");
-    assert_eq!(cli.formats, vec!["json", "sqlite"]);
-    assert!(!cli.validate_only);
+    assert_eq!(
+        cli.build.dataset.input_dir().unwrap().to_str().unwrap(),
+        "./data"
+    );
+    assert_eq!(cli.build.output.to_str().unwrap(), "./output");
+    assert_eq!(cli.build.formats, vec!["json", "sqlite"]);
+    assert!(!cli.build.validate_only);
     assert!(!cli.verbose);
 }
 
@@ -28,9 +31,251 @@ fn test_cli_parsing_custom() {
     ];
     let cli = Cli::parse_from(args);
 
-    assert_eq!(cli.input.to_str().unwrap(), "./in");
-    assert_eq!(cli.output.to_str().unwrap(), "./out");
-    assert_eq!(cli.formats, vec!["csv", "xml"]);
-    assert!(cli.validate_only);
+    assert_eq!(
+        cli.build.dataset.input_dir().unwrap().to_str().unwrap(),
+        "./in"
+    );
+    assert_eq!(cli.build.output.to_str().unwrap(), "./out");
+    assert_eq!(cli.build.formats, vec!["csv", "xml"]);
+    assert!(cli.build.validate_only);
     assert!(cli.verbose);
 }
+
+#[test]
+fn test_cli_parsing_schema() {
+    let args = vec![
+        "ev-etl",
+        "--input",
+        "./in",
+        "--schema",
+        "./vehicle.schema.json",
+    ];
+    let cli = Cli::parse_from(args);
+
+    assert_eq!(
+        cli.build.dataset.schema.as_deref().and_then(|p| p.to_str()),
+        Some("./vehicle.schema.json")
+    );
+}
+
+#[test]
+fn test_cli_requires_input_without_subcommand() {
+    let result = Cli::try_parse_from(vec!["ev-etl", "--formats", "json"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_cli_parsing_schema_subcommand() {
+    let cli = Cli::parse_from(vec!["ev-etl", "schema", "--output", "vehicle.schema.json"]);
+
+    assert!(cli.build.dataset.input.is_none());
+    match cli.command {
+        Some(Command::Schema(args)) => {
+            assert_eq!(
+                args.output.as_deref().and_then(|p| p.to_str()),
+                Some("vehicle.schema.json")
+            );
+        }
+        _ => panic!("expected schema subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_diff_subcommand() {
+    let cli = Cli::parse_from(vec![
+        "ev-etl",
+        "diff",
+        "old/vehicles.json",
+        "new/vehicles.json",
+        "--format",
+        "json",
+    ]);
+
+    match cli.command {
+        Some(Command::Diff(args)) => {
+            assert_eq!(args.old.to_str(), Some("old/vehicles.json"));
+            assert_eq!(args.new.to_str(), Some("new/vehicles.json"));
+            assert_eq!(args.format, DiffFormat::Json);
+            assert!(args.output.is_none());
+        }
+        _ => panic!("expected diff subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_diff_defaults_to_markdown() {
+    let cli = Cli::parse_from(vec!["ev-etl", "diff", "a.json", "b.json"]);
+
+    match cli.command {
+        Some(Command::Diff(args)) => assert_eq!(args.format, DiffFormat::Markdown),
+        _ => panic!("expected diff subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_explain_subcommand() {
+    let cli = Cli::parse_from(vec![
+        "ev-etl",
+        "explain",
+        "tesla:model_3:2024:base",
+        "-i",
+        "dataset",
+    ]);
+
+    match cli.command {
+        Some(Command::Explain(args)) => {
+            assert_eq!(args.unique_code, "tesla:model_3:2024:base");
+            assert_eq!(args.input.to_str(), Some("dataset"));
+            assert_eq!(args.format, ExplainFormat::Text);
+        }
+        _ => panic!("expected explain subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_watch_subcommand() {
+    let cli = Cli::parse_from(vec![
+        "ev-etl",
+        "watch",
+        "-i",
+        "dataset",
+        "--sqlite",
+        "vehicles.db",
+    ]);
+
+    match cli.command {
+        Some(Command::Watch(args)) => {
+            assert_eq!(args.input.to_str(), Some("dataset"));
+            assert_eq!(
+                args.sqlite.as_deref().and_then(|p| p.to_str()),
+                Some("vehicles.db")
+            );
+            assert!(args.schema.is_none());
+            assert_eq!(args.interval, 500);
+        }
+        _ => panic!("expected watch subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_jobs() {
+    let cli = Cli::parse_from(vec!["ev-etl", "--input", "./in", "--jobs", "4"]);
+    assert_eq!(cli.build.dataset.jobs.map(|jobs| jobs.get()), Some(4));
+
+    let cli = Cli::parse_from(vec!["ev-etl", "--input", "./in"]);
+    assert!(cli.build.dataset.jobs.is_none());
+
+    assert!(Cli::try_parse_from(vec!["ev-etl", "--input", "./in", "--jobs", "0"]).is_err());
+}
+
+#[test]
+fn test_into_command_defaults_to_build() {
+    let cli = Cli::parse_from(vec!["ev-etl", "--input", "./in", "--formats", "csv"]);
+
+    match cli.into_command() {
+        Command::Build(args) => {
+            assert_eq!(args.dataset.input_dir().unwrap().to_str(), Some("./in"));
+            assert_eq!(args.formats, vec!["csv"]);
+        }
+        _ => panic!("expected build"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_build_subcommand() {
+    let cli = Cli::parse_from(vec![
+        "ev-etl",
+        "build",
+        "-i",
+        "./in",
+        "-o",
+        "./out",
+        "--no-cache",
+        "--verbose",
+    ]);
+
+    assert!(cli.verbose);
+    match cli.into_command() {
+        Command::Build(args) => {
+            assert_eq!(args.dataset.input_dir().unwrap().to_str(), Some("./in"));
+            assert_eq!(args.output.to_str(), Some("./out"));
+            assert!(args.no_cache);
+        }
+        _ => panic!("expected build subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_rejects_build_options_before_subcommand() {
+    let result = Cli::try_parse_from(vec!["ev-etl", "--input", "./in", "validate"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_cli_parsing_validate_subcommand() {
+    let cli = Cli::parse_from(vec!["ev-etl", "validate", "-i", "./in", "--jobs", "2"]);
+
+    match cli.command {
+        Some(Command::Validate(args)) => {
+            assert_eq!(args.input_dir().unwrap().to_str(), Some("./in"));
+            assert_eq!(args.jobs.map(|jobs| jobs.get()), Some(2));
+        }
+        _ => panic!("expected validate subcommand"),
+    }
+    assert!(Cli::try_parse_from(vec!["ev-etl", "validate"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_lint_and_fmt_subcommands() {
+    match Cli::parse_from(vec!["ev-etl", "lint", "-i", "dataset"]).command {
+        Some(Command::Lint(args)) => assert_eq!(args.input.to_str(), Some("dataset")),
+        _ => panic!("expected lint subcommand"),
+    }
+
+    match Cli::parse_from(vec!["ev-etl", "fmt", "-i", "dataset", "--check"]).command {
+        Some(Command::Fmt(args)) => {
+            assert_eq!(args.input.to_str(), Some("dataset"));
+            assert!(args.check);
+        }
+        _ => panic!("expected fmt subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_new_subcommand() {
+    let cli = Cli::parse_from(vec![
+        "ev-etl",
+        "new",
+        "tesla",
+        "model_3",
+        "2025",
+        "--variant",
+        "long_range",
+        "-i",
+        "dataset",
+    ]);
+
+    match cli.command {
+        Some(Command::New(args)) => {
+            assert_eq!(args.make, "tesla");
+            assert_eq!(args.model, "model_3");
+            assert_eq!(args.year, 2025);
+            assert_eq!(args.variant.as_deref(), Some("long_range"));
+            assert_eq!(args.input.to_str(), Some("dataset"));
+        }
+        _ => panic!("expected new subcommand"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_stats_subcommand() {
+    let cli = Cli::parse_from(vec!["ev-etl", "stats", "-i", "dataset"]);
+
+    match cli.command {
+        Some(Command::Stats(args)) => {
+            assert_eq!(args.dataset.input_dir().unwrap().to_str(), Some("dataset"));
+            assert!(args.output.is_none());
+        }
+        _ => panic!("expected stats subcommand"),
+    }
+}
@@ -0,0 +1,101 @@
+use ev_core::ChangeKind;
+use ev_etl::diff::{diff_datasets, load_vehicles};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+use crate::others::fixtures::sample_vehicle_value;
+
+fn vehicle(code: &str, range_km: f64) -> Value {
+    sample_vehicle_value(json!({
+        "unique_code": code,
+        "range": {"rated": [{"cycle": "wltp", "range_km": range_km}]}
+    }))
+}
+
+#[test]
+fn test_diff_datasets_added_removed_changed() {
+    let old = vec![
+        vehicle("a", 500.0),
+        vehicle("b", 500.0),
+        vehicle("c", 500.0),
+    ];
+    let new = vec![
+        vehicle("b", 500.0),
+        vehicle("c", 513.0),
+        vehicle("d", 500.0),
+    ];
+
+    let diff = diff_datasets(&old, &new).unwrap();
+
+    assert_eq!(diff.added, vec!["d"]);
+    assert_eq!(diff.removed, vec!["a"]);
+    assert_eq!(diff.unchanged_count, 1);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].unique_code, "c");
+    let change = &diff.changed[0].changes[0];
+    assert_eq!(change.path, "/range/rated/0/range_km");
+    assert_eq!(change.kind, ChangeKind::Changed);
+    assert_eq!(change.old, Some(json!(500.0)));
+    assert_eq!(change.new, Some(json!(513.0)));
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn test_diff_datasets_identical() {
+    let vehicles = vec![vehicle("a", 500.0)];
+    let diff = diff_datasets(&vehicles, &vehicles).unwrap();
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.unchanged_count, 1);
+}
+
+#[test]
+fn test_diff_to_markdown() {
+    let old = vec![vehicle("a", 500.0), vehicle("c", 500.0)];
+    let new = vec![vehicle("c", 513.0), vehicle("d", 500.0)];
+
+    let markdown = diff_datasets(&old, &new).unwrap().to_markdown();
+
+    assert!(markdown.contains("2 vehicles before, 2 after: 1 added, 1 removed, 1 changed"));
+    assert!(markdown.contains("## Added\n\n- `d`"));
+    assert!(markdown.contains("## Removed\n\n- `a`"));
+    assert!(markdown.contains("### `c`"));
+    assert!(markdown.contains("| `/range/rated/0/range_km` | changed | `500.0` | `513.0` |"));
+}
+
+#[test]
+fn test_load_vehicles_artifact_and_array() {
+    let dir = TempDir::new().unwrap();
+    let artifact = dir.path().join("vehicles.json");
+    let array = dir.path().join("array.json");
+    let vehicles = json!([vehicle("a", 500.0)]);
+    std::fs::write(&artifact, json!({"vehicles": vehicles}).to_string()).unwrap();
+    std::fs::write(&array, vehicles.to_string()).unwrap();
+
+    assert_eq!(load_vehicles(&artifact).unwrap().len(), 1);
+    assert_eq!(load_vehicles(&array).unwrap().len(), 1);
+    assert!(load_vehicles(&dir.path().join("missing.json")).is_err());
+}
+
+#[test]
+fn test_diff_datasets_keys_vehicles_without_code_by_slugs() {
+    let old = vec![sample_vehicle_value(json!({}))];
+    let new = vec![sample_vehicle_value(json!({"unknown_field": true}))];
+
+    let diff = diff_datasets(&old, &new).unwrap();
+
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].unique_code, "tesla:model_3:2024:base");
+    assert_eq!(diff.changed[0].changes[0].path, "/unknown_field");
+}
+
+#[test]
+fn test_diff_datasets_rejects_duplicate_codes() {
+    let old = vec![vehicle("a", 500.0), vehicle("a", 513.0)];
+
+    let error = diff_datasets(&old, &[]).unwrap_err();
+    assert!(error.to_string().contains("Duplicate unique_code a"));
+
+    let error = diff_datasets(&[], &[json!({"year": 2024})]).unwrap_err();
+    assert!(error.to_string().contains("has no unique_code"));
+}
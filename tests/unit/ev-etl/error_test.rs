@@ -1,4 +1,5 @@
-use ev_etl::error::EtlError;
+use anyhow::Context;
+use ev_etl::error::{EXIT_ERROR, EXIT_VALIDATION_FAILED, EtlError, ValidationFailure, exit_code};
 
 #[test]
 fn test_etl_error_display() {
@@ -16,3 +17,22 @@ fn test_etl_error_display() {
         "Validation failed for test:id: missing field"
     );
 }
+
+#[test]
+fn test_exit_code_validation_failure() {
+    let error = anyhow::Error::new(ValidationFailure(
+        "1 vehicles failed validation".to_string(),
+    ));
+    assert_eq!(exit_code(&error), EXIT_VALIDATION_FAILED);
+
+    let error = Err::<(), _>(error).context("while building").unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_VALIDATION_FAILED);
+}
+
+#[test]
+fn test_exit_code_other_error() {
+    let error = std::fs::read("/nonexistent/vehicles.json")
+        .context("Failed to read")
+        .unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_ERROR);
+}
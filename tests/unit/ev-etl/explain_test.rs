@@ -0,0 +1,178 @@
+use ev_etl::explain::explain;
+use ev_etl::ingest::load_dataset;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn create_layered_dataset() -> TempDir {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let model_dir = temp_dir.path().join("tesla/model_3");
+    std::fs::create_dir_all(model_dir.join("2024")).expect("Failed to create dirs");
+
+    let base = json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "tesla", "name": "Tesla"},
+        "model": {"slug": "model_3", "name": "Model 3"},
+        "vehicle_type": "passenger_car",
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "sources": [{"type": "oem", "title": "Tesla", "url": "https://tesla.com", "accessed_at": "2024-01-01"}]
+    });
+    let year_base = json!({
+        "year": 2024,
+        "trim": {"slug": "base", "name": "Base"},
+        "powertrain": {"drivetrain": "rwd"},
+        "battery": {"pack_capacity_kwh_net": 60.0},
+        "charging": {"ac": {"max_power_kw": 11.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": 513.0}]}
+    });
+    let variant = json!({
+        "trim": {"slug": "long_range", "name": "Long Range"},
+        "variant": {"slug": "awd", "name": "AWD"},
+        "powertrain": {"drivetrain": "awd"},
+        "battery": {"pack_capacity_kwh_net": 75.0}
+    });
+
+    for (path, content) in [
+        (model_dir.join("base.json"), base),
+        (model_dir.join("2024/model_3.json"), year_base),
+        (model_dir.join("2024/model_3_long_range.json"), variant),
+    ] {
+        std::fs::write(path, content.to_string()).expect("Failed to write");
+    }
+
+    temp_dir
+}
+
+#[test]
+fn test_explain_variant_sources() {
+    let dataset = create_layered_dataset();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let explanation = explain(&files, "tesla:model_3:2024:long_range:awd").unwrap();
+
+    assert_eq!(
+        explanation.file,
+        "tesla/model_3/2024/model_3_long_range.json"
+    );
+    assert_eq!(
+        explanation.vehicle["battery"]["pack_capacity_kwh_net"],
+        json!(75.0)
+    );
+    let provenance = &explanation.provenance;
+    assert_eq!(
+        provenance.source("/make/slug"),
+        Some("tesla/model_3/base.json")
+    );
+    assert_eq!(
+        provenance.source("/year"),
+        Some("tesla/model_3/2024/model_3.json")
+    );
+    assert_eq!(
+        provenance.source("/battery/pack_capacity_kwh_net"),
+        Some("tesla/model_3/2024/model_3_long_range.json")
+    );
+
+    let overrides: Vec<_> = provenance
+        .overrides_of("/battery/pack_capacity_kwh_net")
+        .collect();
+    assert_eq!(overrides.len(), 1);
+    assert_eq!(overrides[0].value, json!(60.0));
+    assert_eq!(overrides[0].source, "tesla/model_3/2024/model_3.json");
+}
+
+#[test]
+fn test_explain_to_text() {
+    let dataset = create_layered_dataset();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let text = explain(&files, "tesla:model_3:2024:long_range:awd")
+        .unwrap()
+        .to_text();
+
+    assert!(text.starts_with(
+        "tesla:model_3:2024:long_range:awd (tesla/model_3/2024/model_3_long_range.json)\n"
+    ));
+    assert!(text.contains(
+        "/battery/pack_capacity_kwh_net = 75.0  [tesla/model_3/2024/model_3_long_range.json]\n    overrides 60.0 [tesla/model_3/2024/model_3.json], replaced by tesla/model_3/2024/model_3_long_range.json\n"
+    ));
+    assert!(text.contains("/make/name = \"Tesla\"  [tesla/model_3/base.json]\n"));
+}
+
+#[test]
+fn test_explain_year_base_and_json() {
+    let dataset = create_layered_dataset();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let explanation = explain(&files, "tesla:model_3:2024:base").unwrap();
+    let value = serde_json::to_value(&explanation).unwrap();
+
+    assert_eq!(value["file"], "tesla/model_3/2024/model_3.json");
+    assert_eq!(
+        value["sources"]["/battery/pack_capacity_kwh_net"],
+        "tesla/model_3/2024/model_3.json"
+    );
+    assert_eq!(value["overrides"], json!([]));
+}
+
+#[test]
+fn test_explain_unknown_code() {
+    let dataset = create_layered_dataset();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let error = explain(&files, "tesla:model_y:2024:base").unwrap_err();
+    assert!(error.to_string().contains("tesla:model_y:2024:base"));
+}
+
+#[test]
+fn test_explain_vehicle_that_fails_to_deserialize() {
+    let dataset = create_layered_dataset();
+    let broken = json!({
+        "trim": {"slug": "performance", "name": "Performance"},
+        "battery": {"pack_capacity_kwh_net": "seventy-five"}
+    });
+    std::fs::write(
+        dataset
+            .path()
+            .join("tesla/model_3/2024/model_3_performance.json"),
+        broken.to_string(),
+    )
+    .unwrap();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let explanation = explain(&files, "tesla:model_3:2024:performance").unwrap();
+
+    assert_eq!(
+        explanation.vehicle["battery"]["pack_capacity_kwh_net"],
+        json!("seventy-five")
+    );
+    assert_eq!(
+        explanation
+            .provenance
+            .source("/battery/pack_capacity_kwh_net"),
+        Some("tesla/model_3/2024/model_3_performance.json")
+    );
+}
+
+#[test]
+fn test_explain_ignores_other_models() {
+    let dataset = create_layered_dataset();
+    let broken_dir = dataset.path().join("tesla/model_y/2024");
+    std::fs::create_dir_all(&broken_dir).unwrap();
+    std::fs::write(
+        broken_dir.join("model_y_performance.json"),
+        json!({"trim": {"slug": "performance", "name": "Performance"}}).to_string(),
+    )
+    .unwrap();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let explanation = explain(&files, "tesla:model_3:2024:base").unwrap();
+    assert_eq!(explanation.file, "tesla/model_3/2024/model_3.json");
+}
+
+#[test]
+fn test_explain_malformed_code_is_usage_error() {
+    let dataset = create_layered_dataset();
+    let files = load_dataset(dataset.path()).unwrap();
+
+    let error = explain(&files, "tesla:model_3").unwrap_err();
+    assert_eq!(ev_etl::error::exit_code(&error), ev_etl::error::EXIT_USAGE);
+}
@@ -0,0 +1,64 @@
+use ev_etl::format::{format_dataset, format_json};
+use tempfile::TempDir;
+
+#[test]
+fn test_format_json_indents_with_four_spaces() {
+    let formatted = format_json(r#"{"make":{"slug":"tesla","name":"Tesla"},"year":2024}"#).unwrap();
+
+    assert_eq!(
+        formatted,
+        "{\n    \"make\": {\n        \"slug\": \"tesla\",\n        \"name\": \"Tesla\"\n    },\n    \"year\": 2024\n}\n"
+    );
+}
+
+#[test]
+fn test_format_json_keeps_key_order_and_literals() {
+    let formatted = format_json(r#"{"z": 1.50, "a": "x, {y}: \"z\"", "m": [ ]}"#).unwrap();
+
+    assert_eq!(
+        formatted,
+        "{\n    \"z\": 1.50,\n    \"a\": \"x, {y}: \\\"z\\\"\",\n    \"m\": []\n}\n"
+    );
+}
+
+#[test]
+fn test_format_json_is_idempotent() {
+    let once = format_json(r#"{"a": [1, {"b": {}}, [true, null]]}"#).unwrap();
+    assert_eq!(format_json(&once).unwrap(), once);
+}
+
+#[test]
+fn test_format_json_rejects_invalid_json() {
+    assert!(format_json("{ not json").is_err());
+}
+
+#[test]
+fn test_format_dataset_check_leaves_files_untouched() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("tesla/model_3/base.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{"year":2024}"#).unwrap();
+
+    let changed = format_dataset(dir.path(), true).unwrap();
+
+    assert_eq!(changed, vec![path.clone()]);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{"year":2024}"#);
+}
+
+#[test]
+fn test_format_dataset_rewrites_files() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("tesla/model_3/base.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{"year":2024}"#).unwrap();
+
+    assert_eq!(
+        format_dataset(dir.path(), false).unwrap(),
+        vec![path.clone()]
+    );
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\n    \"year\": 2024\n}\n"
+    );
+    assert!(format_dataset(dir.path(), true).unwrap().is_empty());
+}
@@ -211,3 +211,43 @@ fn test_scan_directory_variant_with_underscore() {
     assert_eq!(year_bases.len(), 1);
     assert_eq!(variants.len(), 2);
 }
+
+#[test]
+fn test_scan_directory_stable_order() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    for model in ["zoe", "megane", "twingo"] {
+        let year_dir = root.join("renault").join(model).join("2024");
+        fs::create_dir_all(&year_dir).unwrap();
+        fs::write(year_dir.join(format!("{model}.json")), "{}").unwrap();
+        fs::write(year_dir.join(format!("{model}_b.json")), "{}").unwrap();
+        fs::write(year_dir.join(format!("{model}_a.json")), "{}").unwrap();
+    }
+
+    let names: Vec<String> = scan_directory(root)
+        .unwrap()
+        .iter()
+        .map(|f| f.file_slug.clone())
+        .collect();
+
+    assert_eq!(
+        names,
+        vec![
+            "megane", "megane_a", "megane_b", "twingo", "twingo_a", "twingo_b", "zoe", "zoe_a",
+            "zoe_b"
+        ]
+    );
+}
+
+#[test]
+fn test_scan_directory_reports_first_invalid_file() {
+    let dir = tempdir().unwrap();
+    let year_dir = dir.path().join("renault").join("zoe").join("2024");
+    fs::create_dir_all(&year_dir).unwrap();
+    fs::write(year_dir.join("zoe_a.json"), "{").unwrap();
+    fs::write(year_dir.join("zoe_b.json"), "{").unwrap();
+
+    let error = format!("{:#}", scan_directory(dir.path()).unwrap_err());
+    assert!(error.contains("zoe_a.json"), "{error}");
+}
@@ -1,5 +1,7 @@
-use ev_etl::cli::Cli;
-use ev_etl::{run_pipeline, run_validation};
+use ev_etl::cli::{BuildArgs, DatasetArgs, DiffArgs, DiffFormat, SchemaArgs};
+use ev_etl::error::{EXIT_ERROR, EXIT_USAGE, EXIT_VALIDATION_FAILED, exit_code};
+use ev_etl::output::{OutputOptions, OutputRegistry, OutputWriter, VehicleSink};
+use ev_etl::{run_diff, run_pipeline, run_pipeline_with, run_schema, run_validation};
 use serde_json::json;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -34,13 +36,25 @@ fn create_valid_test_dataset() -> TempDir {
     temp_dir
 }
 
-fn create_cli(input: PathBuf, output: PathBuf, formats: Vec<String>, validate_only: bool) -> Cli {
-    Cli {
-        input,
+fn create_cli(
+    input: PathBuf,
+    output: PathBuf,
+    formats: Vec<String>,
+    validate_only: bool,
+) -> BuildArgs {
+    BuildArgs {
+        dataset: DatasetArgs {
+            input: Some(input),
+            schema: None,
+            jobs: None,
+        },
         output,
         formats,
+        output_paths: Vec::new(),
+        compress: Vec::new(),
         validate_only,
-        verbose: false,
+        no_cache: false,
+        cache_dir: None,
     }
 }
 
@@ -54,7 +68,7 @@ fn test_run_validation_success() {
         true,
     );
 
-    let result = run_validation(&cli);
+    let result = run_validation(&cli.dataset);
     assert!(result.is_ok());
 }
 
@@ -68,7 +82,7 @@ fn test_run_validation_empty_dataset() {
         true,
     );
 
-    let result = run_validation(&cli);
+    let result = run_validation(&cli.dataset);
     assert!(result.is_ok());
 }
 
@@ -188,6 +202,171 @@ fn test_run_pipeline_all_formats() {
     assert!(output_dir.path().join("statistics.json").exists());
 }
 
+fn create_multi_model_dataset() -> TempDir {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    for (make, model) in [
+        ("tesla", "model_3"),
+        ("tesla", "model_y"),
+        ("byd", "dolphin"),
+        ("byd", "seal"),
+        ("kia", "ev6"),
+        ("hyundai", "ioniq_5"),
+    ] {
+        let year_dir = temp_dir.path().join(make).join(model).join("2024");
+        std::fs::create_dir_all(&year_dir).expect("Failed to create dirs");
+        for (file, trim) in [(model.to_string(), "base"), (format!("{model}_lr"), "lr")] {
+            let vehicle = json!({
+                "schema_version": "1.0.0",
+                "make": {"slug": make, "name": make},
+                "model": {"slug": model, "name": model},
+                "year": 2024,
+                "trim": {"slug": trim, "name": trim},
+                "vehicle_type": "passenger_car",
+                "powertrain": {"drivetrain": "rwd"},
+                "battery": {"pack_capacity_kwh_net": 60.0},
+                "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+                "charging": {"ac": {"max_power_kw": 11.0}},
+                "range": {"rated": [{"cycle": "wltp", "range_km": 450.0}]},
+                "sources": [{"type": "oem", "title": make, "url": "https://example.com", "accessed_at": "2024-01-01"}]
+            });
+            std::fs::write(year_dir.join(format!("{file}.json")), vehicle.to_string())
+                .expect("Failed to write");
+        }
+    }
+
+    temp_dir
+}
+
+#[test]
+fn test_run_pipeline_output_independent_of_jobs() {
+    let input_dir = create_multi_model_dataset();
+    let formats = vec!["json".to_string(), "csv".to_string(), "xml".to_string()];
+
+    let mut outputs = Vec::new();
+    for jobs in [1, 4] {
+        let output_dir = TempDir::new().expect("Failed to create output dir");
+        let mut cli = create_cli(
+            input_dir.path().to_path_buf(),
+            output_dir.path().to_path_buf(),
+            formats.clone(),
+            false,
+        );
+        cli.dataset.jobs = std::num::NonZeroUsize::new(jobs);
+        run_pipeline(&cli).expect("pipeline should succeed");
+
+        let json: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(output_dir.path().join("vehicles.json")).unwrap(),
+        )
+        .unwrap();
+        outputs.push((
+            json["vehicles"].clone(),
+            std::fs::read_to_string(output_dir.path().join("vehicles.csv")).unwrap(),
+            std::fs::read_to_string(output_dir.path().join("vehicles.xml")).unwrap(),
+        ));
+    }
+
+    assert_eq!(outputs[0].0.as_array().unwrap().len(), 12);
+    assert_eq!(outputs[0].0[0]["unique_code"], "byd:dolphin:2024:base");
+    assert_eq!(outputs[0], outputs[1]);
+}
+
+#[test]
+fn test_run_pipeline_skips_unchanged_outputs() {
+    let input_dir = create_valid_test_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let mut cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    );
+    let json_path = output_dir.path().join("vehicles.json");
+
+    run_pipeline(&cli).expect("pipeline should succeed");
+    std::fs::write(&json_path, "stale").unwrap();
+
+    run_pipeline(&cli).expect("pipeline should succeed");
+    assert_eq!(std::fs::read_to_string(&json_path).unwrap(), "stale");
+
+    cli.no_cache = true;
+    run_pipeline(&cli).expect("pipeline should succeed");
+    assert_ne!(std::fs::read_to_string(&json_path).unwrap(), "stale");
+}
+
+#[test]
+fn test_run_pipeline_rebuilds_changed_models_only() {
+    let input_dir = create_multi_model_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    );
+    run_pipeline(&cli).expect("pipeline should succeed");
+
+    let models_dir = output_dir
+        .path()
+        .join(ev_etl::cache::DEFAULT_DIR_NAME)
+        .join("models");
+    let modified = |path: PathBuf| std::fs::metadata(path).unwrap().modified().unwrap();
+    let kia_before = modified(models_dir.join("kia/ev6.json"));
+    let byd_before = modified(models_dir.join("byd/seal.json"));
+
+    let layer_path = input_dir.path().join("kia/ev6/2024/ev6_lr.json");
+    let mut layer: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&layer_path).unwrap()).unwrap();
+    layer["range"]["rated"][0]["range_km"] = json!(520.0);
+    std::fs::write(&layer_path, layer.to_string()).unwrap();
+    run_pipeline(&cli).expect("pipeline should succeed");
+
+    assert_ne!(modified(models_dir.join("kia/ev6.json")), kia_before);
+    assert_eq!(modified(models_dir.join("byd/seal.json")), byd_before);
+
+    let full_dir = TempDir::new().expect("Failed to create output dir");
+    let mut full_cli = create_cli(
+        input_dir.path().to_path_buf(),
+        full_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    );
+    full_cli.no_cache = true;
+    run_pipeline(&full_cli).expect("pipeline should succeed");
+
+    let vehicles = |dir: &std::path::Path| -> serde_json::Value {
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("vehicles.json")).unwrap())
+                .unwrap();
+        json["vehicles"].clone()
+    };
+    let incremental = vehicles(output_dir.path());
+    assert_eq!(incremental, vehicles(full_dir.path()));
+    assert!(
+        incremental
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|v| v["range"]["rated"][0]["range_km"] == json!(520.0))
+    );
+}
+
+#[test]
+fn test_run_pipeline_duplicate_format_written_once() {
+    let input_dir = create_valid_test_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+
+    let cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["sqlite".to_string(), "sqlite".to_string()],
+        false,
+    );
+
+    run_pipeline(&cli).expect("pipeline should succeed");
+    assert!(output_dir.path().join("vehicles.db").exists());
+}
+
 #[test]
 fn test_run_pipeline_unknown_format() {
     let input_dir = create_valid_test_dataset();
@@ -196,12 +375,141 @@ fn test_run_pipeline_unknown_format() {
     let cli = create_cli(
         input_dir.path().to_path_buf(),
         output_dir.path().to_path_buf(),
-        vec!["unknown_format".to_string()],
+        vec!["json".to_string(), "unknown_format".to_string()],
         false,
     );
 
-    let result = run_pipeline(&cli);
-    assert!(result.is_ok());
+    let error = run_pipeline(&cli).unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_USAGE);
+    assert!(error.to_string().contains("unknown_format"));
+    assert!(!output_dir.path().join("vehicles.json").exists());
+}
+
+#[test]
+fn test_run_pipeline_compressed_output() {
+    let input_dir = create_valid_test_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+
+    let mut cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["json".to_string(), "csv".to_string()],
+        false,
+    );
+    cli.compress = vec!["json".to_string()];
+
+    run_pipeline(&cli).expect("pipeline should succeed");
+
+    let compressed = std::fs::File::open(output_dir.path().join("vehicles.json.gz")).unwrap();
+    let vehicles: serde_json::Value =
+        serde_json::from_reader(flate2::read::GzDecoder::new(compressed)).unwrap();
+    assert_eq!(vehicles["vehicle_count"], 1);
+    assert!(output_dir.path().join("vehicles.json.gz.sha256").exists());
+    assert!(!output_dir.path().join("vehicles.json").exists());
+    assert!(output_dir.path().join("vehicles.csv").exists());
+}
+
+#[test]
+fn test_run_pipeline_output_path() {
+    let input_dir = create_valid_test_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let export_path = output_dir.path().join("exports/openev.csv");
+
+    let mut cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["csv".to_string()],
+        false,
+    );
+    cli.output_paths = vec![("csv".to_string(), export_path.clone())];
+
+    run_pipeline(&cli).expect("pipeline should succeed");
+
+    assert!(export_path.exists());
+    assert!(!output_dir.path().join("vehicles.csv").exists());
+}
+
+#[test]
+fn test_run_pipeline_rejects_invalid_output_options() {
+    let input_dir = create_valid_test_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+
+    let mut cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["sqlite".to_string()],
+        false,
+    );
+    cli.compress = vec!["sqlite".to_string()];
+    let error = run_pipeline(&cli).unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_USAGE);
+
+    cli.compress = vec!["csv".to_string()];
+    let error = run_pipeline(&cli).unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_USAGE);
+}
+
+struct CountWriter;
+
+struct CountSink {
+    path: PathBuf,
+    count: usize,
+}
+
+impl OutputWriter for CountWriter {
+    fn name(&self) -> &'static str {
+        "count"
+    }
+
+    fn default_file_name(&self) -> &'static str {
+        "count.txt"
+    }
+
+    fn begin(
+        &self,
+        options: &OutputOptions,
+        _vehicle_count: usize,
+    ) -> anyhow::Result<Box<dyn VehicleSink>> {
+        Ok(Box::new(CountSink {
+            path: options.path.clone(),
+            count: 0,
+        }))
+    }
+}
+
+impl VehicleSink for CountSink {
+    fn write(&mut self, _vehicle: &ev_core::Vehicle) -> anyhow::Result<()> {
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> anyhow::Result<()> {
+        std::fs::write(&self.path, self.count.to_string())?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_run_pipeline_with_registered_writer() {
+    let input_dir = create_valid_test_dataset();
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["count".to_string()],
+        false,
+    );
+
+    assert!(run_pipeline(&cli).is_err());
+
+    let mut registry = OutputRegistry::default();
+    assert!(registry.register(CountWriter).is_none());
+    run_pipeline_with(&cli, &registry).expect("pipeline should succeed");
+
+    assert_eq!(
+        std::fs::read_to_string(output_dir.path().join("count.txt")).unwrap(),
+        "1"
+    );
 }
 
 #[test]
@@ -280,7 +588,7 @@ fn test_run_validation_with_invalid_vehicle() {
         true,
     );
 
-    let result = run_validation(&cli);
+    let result = run_validation(&cli.dataset);
     // Should fail because the vehicle is invalid
     assert!(result.is_err());
 }
@@ -351,3 +659,274 @@ fn test_run_pipeline_fails_on_invalid_vehicles() {
     let result = run_pipeline(&cli);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_run_pipeline_writes_validation_report() {
+    let input_dir = create_valid_test_dataset();
+    let base = input_dir.path();
+    std::fs::create_dir_all(base.join("byd/dolphin/2024")).expect("Failed to create dirs");
+
+    let inconsistent_vehicle = json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "byd", "name": "BYD"},
+        "model": {"slug": "dolphin", "name": "Dolphin"},
+        "year": 2024,
+        "trim": {"slug": "base", "name": "Base"},
+        "vehicle_type": "passenger_car",
+        "powertrain": {"drivetrain": "fwd"},
+        "battery": {"pack_capacity_kwh_gross": 60.4},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {"dc": {"max_power_kw": 88.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": 427.0}]},
+        "sources": [{"type": "oem", "title": "BYD", "url": "https://byd.com", "accessed_at": "2024-01-01"}]
+    });
+    std::fs::write(
+        base.join("byd/dolphin/2024/dolphin.json"),
+        serde_json::to_string_pretty(&inconsistent_vehicle).unwrap(),
+    )
+    .expect("Failed to write");
+    // The flagged value comes from the model base layer.
+    std::fs::write(
+        base.join("byd/dolphin/base.json"),
+        json!({"battery": {"pack_capacity_kwh_net": 70.0}}).to_string(),
+    )
+    .expect("Failed to write");
+
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let cli = create_cli(
+        base.to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    );
+
+    run_pipeline(&cli).expect("pipeline should skip invalid vehicles");
+
+    let content = std::fs::read_to_string(output_dir.path().join("validation_report.json"))
+        .expect("report should be written");
+    let report: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(report["total_vehicles"], 2);
+    assert_eq!(report["valid_count"], 1);
+    assert_eq!(report["errors"][0]["vehicle_id"], "byd:dolphin:2024:base");
+    assert_eq!(report["errors"][0]["error_type"], "exceeds_limit");
+    assert_eq!(report["errors"][0]["severity"], "error");
+    assert_eq!(report["errors"][0]["file"], "byd/dolphin/base.json");
+    assert_eq!(
+        report["errors"][0]["path"],
+        "/battery/pack_capacity_kwh_net"
+    );
+}
+
+#[test]
+fn test_run_validation_with_schema_violation() {
+    let input_dir = create_valid_test_dataset();
+    let schema_dir = TempDir::new().expect("Failed to create temp dir");
+    let schema_path = schema_dir.path().join("vehicle.schema.json");
+    std::fs::write(
+        &schema_path,
+        json!({
+            "type": "object",
+            "properties": {"year": {"type": "integer", "minimum": 2025}}
+        })
+        .to_string(),
+    )
+    .expect("Failed to write schema");
+
+    let mut cli = create_cli(
+        input_dir.path().to_path_buf(),
+        PathBuf::from("/tmp/unused"),
+        vec!["json".to_string()],
+        true,
+    );
+    cli.dataset.schema = Some(schema_path);
+
+    let error = run_validation(&cli.dataset).unwrap_err();
+    assert_eq!(error.to_string(), "2 schema violation(s) in 2 file(s)");
+}
+
+#[test]
+fn test_run_validation_with_conforming_schema() {
+    let input_dir = create_valid_test_dataset();
+    let schema_dir = TempDir::new().expect("Failed to create temp dir");
+    let schema_path = schema_dir.path().join("vehicle.schema.json");
+    std::fs::write(
+        &schema_path,
+        json!({
+            "type": "object",
+            "required": ["make", "model", "year"],
+            "properties": {"year": {"type": "integer"}}
+        })
+        .to_string(),
+    )
+    .expect("Failed to write schema");
+
+    let mut cli = create_cli(
+        input_dir.path().to_path_buf(),
+        PathBuf::from("/tmp/unused"),
+        vec!["json".to_string()],
+        true,
+    );
+    cli.dataset.schema = Some(schema_path);
+
+    assert!(run_validation(&cli.dataset).is_ok());
+}
+
+#[test]
+fn test_run_schema_writes_file() {
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let path = output_dir.path().join("vehicle.schema.json");
+
+    run_schema(&SchemaArgs {
+        output: Some(path.clone()),
+    })
+    .expect("schema should be written");
+
+    let schema: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(schema, ev_core::schema::vehicle_schema());
+}
+
+#[test]
+fn test_run_diff_between_pipeline_runs() {
+    let input_dir = create_valid_test_dataset();
+    let old_dir = TempDir::new().expect("Failed to create output dir");
+    let new_dir = TempDir::new().expect("Failed to create output dir");
+
+    run_pipeline(&create_cli(
+        input_dir.path().to_path_buf(),
+        old_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    ))
+    .expect("old release should build");
+
+    let base_path = input_dir.path().join("tesla/model_3/2024/model_3.json");
+    let mut base: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&base_path).unwrap()).unwrap();
+    base["battery"]["pack_capacity_kwh_net"] = json!(62.5);
+    std::fs::write(&base_path, base.to_string()).unwrap();
+
+    run_pipeline(&create_cli(
+        input_dir.path().to_path_buf(),
+        new_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    ))
+    .expect("new release should build");
+
+    let report = new_dir.path().join("diff.json");
+    run_diff(&DiffArgs {
+        old: old_dir.path().join("vehicles.json"),
+        new: new_dir.path().join("vehicles.json"),
+        format: DiffFormat::Json,
+        output: Some(report.clone()),
+    })
+    .expect("diff should be written");
+
+    let diff: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(diff["added"], json!([]));
+    assert_eq!(diff["removed"], json!([]));
+    assert_eq!(diff["changed"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        diff["changed"][0]["changes"][0],
+        json!({
+            "path": "/battery/pack_capacity_kwh_net",
+            "kind": "changed",
+            "old": 60.0,
+            "new": 62.5
+        })
+    );
+}
+
+#[test]
+fn test_bundled_schema_rejects_invalid_layer_file() {
+    let input_dir = create_valid_test_dataset();
+    std::fs::write(
+        input_dir
+            .path()
+            .join("tesla/model_3/2024/model_3_performance.json"),
+        json!({"battery": {"pack_capacity_kwh_net": "78"}}).to_string(),
+    )
+    .expect("Failed to write");
+
+    let cli = create_cli(
+        input_dir.path().to_path_buf(),
+        PathBuf::from("/tmp/unused"),
+        vec!["json".to_string()],
+        true,
+    );
+
+    let error = run_validation(&cli.dataset).unwrap_err();
+    assert!(error.to_string().contains("schema violation"));
+}
+
+#[test]
+fn test_pipeline_reports_schema_violations() {
+    let input_dir = create_valid_test_dataset();
+    std::fs::write(
+        input_dir
+            .path()
+            .join("tesla/model_3/2024/model_3_performance.json"),
+        json!({"battery": {"pack_capacity_kwh_net": "78"}}).to_string(),
+    )
+    .expect("Failed to write");
+
+    let output_dir = TempDir::new().expect("Failed to create output dir");
+    let cli = create_cli(
+        input_dir.path().to_path_buf(),
+        output_dir.path().to_path_buf(),
+        vec!["json".to_string()],
+        false,
+    );
+
+    let error = run_pipeline(&cli).unwrap_err();
+    assert_eq!(
+        ev_etl::error::exit_code(&error),
+        ev_etl::error::EXIT_VALIDATION_FAILED
+    );
+
+    let content = std::fs::read_to_string(output_dir.path().join("validation_report.json"))
+        .expect("report should be written");
+    let report: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert!(report["error_count"].as_u64().unwrap() > 0);
+    let layer_error = report["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|error| error["file"] == "tesla/model_3/2024/model_3_performance.json")
+        .expect("layer file violation should be reported");
+    assert_eq!(layer_error["error_type"], "schema");
+    assert_eq!(layer_error["path"], "/battery/pack_capacity_kwh_net");
+}
+
+#[test]
+fn test_run_validation_exit_codes() {
+    let input_dir = create_valid_test_dataset();
+    std::fs::write(
+        input_dir
+            .path()
+            .join("tesla/model_3/2024/model_3_broken.json"),
+        "{ not json",
+    )
+    .unwrap();
+    let cli = create_cli(
+        input_dir.path().to_path_buf(),
+        PathBuf::from("/tmp/unused"),
+        vec!["json".to_string()],
+        true,
+    );
+
+    let error = run_validation(&cli.dataset).unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_VALIDATION_FAILED);
+
+    let mut cli = create_cli(
+        create_valid_test_dataset().path().to_path_buf(),
+        PathBuf::from("/tmp/unused"),
+        vec!["json".to_string()],
+        true,
+    );
+    cli.dataset.schema = Some(input_dir.path().join("missing.schema.json"));
+    let error = run_validation(&cli.dataset).unwrap_err();
+    assert_eq!(exit_code(&error), EXIT_ERROR);
+}
@@ -0,0 +1,83 @@
+use ev_etl::format::format_json;
+use ev_etl::lint::lint_dataset;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write(dataset: &Path, file: &str, content: &str) {
+    let path = dataset.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, format_json(content).unwrap()).unwrap();
+}
+
+#[test]
+fn test_lint_dataset_clean() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "tesla/model_3/base.json",
+        r#"{"make": {"slug": "tesla", "name": "Tesla"}, "model": {"slug": "model_3", "name": "Model 3"}}"#,
+    );
+    write(
+        dir.path(),
+        "tesla/model_3/2024/model_3.json",
+        r#"{"year": 2024}"#,
+    );
+
+    assert!(lint_dataset(dir.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_lint_dataset_reports_ignored_file() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "tesla/model_3/2024/model_3.json",
+        r#"{"year": 2024}"#,
+    );
+    write(dir.path(), "tesla/model_3/notes.json", r#"{}"#);
+
+    let findings = lint_dataset(dir.path()).unwrap();
+
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].code, "ignored_file");
+    assert_eq!(findings[0].file, "tesla/model_3/notes.json");
+}
+
+#[test]
+fn test_lint_dataset_reports_unformatted_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("tesla/model_3/2024/model_3.json");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{"year":2024}"#).unwrap();
+
+    let findings = lint_dataset(dir.path()).unwrap();
+
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].code, "unformatted");
+}
+
+#[test]
+fn test_lint_dataset_reports_directory_mismatch() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "tesla/model_3/base.json",
+        r#"{"make": {"slug": "tesla", "name": "Tesla"}, "model": {"slug": "model_y", "name": "Model Y"}}"#,
+    );
+    write(
+        dir.path(),
+        "tesla/model_3/2024/model_3.json",
+        r#"{"year": 2025}"#,
+    );
+
+    let findings = lint_dataset(dir.path()).unwrap();
+    let messages: Vec<String> = findings.iter().map(ToString::to_string).collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "tesla/model_3/2024/model_3.json: /year is 2025 but the directory is 2024 [directory_mismatch]",
+            "tesla/model_3/base.json: /model/slug is model_y but the directory is model_3 [directory_mismatch]",
+        ]
+    );
+}
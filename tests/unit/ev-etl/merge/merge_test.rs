@@ -141,6 +141,7 @@ fn test_vehicle_file_debug() {
         file_type: FileType::YearBase,
         file_slug: "model_3".to_string(),
         content: json!({}),
+        sha256: String::new(),
     };
 
     let debug_str = format!("{:?}", file);
@@ -304,3 +305,140 @@ fn test_merge_all_invalid_variant_vehicle() {
     let vehicles = ev_etl::merge::merge_all(&files);
     assert!(vehicles.is_err());
 }
+
+#[test]
+fn test_merge_documents_labels_leaf_files() {
+    let temp_dir = create_test_dataset();
+    let files = ev_etl::ingest::load_dataset(temp_dir.path()).expect("Failed to load dataset");
+    let documents = ev_etl::merge::merge_documents(&files).expect("Failed to merge");
+
+    let labels: Vec<_> = documents.iter().map(|d| d.label.as_str()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "tesla/model_3/2024/model_3.json",
+            "tesla/model_3/2024/model_3_long_range.json"
+        ]
+    );
+}
+
+#[test]
+fn test_into_vehicles_sets_unique_code_from_vehicle_id() {
+    let temp_dir = create_test_dataset();
+    let files = ev_etl::ingest::load_dataset(temp_dir.path()).expect("Failed to load dataset");
+    let vehicles = ev_etl::merge::merge_all(&files).expect("Failed to merge");
+
+    let codes: Vec<_> = vehicles
+        .iter()
+        .map(|v| v.unique_code.as_deref().unwrap())
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            "tesla:model_3:2024:base",
+            "tesla:model_3:2024:long_range:awd"
+        ]
+    );
+}
+
+#[test]
+fn test_into_vehicles_rejects_duplicate_unique_code() {
+    let temp_dir = create_test_dataset();
+    std::fs::write(
+        temp_dir
+            .path()
+            .join("tesla/model_3/2024/model_3_performance.json"),
+        json!({"powertrain": {"drivetrain": "awd"}}).to_string(),
+    )
+    .expect("Failed to write variant");
+
+    let files = ev_etl::ingest::load_dataset(temp_dir.path()).expect("Failed to load dataset");
+    let error = ev_etl::merge::merge_all(&files).unwrap_err();
+
+    assert!(error.to_string().contains(
+        "tesla/model_3/2024/model_3_performance.json: duplicate unique_code tesla:model_3:2024:base (also tesla/model_3/2024/model_3.json)"
+    ));
+}
+
+#[test]
+fn test_into_vehicles_reports_document_label() {
+    let files = vec![VehicleFile {
+        path: PathBuf::from("byd/dolphin/2024/dolphin.json"),
+        make_slug: "byd".to_string(),
+        model_slug: "dolphin".to_string(),
+        year: Some(2024),
+        file_type: FileType::YearBase,
+        file_slug: "dolphin".to_string(),
+        content: json!({"make": {"slug": "byd", "name": "BYD"}}),
+        sha256: String::new(),
+    }];
+    let documents = ev_etl::merge::merge_documents(&files).expect("Failed to merge");
+
+    let error = ev_etl::merge::into_vehicles(documents).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("byd/dolphin/2024/dolphin.json: missing field")
+    );
+}
+
+#[test]
+fn test_merge_all_applies_directives() {
+    let temp_dir = create_test_dataset();
+    std::fs::write(
+        temp_dir
+            .path()
+            .join("tesla/model_3/2024/model_3_long_range.json"),
+        json!({
+            "trim": {"slug": "long_range", "name": "Long Range"},
+            "charging": {"ac": null, "dc": {"max_power_kw": 250.0}},
+            "charge_ports": {"$append": [{"kind": "ac_only", "connector": "type2"}]},
+            "range": {"rated": {"$merge": [
+                {"cycle": "wltp", "range_km": 629.0},
+                {"cycle": "epa", "range_km": 547.0}
+            ]}}
+        })
+        .to_string(),
+    )
+    .expect("Failed to write variant");
+
+    let files = ev_etl::ingest::load_dataset(temp_dir.path()).expect("Failed to load dataset");
+    let documents = ev_etl::merge::merge_documents(&files).expect("Failed to merge");
+    let variant = &documents[1].content;
+
+    assert_eq!(variant["charging"], json!({"dc": {"max_power_kw": 250.0}}));
+    assert_eq!(variant["charge_ports"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        variant["range"]["rated"],
+        json!([
+            {"cycle": "wltp", "range_km": 629.0},
+            {"cycle": "epa", "range_km": 547.0}
+        ])
+    );
+
+    let vehicles = ev_etl::merge::merge_all(&files).expect("Failed to merge");
+    assert_eq!(vehicles.len(), 2);
+}
+
+#[test]
+fn test_merge_documents_rejects_null_array_item() {
+    let temp_dir = create_test_dataset();
+    std::fs::write(
+        temp_dir
+            .path()
+            .join("tesla/model_3/2024/model_3_long_range.json"),
+        json!({
+            "trim": {"slug": "long_range", "name": "Long Range"},
+            "charge_ports": {"$append": [null]}
+        })
+        .to_string(),
+    )
+    .expect("Failed to write variant");
+
+    let files = ev_etl::ingest::load_dataset(temp_dir.path()).expect("Failed to load dataset");
+    let error = ev_etl::merge::merge_documents(&files).unwrap_err();
+
+    assert!(error.to_string().contains(
+        "tesla/model_3/2024/model_3_long_range.json: array item at /charge_ports/$append/0 is null or a directive"
+    ));
+}
@@ -3,3 +3,6 @@ mod strategy_test;
 
 #[path = "merge_test.rs"]
 mod merge_test;
+
+#[path = "provenance_test.rs"]
+mod provenance_test;
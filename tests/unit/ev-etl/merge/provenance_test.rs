@@ -0,0 +1,216 @@
+use ev_etl::merge::{Override, Provenance, deep_merge, deep_merge_traced};
+use serde_json::json;
+
+fn trace(layers: &[(&str, serde_json::Value)]) -> (serde_json::Value, Provenance) {
+    let mut provenance = Provenance::default();
+    let mut merged = json!({});
+    for (source, layer) in layers {
+        merged = deep_merge_traced(&merged, layer, source, &mut provenance);
+    }
+    (merged, provenance)
+}
+
+#[test]
+fn test_deep_merge_traced_matches_deep_merge() {
+    let base =
+        json!({"battery": {"pack_capacity_kwh_net": 60.0}, "charge_ports": [{"kind": "combo"}]});
+    let overlay = json!({"battery": {"chemistry": "LFP"}, "charge_ports": []});
+
+    let (merged, _) = trace(&[
+        ("base.json", base.clone()),
+        ("model_3.json", overlay.clone()),
+    ]);
+
+    assert_eq!(merged, deep_merge(&deep_merge(&json!({}), &base), &overlay));
+}
+
+#[test]
+fn test_deep_merge_traced_records_leaf_sources() {
+    let (_, provenance) = trace(&[
+        (
+            "base.json",
+            json!({"make": {"slug": "tesla", "name": "Tesla"}, "year": 2023}),
+        ),
+        (
+            "model_3.json",
+            json!({"year": 2024, "battery": {"pack_capacity_kwh_net": 60.0}}),
+        ),
+    ]);
+
+    assert_eq!(provenance.source("/make/slug"), Some("base.json"));
+    assert_eq!(provenance.source("/make/name"), Some("base.json"));
+    assert_eq!(provenance.source("/year"), Some("model_3.json"));
+    assert_eq!(
+        provenance.source("/battery/pack_capacity_kwh_net"),
+        Some("model_3.json")
+    );
+    assert_eq!(provenance.source("/battery"), None);
+}
+
+#[test]
+fn test_deep_merge_traced_records_overrides() {
+    let (_, provenance) = trace(&[
+        (
+            "base.json",
+            json!({"year": 2023, "make": {"slug": "tesla"}}),
+        ),
+        ("model_3.json", json!({"year": 2024})),
+        ("model_3_awd.json", json!({"year": 2025})),
+    ]);
+
+    let overrides: Vec<&Override> = provenance.overrides_of("/year").collect();
+    assert_eq!(overrides.len(), 2);
+    assert_eq!(overrides[0].value, json!(2023));
+    assert_eq!(overrides[0].source, "base.json");
+    assert_eq!(overrides[0].overridden_by, "model_3.json");
+    assert_eq!(overrides[1].value, json!(2024));
+    assert_eq!(overrides[1].overridden_by, "model_3_awd.json");
+    assert_eq!(provenance.overrides_of("/make/slug").count(), 0);
+}
+
+#[test]
+fn test_deep_merge_traced_array_replacement() {
+    let (_, provenance) = trace(&[
+        (
+            "base.json",
+            json!({"charge_ports": [{"kind": "combo"}, {"kind": "ac_only"}]}),
+        ),
+        ("model_3.json", json!({"charge_ports": [{"kind": "combo"}]})),
+    ]);
+
+    assert_eq!(
+        provenance.source("/charge_ports/0/kind"),
+        Some("model_3.json")
+    );
+    assert_eq!(provenance.source("/charge_ports/1/kind"), None);
+    assert_eq!(
+        provenance.overrides,
+        vec![Override {
+            path: "/charge_ports/1/kind".to_string(),
+            value: json!("ac_only"),
+            source: "base.json".to_string(),
+            overridden_by: "model_3.json".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_deep_merge_traced_empty_containers_are_leaves() {
+    let (_, provenance) = trace(&[
+        ("base.json", json!({"sources": [], "battery": {}})),
+        ("model_3.json", json!({"battery": {"chemistry": "LFP"}})),
+    ]);
+
+    assert_eq!(provenance.source("/sources"), Some("base.json"));
+    assert_eq!(provenance.source("/battery"), None);
+    assert_eq!(
+        provenance.source("/battery/chemistry"),
+        Some("model_3.json")
+    );
+    assert!(provenance.overrides.is_empty());
+}
+
+#[test]
+fn test_deep_merge_traced_escapes_pointer_tokens() {
+    let (_, provenance) = trace(&[("base.json", json!({"a/b": {"c~d": 1}}))]);
+
+    assert_eq!(provenance.source("/a~1b/c~0d"), Some("base.json"));
+}
+
+#[test]
+fn test_deep_merge_traced_matches_deep_merge_with_directives() {
+    let base = json!({
+        "range": {"rated": [{"cycle": "wltp", "range_km": 513.0}, {"cycle": "epa", "range_km": 438.0}]},
+        "tags": ["b"],
+        "battery": {"chemistry": "NMC"}
+    });
+    let overlay = json!({
+        "range": {"rated": {"$merge": [{"cycle": "epa", "$delete": true}, {"cycle": "cltc", "range_km": 606.0}]}},
+        "tags": {"$prepend": ["a"]},
+        "battery": {"chemistry": null}
+    });
+
+    let (merged, _) = trace(&[
+        ("base.json", base.clone()),
+        ("model_3.json", overlay.clone()),
+    ]);
+
+    assert_eq!(merged, deep_merge(&deep_merge(&json!({}), &base), &overlay));
+}
+
+#[test]
+fn test_deep_merge_traced_moves_sources_with_items() {
+    let (_, provenance) = trace(&[
+        (
+            "base.json",
+            json!({"tags": ["b"], "range": {"rated": [
+                {"cycle": "wltp", "range_km": 513.0},
+                {"cycle": "epa", "range_km": 438.0}
+            ]}}),
+        ),
+        (
+            "model_3.json",
+            json!({
+                "tags": {"$prepend": ["a"]},
+                "range": {"rated": {"$merge": [
+                    {"cycle": "wltp", "$delete": true},
+                    {"cycle": "epa", "range_km": 440.0}
+                ]}}
+            }),
+        ),
+    ]);
+
+    assert_eq!(provenance.source("/tags/0"), Some("model_3.json"));
+    assert_eq!(provenance.source("/tags/1"), Some("base.json"));
+    // The merge key is restated by the layer, so it is credited with it.
+    assert_eq!(
+        provenance.source("/range/rated/0/cycle"),
+        Some("model_3.json")
+    );
+    assert_eq!(
+        provenance.source("/range/rated/0/range_km"),
+        Some("model_3.json")
+    );
+    assert_eq!(provenance.source("/range/rated/1/cycle"), None);
+
+    let replaced: Vec<&str> = provenance
+        .overrides
+        .iter()
+        .map(|replaced| replaced.path.as_str())
+        .collect();
+    // The EPA value is overridden where it now sits; the deleted WLTP item keeps
+    // its former pointer.
+    assert_eq!(
+        replaced,
+        vec![
+            "/range/rated/0/range_km",
+            "/range/rated/0/cycle",
+            "/range/rated/0/range_km"
+        ]
+    );
+}
+
+#[test]
+fn test_provenance_prune_drops_deleted_fields() {
+    let (mut merged, mut provenance) = trace(&[
+        (
+            "base.json",
+            json!({"battery": {"chemistry": "NMC", "pack_capacity_kwh_net": 75.0}}),
+        ),
+        (
+            "model_3.json",
+            json!({"battery": {"chemistry": {"$delete": true}}}),
+        ),
+    ]);
+    ev_etl::merge::remove_null_values(&mut merged);
+    provenance.prune(&merged);
+
+    assert_eq!(provenance.source("/battery/chemistry"), None);
+    assert_eq!(
+        provenance
+            .overrides_of("/battery/chemistry")
+            .next()
+            .map(|o| &o.value),
+        Some(&json!("NMC"))
+    );
+}
@@ -157,19 +157,19 @@ fn test_deep_merge_non_object_base() {
 }
 
 #[test]
-fn test_merge_arrays_replace() {
-    let base = vec![json!("a"), json!("b")];
-    let overlay = vec![json!("c")];
-    let result = ev_etl::merge::merge_arrays_replace(&base, &overlay);
-    assert_eq!(result, vec![json!("c")]);
+fn test_deep_merge_array_replaces_base() {
+    let base = json!({"tags": ["a", "b"]});
+    let overlay = json!({"tags": ["c"]});
+    let result = deep_merge(&base, &overlay);
+    assert_eq!(result, json!({"tags": ["c"]}));
 }
 
 #[test]
-fn test_merge_arrays_replace_empty_overlay() {
-    let base = vec![json!("a"), json!("b")];
-    let overlay: Vec<serde_json::Value> = vec![];
-    let result = ev_etl::merge::merge_arrays_replace(&base, &overlay);
-    assert!(result.is_empty());
+fn test_deep_merge_array_replaced_by_empty_overlay() {
+    let base = json!({"tags": ["a", "b"]});
+    let overlay = json!({"tags": []});
+    let result = deep_merge(&base, &overlay);
+    assert_eq!(result, json!({"tags": []}));
 }
 
 #[test]
@@ -212,3 +212,204 @@ fn test_remove_null_values_scalar() {
     ev_etl::merge::remove_null_values(&mut value);
     assert_eq!(value, json!(42));
 }
+
+#[test]
+fn test_deep_merge_null_deletes_field() {
+    let base = json!({"battery": {"chemistry": "NMC", "pack_capacity_kwh_net": 75.0}});
+    let overlay = json!({"battery": {"chemistry": null}});
+
+    let mut result = deep_merge(&base, &overlay);
+    ev_etl::merge::remove_null_values(&mut result);
+
+    assert_eq!(result, json!({"battery": {"pack_capacity_kwh_net": 75.0}}));
+}
+
+#[test]
+fn test_deep_merge_delete_marker() {
+    let base = json!({"pricing": {"msrp": []}, "year": 2024});
+    let overlay = json!({"pricing": {"$delete": true}});
+
+    let mut result = deep_merge(&base, &overlay);
+    ev_etl::merge::remove_null_values(&mut result);
+
+    assert_eq!(result, json!({"year": 2024}));
+}
+
+#[test]
+fn test_deep_merge_deleted_field_can_be_set_again() {
+    let base = deep_merge(&json!({"a": {"b": 1}}), &json!({"a": null}));
+    let result = deep_merge(&base, &json!({"a": {"c": 2}}));
+
+    assert_eq!(result, json!({"a": {"c": 2}}));
+}
+
+#[test]
+fn test_deep_merge_append_and_prepend() {
+    let base = json!({"tags": ["b"]});
+
+    let appended = deep_merge(&base, &json!({"tags": {"$append": ["c"]}}));
+    assert_eq!(appended, json!({"tags": ["b", "c"]}));
+
+    let both = deep_merge(
+        &base,
+        &json!({"tags": {"$prepend": ["a"], "$append": ["c"]}}),
+    );
+    assert_eq!(both, json!({"tags": ["a", "b", "c"]}));
+}
+
+#[test]
+fn test_deep_merge_append_without_inherited_array() {
+    let result = deep_merge(&json!({}), &json!({"tags": {"$append": ["a"]}}));
+    assert_eq!(result, json!({"tags": ["a"]}));
+}
+
+#[test]
+fn test_deep_merge_merge_by_explicit_key() {
+    let base = json!({"motors": [
+        {"position": "front", "power_kw": 150.0},
+        {"position": "rear", "power_kw": 200.0}
+    ]});
+    let overlay = json!({"motors": {
+        "$merge": [{"position": "rear", "power_kw": 250.0}, {"position": "middle", "power_kw": 10.0}],
+        "$key": "position"
+    }});
+
+    let result = deep_merge(&base, &overlay);
+
+    assert_eq!(
+        result["motors"],
+        json!([
+            {"position": "front", "power_kw": 150.0},
+            {"position": "rear", "power_kw": 250.0},
+            {"position": "middle", "power_kw": 10.0}
+        ])
+    );
+}
+
+#[test]
+fn test_deep_merge_merge_by_default_keys() {
+    let base = json!({
+        "range": {"rated": [
+            {"cycle": "wltp", "range_km": 513.0},
+            {"cycle": "epa", "range_km": 438.0}
+        ]},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}]
+    });
+    let overlay = json!({
+        "range": {"rated": {"$merge": [{"cycle": "wltp", "range_km": 629.0}]}},
+        "charge_ports": {"$merge": [{"connector": "ccs2", "location": {"side": "left"}}]}
+    });
+
+    let result = deep_merge(&base, &overlay);
+
+    assert_eq!(
+        result["range"]["rated"],
+        json!([
+            {"cycle": "wltp", "range_km": 629.0},
+            {"cycle": "epa", "range_km": 438.0}
+        ])
+    );
+    assert_eq!(
+        result["charge_ports"],
+        json!([{"kind": "combo", "connector": "ccs2", "location": {"side": "left"}}])
+    );
+}
+
+#[test]
+fn test_deep_merge_merge_deletes_keyed_item() {
+    let base = json!({"range": {"rated": [
+        {"cycle": "wltp", "range_km": 513.0},
+        {"cycle": "epa", "range_km": 438.0}
+    ]}});
+    let overlay = json!({"range": {"rated": {"$merge": [{"cycle": "epa", "$delete": true}]}}});
+
+    let result = deep_merge(&base, &overlay);
+
+    assert_eq!(
+        result["range"]["rated"],
+        json!([{"cycle": "wltp", "range_km": 513.0}])
+    );
+}
+
+#[test]
+fn test_deep_merge_merge_by_position_without_key() {
+    let base = json!({"values": [{"a": 1}, {"a": 2}]});
+    let overlay = json!({"values": {"$merge": [{"b": 1}, {"b": 2}, {"b": 3}]}});
+
+    let result = deep_merge(&base, &overlay);
+
+    assert_eq!(
+        result["values"],
+        json!([{"a": 1, "b": 1}, {"a": 2, "b": 2}, {"b": 3}])
+    );
+}
+
+#[test]
+fn test_deep_merge_merge_by_position_after_delete() {
+    let base = json!({"values": [{"a": 0}, {"a": 1}, {"a": 2}]});
+    let overlay = json!({"values": {"$merge": [{"$delete": true}, {"x": 1}]}});
+
+    let result = deep_merge(&base, &overlay);
+
+    assert_eq!(result["values"], json!([{"a": 1, "x": 1}, {"a": 2}]));
+}
+
+#[test]
+fn test_invalid_array_item() {
+    use ev_etl::merge::invalid_array_item;
+
+    assert_eq!(
+        invalid_array_item(&json!({"tags": [1, null]})),
+        Some("/tags/1".to_string())
+    );
+    assert_eq!(
+        invalid_array_item(&json!({"tags": {"$append": [{"$delete": true}]}})),
+        Some("/tags/$append/0".to_string())
+    );
+    assert_eq!(
+        invalid_array_item(&json!({"tags": {"$merge": [null]}})),
+        Some("/tags/$merge/0".to_string())
+    );
+    assert_eq!(
+        invalid_array_item(&json!({"tags": {"$merge": [{"$delete": true}, {"a": [1]}]}})),
+        None
+    );
+    assert_eq!(invalid_array_item(&json!({"a": null, "b": [[1]]})), None);
+}
+
+#[test]
+fn test_deep_merge_plain_array_still_replaces() {
+    let base = json!({"tags": ["a", "b"]});
+    let result = deep_merge(&base, &json!({"tags": ["c"]}));
+    assert_eq!(result, json!({"tags": ["c"]}));
+}
+
+#[test]
+fn test_directive_parse() {
+    use ev_etl::merge::Directive;
+
+    assert_eq!(Directive::parse(&json!(null)), Some(Directive::Delete));
+    assert_eq!(
+        Directive::parse(&json!({"$delete": true})),
+        Some(Directive::Delete)
+    );
+    assert_eq!(
+        Directive::parse(&json!({"$merge": [], "$key": "cycle"})),
+        Some(Directive::MergeBy {
+            items: &[],
+            key: Some("cycle")
+        })
+    );
+    assert_eq!(Directive::parse(&json!({})), None);
+    assert_eq!(Directive::parse(&json!({"$unknown": 1})), None);
+    assert_eq!(Directive::parse(&json!({"cycle": "wltp"})), None);
+}
+
+#[test]
+fn test_default_merge_key() {
+    use ev_etl::merge::default_merge_key;
+
+    assert_eq!(default_merge_key("/range/rated"), Some("cycle"));
+    assert_eq!(default_merge_key("/charge_ports"), Some("connector"));
+    assert_eq!(default_merge_key("/powertrain/motors"), None);
+}
@@ -1,11 +1,25 @@
+#[path = "cache_test.rs"]
+mod cache_test;
 #[path = "cli_test.rs"]
 mod cli_test;
+#[path = "diff_test.rs"]
+mod diff_test;
 #[path = "error_test.rs"]
 mod error_test;
+#[path = "explain_test.rs"]
+mod explain_test;
+#[path = "format_test.rs"]
+mod format_test;
 pub mod ingest;
 #[path = "lib_test.rs"]
 mod lib_test;
+#[path = "lint_test.rs"]
+mod lint_test;
 pub mod merge;
 pub mod output;
+#[path = "scaffold_test.rs"]
+mod scaffold_test;
 #[path = "validate_test.rs"]
 mod validate_test;
+#[path = "watch_test.rs"]
+mod watch_test;
@@ -290,7 +290,7 @@ fn test_csv_connector_formatting() {
 
     let content = std::fs::read_to_string(path).expect("Failed to read generated file");
 
-    assert!(content.contains("Ccs2"));
+    assert!(content.contains("ccs2"));
 }
 
 #[test]
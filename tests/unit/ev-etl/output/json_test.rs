@@ -1,5 +1,6 @@
 use ev_core::Vehicle;
 use ev_etl::output::json;
+use serde::Serialize;
 use tempfile::NamedTempFile;
 
 fn create_test_vehicle() -> Vehicle {
@@ -109,3 +110,57 @@ fn test_json_generate() {
     let checksum_content = std::fs::read_to_string(checksum_path).expect("Failed to read checksum");
     assert!(checksum_content.len() > 64);
 }
+
+/// `vehicles.json` laid out as `serde_json::to_string_pretty` would.
+#[derive(Serialize)]
+struct PrettyOutput {
+    schema_version: serde_json::Value,
+    generated_at: serde_json::Value,
+    vehicle_count: usize,
+    vehicles: Vec<Vehicle>,
+    metadata: serde_json::Value,
+}
+
+fn assert_pretty_layout(vehicles: Vec<Vehicle>) {
+    let file = NamedTempFile::new().expect("Failed to create temp file");
+    json::generate(&vehicles, file.path()).expect("Failed to generate JSON");
+
+    let content = std::fs::read_to_string(file.path()).expect("Failed to read generated file");
+    let value: serde_json::Value = serde_json::from_str(&content).expect("valid JSON");
+    let expected = PrettyOutput {
+        schema_version: value["schema_version"].clone(),
+        generated_at: value["generated_at"].clone(),
+        vehicle_count: vehicles.len(),
+        vehicles,
+        metadata: value["metadata"].clone(),
+    };
+    assert_eq!(content, serde_json::to_string_pretty(&expected).unwrap());
+}
+
+#[test]
+fn test_json_generate_streams_pretty_layout() {
+    assert_pretty_layout(vec![create_test_vehicle(), create_test_vehicle()]);
+}
+
+#[test]
+fn test_json_generate_empty() {
+    assert_pretty_layout(Vec::new());
+}
+
+#[test]
+fn test_checksum_path() {
+    use std::path::Path;
+
+    assert_eq!(
+        json::checksum_path(Path::new("out/vehicles.json")),
+        Path::new("out/vehicles.json.sha256")
+    );
+    assert_eq!(
+        json::checksum_path(Path::new("out/vehicles.json.gz")),
+        Path::new("out/vehicles.json.gz.sha256")
+    );
+    assert_eq!(
+        json::checksum_path(Path::new("out/vehicles")),
+        Path::new("out/vehicles.json.sha256")
+    );
+}
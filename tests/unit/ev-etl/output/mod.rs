@@ -4,6 +4,8 @@ mod csv_test;
 mod json_test;
 #[path = "postgresql_test.rs"]
 mod postgresql_test;
+#[path = "registry_test.rs"]
+mod registry_test;
 #[path = "sqlite_test.rs"]
 mod sqlite_test;
 #[path = "statistics_test.rs"]
@@ -266,3 +266,191 @@ fn test_postgresql_with_performance() {
     assert!(content.contains("600"));
     assert!(content.contains("500"));
 }
+
+#[test]
+fn test_postgresql_writes_filter_rows() {
+    let vehicle: Vehicle = serde_json::from_value(serde_json::json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "kia", "name": "Kia"},
+        "model": {"slug": "ev6", "name": "EV6"},
+        "year": 2024,
+        "trim": {"slug": "gt", "name": "GT"},
+        "unique_code": "kia:ev6:2024:gt",
+        "vehicle_type": "suv",
+        "powertrain": {"drivetrain": "awd"},
+        "battery": {"pack_capacity_kwh_net": 77.4, "heat_pump": true},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {"dc": {"max_power_kw": 258.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": 424.0}]},
+        "v2x": {"v2l": {"supported": true}},
+        "markets": ["DE"],
+        "availability": {"status": "production"},
+        "pricing": {"msrp": [{"currency": "EUR", "amount": 69990.0}]},
+        "sources": []
+    }))
+    .unwrap();
+
+    let mut file = NamedTempFile::new().unwrap();
+    generate(&[vehicle], file.path()).unwrap();
+    let mut content = String::new();
+    file.read_to_string(&mut content).unwrap();
+
+    assert!(content.contains("TRUE, TRUE, NULL, NULL, 'production'"));
+    let vehicle_id = "(SELECT id FROM vehicles WHERE unique_code = 'kia:ev6:2024:gt')";
+    assert!(content.contains(&format!(
+        "INSERT INTO charge_ports (vehicle_id, kind, connector, location_side, location_position) VALUES ({vehicle_id}, 'combo', 'ccs2', NULL, NULL);"
+    )));
+    assert!(content.contains(&format!(
+        "INSERT INTO markets (vehicle_id, country) VALUES ({vehicle_id}, 'DE');"
+    )));
+    assert!(content.contains(&format!(
+        "INSERT INTO prices (vehicle_id, currency, amount, country, year) VALUES ({vehicle_id}, 'EUR', 69990, NULL, NULL);"
+    )));
+}
+
+/// Runs `sql` in a fresh `schema` and returns its columns, indexes, views and routines.
+async fn postgres_layout(admin_url: &str, schema: &str, sql: &str) -> Vec<String> {
+    let pool = sqlx::PgPool::connect(admin_url).await.unwrap();
+    sqlx::raw_sql(sqlx::AssertSqlSafe(format!(
+        "DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema}; SET search_path TO {schema};\n{sql}"
+    )))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let layout: Vec<String> = sqlx::query_scalar(
+        "SELECT format('column %s.%s %s %s %s', table_name, column_name, data_type, \
+             COALESCE(character_maximum_length, numeric_precision), is_nullable) \
+         FROM information_schema.columns WHERE table_schema = $1 \
+         UNION ALL \
+         SELECT format('index %s %s', indexname, replace(indexdef, $1 || '.', '')) \
+         FROM pg_indexes WHERE schemaname = $1 \
+         UNION ALL \
+         SELECT format('view %s', table_name) FROM information_schema.views WHERE table_schema = $1 \
+         UNION ALL \
+         SELECT format('routine %s', routine_name) FROM information_schema.routines WHERE routine_schema = $1 \
+         ORDER BY 1",
+    )
+    .bind(schema)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+
+    sqlx::raw_sql(sqlx::AssertSqlSafe(format!("DROP SCHEMA {schema} CASCADE")))
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+    layout
+}
+
+#[tokio::test]
+async fn test_postgresql_reference_schema_matches_generated() {
+    let Ok(admin_url) = std::env::var("TEST_POSTGRES_URL") else {
+        eprintln!("TEST_POSTGRES_URL not set, skipping test");
+        return;
+    };
+
+    let mut file = NamedTempFile::new().unwrap();
+    generate(&[], file.path()).unwrap();
+    let mut generated = String::new();
+    file.read_to_string(&mut generated).unwrap();
+    let reference = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas/postgresql/schema.sql"),
+    )
+    .unwrap();
+
+    let reference = postgres_layout(&admin_url, "ev_etl_reference_schema", &reference).await;
+    let generated = postgres_layout(&admin_url, "ev_etl_generated_schema", &generated).await;
+    assert!(
+        reference
+            .iter()
+            .any(|line| line.starts_with("column motors."))
+    );
+    assert_eq!(reference, generated);
+}
+
+#[tokio::test]
+async fn test_postgresql_dump_normalizes_nested_data() {
+    let Ok(admin_url) = std::env::var("TEST_POSTGRES_URL") else {
+        eprintln!("TEST_POSTGRES_URL not set, skipping test");
+        return;
+    };
+
+    let vehicle: Vehicle = serde_json::from_value(serde_json::json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "kia", "name": "Kia"},
+        "model": {"slug": "ev6", "name": "EV6"},
+        "year": 2024,
+        "trim": {"slug": "gt", "name": "GT"},
+        "unique_code": "kia:ev6:2024:gt",
+        "vehicle_type": "suv",
+        "powertrain": {"drivetrain": "awd", "motors": [{"position": "rear", "power_kw": 270.0}]},
+        "battery": {"pack_capacity_kwh_net": 77.4},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {
+            "dc": {"max_power_kw": 258.0},
+            "dc_charge_curve": {"curve_type": "power_by_soc", "points": [{"soc_percent": 10.0, "power_kw": 240.0}]},
+            "charging_time": {"dc": [{"charger_power_kw": 350.0, "from_soc_percent": 10.0, "to_soc_percent": 80.0, "time_min": 18.0}]}
+        },
+        "range": {
+            "rated": [{"cycle": "wltp", "range_km": 424.0, "notes": "Driver's edition"}],
+            "real_world": [{"profile": "winter", "range_km": 300.0}]
+        },
+        "v2x": {"v2l": {"supported": true}},
+        "dimensions": {"length_mm": 4695.0},
+        "weights": {"curb_weight_kg": 2185.0},
+        "sources": [{"type": "oem", "title": "Kia's site", "url": "https://www.kia.com", "accessed_at": "2024-05-01"}]
+    }))
+    .unwrap();
+
+    let mut file = NamedTempFile::new().unwrap();
+    generate(&[vehicle], file.path()).unwrap();
+    let mut dump = String::new();
+    file.read_to_string(&mut dump).unwrap();
+
+    let pool = sqlx::PgPool::connect(&admin_url).await.unwrap();
+    sqlx::raw_sql(sqlx::AssertSqlSafe(format!(
+        "DROP SCHEMA IF EXISTS ev_etl_normalized_test CASCADE; CREATE SCHEMA ev_etl_normalized_test; SET search_path TO ev_etl_normalized_test;\n{dump}"
+    )))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut counts = Vec::new();
+    for table in [
+        "charge_ports",
+        "motors",
+        "charge_curve_points",
+        "charging_times",
+        "range_ratings",
+        "real_world_ranges",
+        "v2x_capabilities",
+        "dimensions",
+        "weights",
+        "sources",
+    ] {
+        let count: i64 = sqlx::query_scalar(sqlx::AssertSqlSafe(format!(
+            "SELECT COUNT(*) FROM ev_etl_normalized_test.{table} t JOIN ev_etl_normalized_test.vehicles v ON v.id = t.vehicle_id"
+        )))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        counts.push((table, count));
+    }
+    assert!(counts.iter().all(|(_, count)| *count == 1), "{counts:?}");
+
+    let matches: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM ev_etl_normalized_test.vehicles WHERE search_vector @@ plainto_tsquery('english', 'kia')",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(matches, 1);
+
+    sqlx::raw_sql("DROP SCHEMA ev_etl_normalized_test CASCADE")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+}
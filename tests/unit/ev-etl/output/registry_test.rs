@@ -0,0 +1,31 @@
+use ev_etl::output::OutputRegistry;
+use ev_etl::output::json::JsonWriter;
+
+#[test]
+fn test_default_registry_has_builtin_formats() {
+    let registry = OutputRegistry::default();
+
+    assert_eq!(
+        registry.names().collect::<Vec<_>>(),
+        vec!["csv", "json", "postgresql", "sqlite", "xml"]
+    );
+    assert_eq!(
+        registry.get("sqlite").unwrap().default_file_name(),
+        "vehicles.db"
+    );
+    assert!(!registry.get("sqlite").unwrap().supports_compression());
+    assert!(registry.get("json").unwrap().supports_compression());
+    assert!(registry.get("parquet").is_none());
+}
+
+#[test]
+fn test_register_replaces_writer_of_same_name() {
+    let mut registry = OutputRegistry::empty();
+    assert!(registry.get("json").is_none());
+
+    assert!(registry.register(JsonWriter).is_none());
+    let replaced = registry.register(JsonWriter).expect("json was registered");
+
+    assert_eq!(replaced.name(), "json");
+    assert_eq!(registry.names().collect::<Vec<_>>(), vec!["json"]);
+}
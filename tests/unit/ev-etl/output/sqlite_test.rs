@@ -1,6 +1,8 @@
 use ev_core::Drivetrain;
-use ev_core::{Battery, Charging, Powertrain, Range, SlugName, Vehicle, VehicleType};
-use ev_etl::output::sqlite::generate;
+use ev_core::{
+    Battery, Charging, Powertrain, Range, RangeCycle, RangeRated, SlugName, Vehicle, VehicleType,
+};
+use ev_etl::output::sqlite::{generate, refresh};
 use rusqlite::Connection;
 use tempfile::NamedTempFile;
 
@@ -83,3 +85,310 @@ fn test_sqlite_generation() {
         .unwrap();
     assert_eq!(make, "tesla");
 }
+
+#[test]
+fn test_sqlite_refresh_updates_open_database() {
+    let file = NamedTempFile::new().unwrap();
+    generate(&[create_test_vehicle()], file.path()).unwrap();
+    let conn = Connection::open(file.path()).unwrap();
+
+    let mut first = create_test_vehicle();
+    first.battery.pack_capacity_kwh_net = Some(75.0);
+    let mut second = create_test_vehicle();
+    second.trim.slug = "long_range".into();
+    second.unique_code = Some("tesla:model_3:2024:long_range".to_string());
+    refresh(&[first, second], file.path()).unwrap();
+
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM vehicles", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 2);
+    let capacity: f64 = conn
+        .query_row(
+            "SELECT battery_capacity_net_kwh FROM vehicles WHERE trim_slug = 'base'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(capacity, 75.0);
+    let indexed: i64 = conn
+        .query_row("SELECT COUNT(*) FROM vehicles_fts", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(indexed, 2);
+}
+
+#[test]
+fn test_sqlite_deleting_vehicle_cascades_to_child_rows() {
+    let mut vehicle = create_test_vehicle();
+    vehicle.range.rated = vec![RangeRated {
+        cycle: RangeCycle::Wltp,
+        range_km: 513.0,
+        notes: None,
+    }];
+    let file = NamedTempFile::new().unwrap();
+    generate(&[vehicle], file.path()).unwrap();
+
+    let conn = Connection::open(file.path()).unwrap();
+    conn.pragma_update(None, "foreign_keys", true).unwrap();
+    let ranges: i64 = conn
+        .query_row("SELECT COUNT(*) FROM range_ratings", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(ranges, 1);
+    let orphans = conn
+        .prepare("PRAGMA foreign_key_check")
+        .unwrap()
+        .query_map([], |_| Ok(()))
+        .unwrap()
+        .count();
+    assert_eq!(orphans, 0);
+
+    conn.execute("DELETE FROM vehicles", []).unwrap();
+    let ranges: i64 = conn
+        .query_row("SELECT COUNT(*) FROM range_ratings", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(ranges, 0);
+}
+
+#[test]
+fn test_sqlite_refresh_creates_missing_database() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("vehicles.db");
+
+    refresh(&[create_test_vehicle()], &path).unwrap();
+
+    let count: i64 = Connection::open(&path)
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM vehicles", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_sqlite_generation_writes_filter_columns() {
+    let vehicle: Vehicle = serde_json::from_value(serde_json::json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "kia", "name": "Kia"},
+        "model": {"slug": "ev6", "name": "EV6"},
+        "year": 2024,
+        "trim": {"slug": "gt", "name": "GT"},
+        "unique_code": "kia:ev6:2024:gt",
+        "vehicle_type": "suv",
+        "powertrain": {"drivetrain": "awd"},
+        "battery": {"pack_capacity_kwh_net": 77.4, "heat_pump": true},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {"dc": {"max_power_kw": 258.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": 424.0}]},
+        "v2x": {"v2l": {"supported": true}, "v2g": {"supported": false}},
+        "markets": ["DE", "FR"],
+        "availability": {"status": "production"},
+        "pricing": {"msrp": [{"currency": "EUR", "amount": 69990.0, "country": "DE"}]},
+        "sources": []
+    }))
+    .unwrap();
+    let file = NamedTempFile::new().unwrap();
+    generate(&[vehicle], file.path()).unwrap();
+
+    let conn = Connection::open(file.path()).unwrap();
+    let row: (Option<bool>, Option<bool>, Option<bool>, Option<bool>, Option<String>) = conn
+        .query_row(
+            "SELECT heat_pump, v2l_supported, v2h_supported, v2g_supported, availability_status FROM vehicles",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        row,
+        (
+            Some(true),
+            Some(true),
+            None,
+            Some(false),
+            Some("production".to_string())
+        )
+    );
+
+    let markets: Vec<String> = conn
+        .prepare("SELECT country FROM markets ORDER BY country")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(markets, vec!["DE", "FR"]);
+
+    let price: (String, f64, Option<String>) = conn
+        .query_row("SELECT currency, amount, country FROM prices", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap();
+    assert_eq!(price, ("EUR".to_string(), 69990.0, Some("DE".to_string())));
+}
+
+/// Tables with their `PRAGMA table_info` rows, and `(index, table)` pairs.
+type SqliteLayout = (
+    Vec<(String, Vec<(String, String, bool, bool)>)>,
+    Vec<(String, String)>,
+);
+
+fn sqlite_layout(conn: &Connection) -> SqliteLayout {
+    let tables: Vec<String> = conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let columns = tables
+        .into_iter()
+        .map(|table| {
+            let columns = conn
+                .prepare(&format!("PRAGMA table_info({table})"))
+                .unwrap()
+                .query_map([], |row| {
+                    Ok((
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get::<_, i64>(5)? > 0,
+                    ))
+                })
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            (table, columns)
+        })
+        .collect();
+
+    let indexes = conn
+        .prepare(
+            "SELECT name, tbl_name FROM sqlite_master WHERE type = 'index' AND sql IS NOT NULL ORDER BY name",
+        )
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    (columns, indexes)
+}
+
+#[test]
+fn test_sqlite_reference_schema_matches_generated() {
+    let file = NamedTempFile::new().unwrap();
+    generate(&[], file.path()).unwrap();
+    let generated = Connection::open(file.path()).unwrap();
+
+    let reference = Connection::open_in_memory().unwrap();
+    let sql = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas/sqlite/schema.sql"),
+    )
+    .unwrap();
+    reference.execute_batch(&sql).unwrap();
+
+    assert_eq!(sqlite_layout(&reference), sqlite_layout(&generated));
+}
+
+#[test]
+fn test_sqlite_generation_normalizes_nested_data() {
+    let vehicle: Vehicle = serde_json::from_value(serde_json::json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "kia", "name": "Kia"},
+        "model": {"slug": "ev6", "name": "EV6"},
+        "year": 2024,
+        "trim": {"slug": "gt", "name": "GT"},
+        "unique_code": "kia:ev6:2024:gt",
+        "vehicle_type": "suv",
+        "powertrain": {
+            "drivetrain": "awd",
+            "motors": [
+                {"position": "front", "type": "pmsm", "power_kw": 160.0},
+                {"position": "rear", "type": "pmsm", "power_kw": 270.0}
+            ]
+        },
+        "battery": {"pack_capacity_kwh_net": 77.4},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {
+            "dc": {"max_power_kw": 258.0},
+            "dc_charge_curve": {
+                "curve_type": "power_by_soc",
+                "points": [
+                    {"soc_percent": 10.0, "power_kw": 240.0},
+                    {"soc_percent": 80.0, "power_kw": 80.0}
+                ]
+            },
+            "charging_time": {
+                "ac": [{"power_kw": 11.0, "from_soc_percent": 10.0, "to_soc_percent": 100.0, "time_min": 440.0}],
+                "dc": [{"charger_power_kw": 350.0, "from_soc_percent": 10.0, "to_soc_percent": 80.0, "time_min": 18.0}]
+            }
+        },
+        "range": {
+            "rated": [{"cycle": "wltp", "range_km": 424.0}],
+            "real_world": [{"profile": "highway", "range_km": 330.0, "conditions": {"speed_kmh": 120.0}}]
+        },
+        "v2x": {
+            "v2l": {"supported": true, "max_power_kw": 3.6},
+            "v2g": {"supported": true, "protocols": ["ISO 15118-20", "CHAdeMO"]}
+        },
+        "dimensions": {"length_mm": 4695.0, "wheelbase_mm": 2900.0},
+        "weights": {"curb_weight_kg": 2185.0},
+        "sources": [{"type": "oem", "title": "Kia", "url": "https://www.kia.com", "accessed_at": "2024-05-01"}]
+    }))
+    .unwrap();
+    let file = NamedTempFile::new().unwrap();
+    generate(&[vehicle], file.path()).unwrap();
+    let conn = Connection::open(file.path()).unwrap();
+
+    let count = |table: &str| -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    };
+    assert_eq!(count("motors"), 2);
+    assert_eq!(count("charge_curve_points"), 2);
+    assert_eq!(count("charging_times"), 2);
+    assert_eq!(count("real_world_ranges"), 1);
+    assert_eq!(count("v2x_capabilities"), 2);
+    assert_eq!(count("dimensions"), 1);
+    assert_eq!(count("weights"), 1);
+    assert_eq!(count("sources"), 1);
+
+    let dc_time: (f64, f64) = conn
+        .query_row(
+            "SELECT power_kw, time_min FROM charging_times WHERE current = 'dc'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(dc_time, (350.0, 18.0));
+
+    let protocols: String = conn
+        .query_row(
+            "SELECT protocols FROM v2x_capabilities WHERE mode = 'v2g'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(protocols, "ISO 15118-20,CHAdeMO");
+
+    let (vehicle_id, wheelbase): (i64, f64) = conn
+        .query_row(
+            "SELECT d.vehicle_id, d.wheelbase_mm FROM dimensions d JOIN vehicles v ON v.id = d.vehicle_id WHERE v.unique_code = 'kia:ev6:2024:gt'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((vehicle_id, wheelbase), (1, 2900.0));
+
+    let rear_power: f64 = conn
+        .query_row(
+            "SELECT power_kw FROM motors WHERE position = 'rear'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(rear_power, 270.0);
+}
@@ -2,7 +2,7 @@ use ev_core::{
     Battery, Body, ChargePort, Charging, ConnectorType, Drivetrain, PortKind, Powertrain, Range,
     RangeCycle, RangeRated, SlugName, Source, SourceType, Vehicle, VehicleType,
 };
-use ev_etl::output::xml;
+use ev_etl::output::{Compression, OutputOptions, write_all, xml};
 use tempfile::NamedTempFile;
 
 fn create_test_vehicle() -> Vehicle {
@@ -285,8 +285,8 @@ fn test_xml_charge_ports_section() {
     assert!(content.contains("<chargePorts>"));
     assert!(content.contains("</chargePorts>"));
     assert!(content.contains("<port"));
-    assert!(content.contains("Combo"));
-    assert!(content.contains("Ccs2"));
+    assert!(content.contains("kind=\"combo\""));
+    assert!(content.contains("connector=\"ccs2\""));
 }
 
 #[test]
@@ -345,7 +345,7 @@ fn test_xml_range_section() {
     assert!(content.contains("<range>"));
     assert!(content.contains("</range>"));
     assert!(content.contains("<rated"));
-    assert!(content.contains("Wltp"));
+    assert!(content.contains("cycle=\"wltp\""));
     assert!(content.contains(r#"km="500""#));
 }
 
@@ -362,7 +362,7 @@ fn test_xml_sources_section() {
     assert!(content.contains("<sources>"));
     assert!(content.contains("</sources>"));
     assert!(content.contains("<source"));
-    assert!(content.contains("Oem"));
+    assert!(content.contains("type=\"oem\""));
     assert!(content.contains("https://tesla.com"));
 }
 
@@ -504,7 +504,7 @@ fn test_xml_vehicle_type() {
 
     assert!(content.contains("<vehicleType>"));
     assert!(content.contains("</vehicleType>"));
-    assert!(content.contains("PassengerCar"));
+    assert!(content.contains("<vehicleType>passenger_car</vehicleType>"));
 }
 
 #[test]
@@ -523,3 +523,25 @@ fn test_xml_minimal_vehicle() {
     assert!(content.contains("<chargePorts>"));
     assert!(content.contains("</chargePorts>"));
 }
+
+#[test]
+fn test_xml_writer_gzip() {
+    let vehicles = vec![create_test_vehicle()];
+    let plain = NamedTempFile::new().expect("Failed to create temp file");
+    let compressed = NamedTempFile::new().expect("Failed to create temp file");
+
+    xml::generate(&vehicles, plain.path()).expect("Failed to generate XML");
+    let options = OutputOptions {
+        path: compressed.path().to_path_buf(),
+        compression: Compression::Gzip,
+    };
+    write_all(&xml::XmlWriter, &vehicles, &options).expect("Failed to generate XML");
+
+    let mut content = String::new();
+    std::io::Read::read_to_string(
+        &mut flate2::read::GzDecoder::new(std::fs::File::open(compressed.path()).unwrap()),
+        &mut content,
+    )
+    .expect("valid gzip");
+    assert_eq!(content, std::fs::read_to_string(plain.path()).unwrap());
+}
@@ -0,0 +1,71 @@
+use ev_etl::format::format_json;
+use ev_etl::ingest;
+use ev_etl::lint::lint_dataset;
+use ev_etl::scaffold::new_vehicle;
+use serde_json::json;
+use tempfile::TempDir;
+
+#[test]
+fn test_new_vehicle_creates_layer_files() {
+    let dir = TempDir::new().unwrap();
+
+    let created = new_vehicle(dir.path(), "tesla", "model_3", 2025, Some("long_range")).unwrap();
+
+    assert_eq!(
+        created,
+        vec![
+            dir.path().join("tesla/model_3/base.json"),
+            dir.path().join("tesla/model_3/2025/model_3.json"),
+            dir.path()
+                .join("tesla/model_3/2025/model_3_long_range.json"),
+        ]
+    );
+    let base: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&created[0]).unwrap()).unwrap();
+    assert_eq!(base["model"], json!({"slug": "model_3", "name": "Model 3"}));
+    let variant: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&created[2]).unwrap()).unwrap();
+    assert_eq!(variant["variant"]["name"], "Long Range");
+}
+
+#[test]
+fn test_new_vehicle_files_are_ingested_and_lint_clean() {
+    let dir = TempDir::new().unwrap();
+    new_vehicle(dir.path(), "kia", "ev6", 2024, Some("gt")).unwrap();
+
+    assert_eq!(ingest::load_dataset(dir.path()).unwrap().len(), 3);
+    assert!(lint_dataset(dir.path()).unwrap().is_empty());
+
+    let source = std::fs::read_to_string(dir.path().join("kia/ev6/base.json")).unwrap();
+    assert_eq!(format_json(&source).unwrap(), source);
+}
+
+#[test]
+fn test_new_vehicle_adds_year_to_existing_model() {
+    let dir = TempDir::new().unwrap();
+    new_vehicle(dir.path(), "kia", "ev6", 2024, None).unwrap();
+    std::fs::write(dir.path().join("kia/ev6/base.json"), "{}").unwrap();
+
+    let created = new_vehicle(dir.path(), "kia", "ev6", 2025, None).unwrap();
+
+    assert_eq!(created, vec![dir.path().join("kia/ev6/2025/ev6.json")]);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("kia/ev6/base.json")).unwrap(),
+        "{}"
+    );
+}
+
+#[test]
+fn test_new_vehicle_rejects_existing_vehicle() {
+    let dir = TempDir::new().unwrap();
+    new_vehicle(dir.path(), "kia", "ev6", 2024, Some("gt")).unwrap();
+
+    assert!(new_vehicle(dir.path(), "kia", "ev6", 2024, None).is_err());
+    assert!(new_vehicle(dir.path(), "kia", "ev6", 2024, Some("gt")).is_err());
+}
+
+#[test]
+fn test_new_vehicle_rejects_invalid_slug() {
+    let dir = TempDir::new().unwrap();
+    assert!(new_vehicle(dir.path(), "Kia Motors", "ev6", 2024, None).is_err());
+}
@@ -1,10 +1,15 @@
 use ev_core::{
-    Battery, ChargePort, Charging, ConnectorType, Drivetrain, PortKind, Powertrain, Range,
-    RangeCycle, RangeRated, SlugName, Source, SourceType, Vehicle, VehicleType,
+    Battery, ChargePort, Charging, ConnectorType, Drivetrain, Finding, PortKind, Powertrain, Range,
+    RangeCycle, RangeRated, Severity, SlugName, Source, SourceType, ValidationError as CoreError,
+    Vehicle, VehicleType,
 };
+use ev_etl::ingest::{FileType, VehicleFile};
+use ev_etl::merge::{MergedDocument, Provenance, VehicleOrigin};
 use ev_etl::validate::{
-    ValidationReport, load_schema, validate_all, validate_vehicle, validate_with_json_schema,
+    ValidationReport, layer_schema, load_schema, validate_all, validate_layer_files,
+    validate_merged_documents, validate_vehicle, validate_with_json_schema, validate_with_report,
 };
+use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
 fn create_valid_vehicle() -> Vehicle {
@@ -217,6 +222,7 @@ fn test_validation_report_add_error() {
 
     report.add_error(
         "vehicle_1",
+        "tesla/model_y/base.json",
         "missing_field",
         "Field is required",
         "/sources",
@@ -236,17 +242,25 @@ fn test_validation_report_add_multiple_errors() {
 
     report.add_error(
         "vehicle_1",
+        "tesla/model_y/base.json",
         "missing_field",
         "Field is required",
         "/sources",
     );
     report.add_error(
         "vehicle_1",
+        "tesla/model_y/base.json",
         "invalid_value",
         "Value out of range",
         "/battery/capacity",
     );
-    report.add_error("vehicle_2", "missing_field", "Field is required", "/range");
+    report.add_error(
+        "vehicle_2",
+        "tesla/model_y/base.json",
+        "missing_field",
+        "Field is required",
+        "/range",
+    );
 
     assert_eq!(report.error_count, 3);
     assert_eq!(report.errors.len(), 3);
@@ -259,11 +273,18 @@ fn test_validation_report_save_to_file() {
     report.valid_count = 8;
     report.add_error(
         "vehicle_1",
+        "tesla/model_y/base.json",
         "missing_field",
         "Field is required",
         "/sources",
     );
-    report.add_error("vehicle_2", "invalid_value", "Invalid year", "/year");
+    report.add_error(
+        "vehicle_2",
+        "tesla/model_y/base.json",
+        "invalid_value",
+        "Invalid year",
+        "/year",
+    );
 
     let file = NamedTempFile::new().expect("Failed to create temp file");
     let path = file.path();
@@ -285,7 +306,13 @@ fn test_validation_report_serialization() {
     let mut report = ValidationReport::new();
     report.total_vehicles = 5;
     report.valid_count = 4;
-    report.add_error("vehicle_1", "test_error", "Test message", "/path");
+    report.add_error(
+        "vehicle_1",
+        "tesla/model_y/base.json",
+        "test_error",
+        "Test message",
+        "/path",
+    );
 
     let json = serde_json::to_value(&report).expect("Serialization failed");
 
@@ -415,7 +442,13 @@ fn test_load_schema_invalid_json() {
 #[test]
 fn test_validation_error_clone() {
     let mut report = ValidationReport::new();
-    report.add_error("v1", "error_type", "message", "/path");
+    report.add_error(
+        "v1",
+        "tesla/model_y/base.json",
+        "error_type",
+        "message",
+        "/path",
+    );
 
     let error = report.errors[0].clone();
     assert_eq!(error.vehicle_id, "v1");
@@ -426,7 +459,7 @@ fn test_validation_error_clone() {
 fn test_validation_report_clone() {
     let mut report = ValidationReport::new();
     report.total_vehicles = 5;
-    report.add_error("v1", "error", "msg", "/path");
+    report.add_error("v1", "tesla/model_y/base.json", "error", "msg", "/path");
 
     let cloned = report.clone();
     assert_eq!(cloned.total_vehicles, 5);
@@ -462,3 +495,269 @@ fn test_validate_with_json_schema_invalid_schema() {
     let result = validate_with_json_schema(&valid_json, &invalid_schema);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_validation_report_record_findings() {
+    let mut report = ValidationReport::new();
+    let findings = vec![
+        Finding {
+            severity: Severity::Warning,
+            ..Finding::new("/range/rated", CoreError::MissingRatedRange)
+        },
+        Finding::new("/charge_ports", CoreError::MissingChargePort),
+    ];
+
+    let origin = VehicleOrigin {
+        label: "tesla/model_y/2024/model_y.json".to_string(),
+        provenance: None,
+    };
+
+    assert!(!report.record("tesla:model_y:2024:model_y", &origin, &findings));
+    assert!(report.record("tesla:model_3:2024:model_3", &origin, &[]));
+
+    assert_eq!(report.total_vehicles, 2);
+    assert_eq!(report.valid_count, 1);
+    assert_eq!(report.error_count, 1);
+    assert_eq!(report.warning_count, 1);
+    assert_eq!(report.errors[0].error_type, "missing_charge_port");
+    assert_eq!(report.errors[0].path, "/charge_ports");
+    assert_eq!(report.warnings[0].severity, Severity::Warning);
+    assert_eq!(report.errors[0].file, "tesla/model_y/2024/model_y.json");
+}
+
+#[test]
+fn test_validate_with_report() {
+    let mut inconsistent = create_valid_vehicle();
+    inconsistent.battery.pack_capacity_kwh_net = Some(70.0);
+
+    let mut provenance = Provenance::default();
+    for (pointer, file) in [
+        (
+            "/battery/pack_capacity_kwh_gross",
+            "tesla/model_y/base.json",
+        ),
+        (
+            "/battery/pack_capacity_kwh_net",
+            "tesla/model_y/2024/base.json",
+        ),
+    ] {
+        provenance
+            .sources
+            .insert(pointer.to_string(), file.to_string());
+    }
+    let origin = VehicleOrigin {
+        label: "tesla/model_y/2024/base.json".to_string(),
+        provenance: Some(provenance),
+    };
+
+    let (valid, report) = validate_with_report(vec![
+        (create_valid_vehicle(), origin.clone()),
+        (inconsistent, origin),
+    ]);
+
+    assert_eq!(valid.len(), 1);
+    assert_eq!(report.total_vehicles, 2);
+    assert_eq!(report.valid_count, 1);
+    assert_eq!(report.errors[0].vehicle_id, "tesla:model_y:2024:base");
+    assert_eq!(report.errors[0].file, "tesla/model_y/2024/base.json");
+    assert_eq!(report.errors[0].path, "/battery/pack_capacity_kwh_net");
+}
+
+#[test]
+fn test_vehicle_origin_file_of() {
+    let mut provenance = Provenance::default();
+    for (pointer, file) in [
+        (
+            "/battery/pack_capacity_kwh_gross",
+            "tesla/model_y/base.json",
+        ),
+        ("/range/rated/0/cycle", "tesla/model_y/2024/model_y.json"),
+        ("/range/rated/0/range_km", "tesla/model_y/2024/model_y.json"),
+        ("/sources/0/url", "tesla/model_y/base.json"),
+        ("/sources/1/url", "tesla/model_y/2024/long_range.json"),
+    ] {
+        provenance
+            .sources
+            .insert(pointer.to_string(), file.to_string());
+    }
+    let origin = VehicleOrigin {
+        label: "tesla/model_y/2024/long_range.json".to_string(),
+        provenance: Some(provenance),
+    };
+
+    assert_eq!(
+        origin.file_of("/battery/pack_capacity_kwh_gross"),
+        "tesla/model_y/base.json"
+    );
+    // One layer set every value below the pointer.
+    assert_eq!(
+        origin.file_of("/range/rated"),
+        "tesla/model_y/2024/model_y.json"
+    );
+    // Several layers, or none, fall back to the leaf file.
+    assert_eq!(
+        origin.file_of("/sources"),
+        "tesla/model_y/2024/long_range.json"
+    );
+    assert_eq!(
+        origin.file_of("/charge_ports"),
+        "tesla/model_y/2024/long_range.json"
+    );
+}
+
+fn vehicle_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["make", "year"],
+        "properties": {
+            "make": {
+                "type": "object",
+                "required": ["slug"],
+                "properties": {"slug": {"type": "string"}}
+            },
+            "year": {"type": "integer"},
+            "required": {"type": "boolean"}
+        }
+    })
+}
+
+fn bundled_schema() -> serde_json::Value {
+    ev_core::schema::vehicle_schema()
+}
+
+fn layer_file(path: &str, content: serde_json::Value) -> VehicleFile {
+    VehicleFile {
+        path: PathBuf::from(path),
+        make_slug: "tesla".to_string(),
+        model_slug: "model_3".to_string(),
+        year: None,
+        file_type: FileType::ModelBase,
+        file_slug: "base".to_string(),
+        content,
+        sha256: String::new(),
+    }
+}
+
+#[test]
+fn test_layer_schema_strips_required() {
+    let schema = layer_schema(&vehicle_schema());
+
+    assert!(schema.get("required").is_none());
+    assert!(
+        schema["properties"]["make"]["else"]
+            .get("required")
+            .is_none()
+    );
+    assert_eq!(schema["properties"]["required"]["else"]["type"], "boolean");
+}
+
+#[test]
+fn test_validate_layer_files_allows_partial_layers() {
+    let files = vec![
+        layer_file(
+            "/data/tesla/model_3/base.json",
+            serde_json::json!({"make": {}}),
+        ),
+        layer_file(
+            "/data/tesla/model_3/2024/model_3.json",
+            serde_json::json!({"year": "2024"}),
+        ),
+    ];
+
+    let violations =
+        validate_layer_files(&files, &vehicle_schema(), Path::new("/data")).expect("valid schema");
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].file, "tesla/model_3/2024/model_3.json");
+    assert_eq!(violations[0].instance_path, "/year");
+}
+
+#[test]
+fn test_validate_merged_documents_requires_complete_vehicles() {
+    let documents = vec![MergedDocument {
+        label: "tesla/model_3/2024/model_3.json".to_string(),
+        path: PathBuf::from("/data/tesla/model_3/2024/model_3.json"),
+        make_slug: "tesla".to_string(),
+        model_slug: "model_3".to_string(),
+        content: serde_json::json!({"make": {"slug": 42}}),
+        provenance: None,
+    }];
+
+    let violations =
+        validate_merged_documents(&documents, &vehicle_schema()).expect("valid schema");
+
+    assert_eq!(violations.len(), 2);
+    assert!(violations.iter().any(|v| v.instance_path.is_empty()));
+    assert!(violations.iter().any(|v| v.instance_path == "/make/slug"));
+    assert_eq!(
+        violations[0].file,
+        "tesla/model_3/2024/model_3.json (merged)"
+    );
+}
+
+#[test]
+fn test_validate_layer_files_accepts_merge_directives() {
+    let files = vec![layer_file(
+        "/data/tesla/model_3/2024/model_3_long_range.json",
+        serde_json::json!({
+            "variant": null,
+            "pricing": {"$delete": true},
+            "charge_ports": {"$append": [{"kind": "ac_only", "connector": "type2"}]},
+            "range": {"rated": {
+                "$merge": [{"cycle": "wltp", "range_km": 629.0}, {"cycle": "epa", "$delete": true}],
+                "$key": "cycle"
+            }}
+        }),
+    )];
+
+    let violations =
+        validate_layer_files(&files, &bundled_schema(), Path::new("/data")).expect("valid schema");
+
+    assert!(violations.is_empty(), "{:?}", violations);
+}
+
+#[test]
+fn test_validate_layer_files_rejects_malformed_directives() {
+    let files = vec![layer_file(
+        "/data/tesla/model_3/base.json",
+        serde_json::json!({
+            "year": {"$append": [2024]},
+            "charge_ports": {"$append": [{"kind": "combo", "connector": 7}]},
+            "battery": {"$delete": false},
+            "range": {"$unknown": true}
+        }),
+    )];
+
+    let violations =
+        validate_layer_files(&files, &bundled_schema(), Path::new("/data")).expect("valid schema");
+
+    let paths: Vec<&str> = violations
+        .iter()
+        .map(|v| v.instance_path.as_str())
+        .collect();
+    for path in ["/year", "/charge_ports", "/battery", "/range"] {
+        assert!(paths.contains(&path), "{path} not in {paths:?}");
+    }
+}
+
+#[test]
+fn test_layer_schema_keeps_type_errors_for_plain_values() {
+    let files = vec![layer_file(
+        "/data/tesla/model_3/base.json",
+        serde_json::json!({"battery": {"pack_capacity_kwh_net": "78"}}),
+    )];
+
+    let violations =
+        validate_layer_files(&files, &bundled_schema(), Path::new("/data")).expect("valid schema");
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(
+        violations[0].instance_path,
+        "/battery/pack_capacity_kwh_net"
+    );
+    assert!(
+        violations[0]
+            .message
+            .contains("is not of types \"null\", \"number\"")
+    );
+}
@@ -0,0 +1,150 @@
+use ev_etl::cli::WatchArgs;
+use ev_etl::watch::Watcher;
+use rusqlite::Connection;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn write_vehicle(dataset: &Path, make: &str, model: &str, range_km: f64) {
+    let year_dir = dataset.join(make).join(model).join("2024");
+    std::fs::create_dir_all(&year_dir).expect("Failed to create dirs");
+    let vehicle = json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": make, "name": make},
+        "model": {"slug": model, "name": model},
+        "year": 2024,
+        "trim": {"slug": "base", "name": "Base"},
+        "vehicle_type": "passenger_car",
+        "powertrain": {"drivetrain": "rwd"},
+        "battery": {"pack_capacity_kwh_net": 60.0},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {"ac": {"max_power_kw": 11.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": range_km}]},
+        "sources": [{"type": "oem", "title": make, "url": "https://example.com", "accessed_at": "2024-01-01"}]
+    });
+    std::fs::write(
+        year_dir.join(format!("{model}.json")),
+        serde_json::to_string_pretty(&vehicle).unwrap(),
+    )
+    .expect("Failed to write");
+}
+
+fn create_dataset() -> TempDir {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    write_vehicle(dir.path(), "tesla", "model_3", 500.0);
+    write_vehicle(dir.path(), "kia", "ev6", 450.0);
+    dir
+}
+
+fn watch_args(input: &Path, sqlite: Option<PathBuf>) -> WatchArgs {
+    WatchArgs {
+        input: input.to_path_buf(),
+        schema: None,
+        sqlite,
+        interval: 500,
+    }
+}
+
+fn model(make: &str, model: &str) -> (String, String) {
+    (make.to_string(), model.to_string())
+}
+
+fn range_of(watcher: &Watcher, make: &str, model: &str) -> Option<f64> {
+    watcher.build(make, model)?.vehicles[0]
+        .range
+        .wltp_range_km()
+}
+
+#[test]
+fn test_watcher_builds_every_model_on_start() {
+    let dataset = create_dataset();
+
+    let watcher = Watcher::new(&watch_args(dataset.path(), None)).unwrap();
+
+    assert_eq!(range_of(&watcher, "tesla", "model_3"), Some(500.0));
+    assert_eq!(range_of(&watcher, "kia", "ev6"), Some(450.0));
+}
+
+#[test]
+fn test_poll_rebuilds_changed_model_only() {
+    let dataset = create_dataset();
+    let mut watcher = Watcher::new(&watch_args(dataset.path(), None)).unwrap();
+    assert!(watcher.poll().unwrap().is_empty());
+
+    write_vehicle(dataset.path(), "kia", "ev6", 1450.0);
+
+    assert_eq!(
+        watcher.poll().unwrap(),
+        BTreeSet::from([model("kia", "ev6")])
+    );
+    assert_eq!(range_of(&watcher, "kia", "ev6"), Some(1450.0));
+}
+
+#[test]
+fn test_poll_reports_findings_of_changed_model() {
+    let dataset = create_dataset();
+    let mut watcher = Watcher::new(&watch_args(dataset.path(), None)).unwrap();
+
+    write_vehicle(dataset.path(), "kia", "ev6", -10.0);
+    watcher.poll().unwrap();
+
+    let build = watcher.build("kia", "ev6").expect("model still built");
+    assert_eq!(build.report.total_vehicles, 1);
+    assert_eq!(build.report.valid_count, 0);
+    assert!(build.vehicles.is_empty());
+}
+
+#[test]
+fn test_poll_keeps_previous_build_on_error() {
+    let dataset = create_dataset();
+    let mut watcher = Watcher::new(&watch_args(dataset.path(), None)).unwrap();
+
+    std::fs::write(dataset.path().join("kia/ev6/2024/ev6.json"), "{ not json").unwrap();
+
+    assert_eq!(
+        watcher.poll().unwrap(),
+        BTreeSet::from([model("kia", "ev6")])
+    );
+    assert_eq!(range_of(&watcher, "kia", "ev6"), Some(450.0));
+}
+
+#[test]
+fn test_poll_drops_removed_model() {
+    let dataset = create_dataset();
+    let mut watcher = Watcher::new(&watch_args(dataset.path(), None)).unwrap();
+
+    std::fs::remove_dir_all(dataset.path().join("kia/ev6")).unwrap();
+
+    assert_eq!(
+        watcher.poll().unwrap(),
+        BTreeSet::from([model("kia", "ev6")])
+    );
+    assert!(watcher.build("kia", "ev6").is_none());
+    assert!(watcher.build("tesla", "model_3").is_some());
+}
+
+#[test]
+fn test_poll_updates_sqlite() {
+    let dataset = create_dataset();
+    let output = TempDir::new().unwrap();
+    let db_path = output.path().join("vehicles.db");
+    let mut watcher = Watcher::new(&watch_args(dataset.path(), Some(db_path.clone()))).unwrap();
+    let conn = Connection::open(&db_path).unwrap();
+
+    write_vehicle(dataset.path(), "kia", "ev6", 1450.0);
+    watcher.poll().unwrap();
+
+    let range: f64 = conn
+        .query_row(
+            "SELECT range_wltp_km FROM vehicles WHERE make_slug = 'kia'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(range, 1450.0);
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM vehicles", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 2);
+}
@@ -1,8 +1,10 @@
-use ev_server::api::create_router;
+use ev_core::Vehicle;
+use ev_server::api::{ApiDoc, create_router};
 use ev_server::config::Config;
 use ev_server::db::Database;
 use std::sync::Arc;
 use tempfile::TempDir;
+use utoipa::OpenApi;
 
 fn create_test_db() -> (TempDir, Arc<Database>) {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -67,3 +69,62 @@ fn test_create_router_without_features() {
 
     drop(temp_dir);
 }
+
+#[test]
+fn test_api_doc_describes_vehicle_schema() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = &doc["components"]["schemas"];
+
+    assert_eq!(schemas["Vehicle"]["type"], "object");
+    assert!(schemas["Vehicle"]["properties"]["battery"].is_object());
+    for name in ["Battery", "Charging", "ChargePort", "Source", "Drivetrain"] {
+        assert!(schemas[name].is_object(), "missing schema {name}");
+    }
+
+    let response = &doc["paths"]["/vehicles/code/{unique_code}"]["get"]["responses"]["200"]["content"]
+        ["application/json"];
+    assert_eq!(response["schema"]["$ref"], "#/components/schemas/Vehicle");
+}
+
+#[test]
+fn test_api_doc_vehicle_example_deserializes() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let example = doc["paths"]["/vehicles/code/{unique_code}"]["get"]["responses"]["200"]
+        ["content"]["application/json"]["example"]
+        .clone();
+
+    let vehicle: Vehicle = serde_json::from_value(example).expect("example should be a Vehicle");
+    assert_eq!(
+        vehicle.unique_code.as_deref(),
+        Some("byd:dolphin:2024:standard")
+    );
+}
+
+#[test]
+fn test_api_doc_describes_search_and_compare_schemas() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = &doc["components"]["schemas"];
+
+    for name in [
+        "SearchHit",
+        "SearchHighlight",
+        "Suggestion",
+        "CompareResponse",
+        "Comparison",
+        "MetricComparison",
+        "MetricValue",
+        "FieldComparison",
+        "VehicleDiffResponse",
+        "FieldChange",
+        "ChangeKind",
+    ] {
+        assert!(schemas[name].is_object(), "missing schema {name}");
+    }
+    for path in [
+        "/vehicles/suggest",
+        "/vehicles/compare",
+        "/vehicles/{unique_code}/diff/{other_code}",
+    ] {
+        assert!(doc["paths"][path]["get"].is_object(), "missing path {path}");
+    }
+}
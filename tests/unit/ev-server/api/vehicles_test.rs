@@ -5,6 +5,7 @@ use axum::{
 use ev_server::db::Database;
 use http_body_util::BodyExt;
 use rusqlite::Connection;
+use serde_json::json;
 use std::sync::Arc;
 use tempfile::NamedTempFile;
 use tower::ServiceExt;
@@ -40,7 +41,7 @@ async fn test_list_vehicles_pagination() {
         for i in 1..=25 {
             conn.execute(
                 "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
-                 VALUES (?1, 'make', 'Make', 'model', 'Model', 2024, 'Trim', 'bev')",
+                 VALUES (?1, 'make', 'Make', 'model', 'Model', 2024, 'Trim', 'passenger_car')",
                 [format!("code-{}", i)],
             )
             .unwrap();
@@ -170,7 +171,7 @@ async fn test_get_vehicle_by_code_not_found() {
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/vehicles/code/missing-code")
+                .uri("/vehicles/code/byd:dolphin:2024:missing")
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -180,6 +181,43 @@ async fn test_get_vehicle_by_code_not_found() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_get_vehicle_by_code_malformed() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+
+    let db = Arc::new(Database::new(path).unwrap());
+    let app = ev_server::api::vehicles::routes().with_state(db);
+
+    for code in [
+        "missing-code",
+        "byd:dolphin:20x4:base",
+        "BYD:dolphin:2024:base",
+    ] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/vehicles/code/{code}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{code}");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], 400);
+        assert!(
+            json["detail"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid vehicle id")
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_search_vehicles_empty() {
     let file = NamedTempFile::new().unwrap();
@@ -229,6 +267,58 @@ async fn test_search_vehicles_empty() {
     assert!(json["results"].as_array().unwrap().is_empty());
 }
 
+#[tokio::test]
+async fn test_search_vehicles_highlights() {
+    let vehicle: ev_core::Vehicle = serde_json::from_value(json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": "byd", "name": "BYD"},
+        "model": {"slug": "dolphin", "name": "Dolphin"},
+        "year": 2024,
+        "trim": {"slug": "comfort", "name": "Comfort"},
+        "unique_code": "byd:dolphin:2024:comfort",
+        "vehicle_type": "passenger_car",
+        "powertrain": {"drivetrain": "fwd"},
+        "battery": {"pack_capacity_kwh_net": 60.4, "chemistry": "LFP"},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {"dc": {"max_power_kw": 88.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": 427.0}]},
+        "sources": []
+    }))
+    .unwrap();
+    let file = NamedTempFile::new().unwrap();
+    ev_etl::output::sqlite::generate(&[vehicle], file.path()).unwrap();
+
+    let db = Arc::new(Database::new(file.path().to_str().unwrap()).unwrap());
+    let app = ev_server::api::vehicles::routes().with_state(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/vehicles/search?q=dolph%20lfp")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["pagination"]["total"], 1);
+    assert_eq!(
+        json["results"][0]["unique_code"],
+        "byd:dolphin:2024:comfort"
+    );
+    assert_eq!(
+        json["results"][0]["highlights"],
+        json!([
+            {"field": "model_name", "snippet": "<mark>Dolphin</mark>"},
+            {"field": "battery_chemistry", "snippet": "<mark>LFP</mark>"}
+        ])
+    );
+}
+
 #[tokio::test]
 async fn test_search_vehicles_bad_request() {
     let file = NamedTempFile::new().unwrap();
@@ -288,3 +378,410 @@ async fn test_search_vehicles_bad_request() {
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+fn compatibility_fixture(path: &str) {
+    let vehicles = [
+        (
+            "hyundai",
+            "ioniq_5",
+            "ccs2",
+            json!({"max_power_kw": 233.0, "power_limits_by_voltage": [{"voltage_class": "400v", "max_power_kw": 125.0}]}),
+        ),
+        ("nissan", "leaf", "chademo", json!({"max_power_kw": 50.0})),
+        ("tesla", "model_3", "ccs2", json!({"max_power_kw": 170.0})),
+    ]
+    .map(|(make, model, connector, dc)| {
+        serde_json::from_value::<ev_core::Vehicle>(json!({
+            "schema_version": "1.0.0",
+            "make": {"slug": make, "name": make.to_uppercase()},
+            "model": {"slug": model, "name": model.to_uppercase()},
+            "year": 2024,
+            "trim": {"slug": "base", "name": "Base"},
+            "vehicle_type": "passenger_car",
+            "powertrain": {"drivetrain": "rwd"},
+            "battery": {"pack_capacity_kwh_net": 60.0},
+            "charge_ports": [{"kind": "combo", "connector": connector}],
+            "charging": {"dc": dc},
+            "range": {"rated": [{"cycle": "wltp", "range_km": 400.0}]},
+            "sources": [],
+            "unique_code": format!("{make}:{model}:2024:{model}")
+        }))
+        .unwrap()
+    });
+
+    ev_etl::output::sqlite::generate(&vehicles, std::path::Path::new(path)).unwrap();
+}
+
+#[tokio::test]
+async fn test_list_compatible_vehicles() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    compatibility_fixture(path);
+
+    let db = Arc::new(Database::new(path).unwrap());
+    let app = ev_server::api::vehicles::routes().with_state(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/vehicles/compatible?connector=ccs2&max_power_kw=150&voltage_class=400v")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["charger"]["current"], "dc");
+    assert_eq!(json["pagination"]["total"], 2);
+    let vehicles = json["vehicles"].as_array().unwrap();
+    assert_eq!(vehicles[0]["unique_code"], "hyundai:ioniq_5:2024:ioniq_5");
+    assert_eq!(vehicles[0]["compatibility"]["max_power_kw"], 125.0);
+    assert_eq!(vehicles[0]["compatibility"]["limited_by"], "voltage_class");
+    assert_eq!(vehicles[1]["compatibility"]["max_power_kw"], 150.0);
+    assert_eq!(vehicles[1]["compatibility"]["limited_by"], "charger");
+}
+
+#[tokio::test]
+async fn test_list_compatible_vehicles_invalid_connector() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    compatibility_fixture(path);
+
+    let db = Arc::new(Database::new(path).unwrap());
+    let app = ev_server::api::vehicles::routes().with_state(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/vehicles/compatible?connector=scart&max_power_kw=50")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_list_vehicles_vehicle_type_filter() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+
+    {
+        let conn = Connection::open(path).unwrap();
+        conn.execute(
+            "CREATE TABLE vehicles (
+                id INTEGER PRIMARY KEY,
+                unique_code TEXT NOT NULL,
+                make_slug TEXT NOT NULL,
+                make_name TEXT NOT NULL,
+                model_slug TEXT NOT NULL,
+                model_name TEXT NOT NULL,
+                year INTEGER NOT NULL,
+                trim_name TEXT NOT NULL,
+                variant_name TEXT,
+                vehicle_type TEXT NOT NULL,
+                battery_capacity_net_kwh REAL,
+                range_wltp_km REAL,
+                range_epa_km REAL,
+                dc_max_power_kw REAL
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
+             VALUES ('a:b:2024:c', 'make', 'Make', 'model', 'Model', 2024, 'Trim', 'passenger_car'),
+                    ('a:d:2024:c', 'make', 'Make', 'other', 'Other', 2024, 'Trim', 'suv')",
+            [],
+        )
+        .unwrap();
+    }
+
+    let db = Arc::new(Database::new(path).unwrap());
+    let app = ev_server::api::vehicles::routes().with_state(db);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/vehicles/list?vehicle_type=suv")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["pagination"]["total"], 1);
+    assert_eq!(json["vehicles"][0]["vehicle_type"], "suv");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/vehicles/list?vehicle_type=bev")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let detail = json["detail"].as_str().unwrap();
+    assert!(detail.contains("'bev'"));
+    assert!(detail.contains("passenger_car, suv"));
+}
+
+#[tokio::test]
+async fn test_list_vehicles_invalid_advanced_filters() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    compatibility_fixture(path);
+
+    let db = Arc::new(Database::new(path).unwrap());
+    let app = ev_server::api::vehicles::routes().with_state(db);
+
+    for uri in [
+        "/vehicles/list?max_price=50000",
+        "/vehicles/list?min_price=1&currency=eur",
+        "/vehicles/list?market=germany",
+        "/vehicles/list?drivetrain=2wd",
+        "/vehicles/list?connector=scart",
+        "/vehicles/list?availability=sold_out",
+    ] {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
+fn etl_app(names: &[(&str, &str, &str)]) -> (NamedTempFile, axum::Router) {
+    let vehicles: Vec<ev_core::Vehicle> = names
+        .iter()
+        .map(|(make, model, trim)| {
+            let slug = |name: &str| name.to_lowercase().replace(' ', "_");
+            serde_json::from_value(json!({
+                "schema_version": "1.0.0",
+                "make": {"slug": slug(make), "name": make},
+                "model": {"slug": slug(model), "name": model},
+                "year": 2024,
+                "trim": {"slug": slug(trim), "name": trim},
+                "unique_code": format!("{}:{}:2024:{}", slug(make), slug(model), slug(trim)),
+                "vehicle_type": "passenger_car",
+                "powertrain": {"drivetrain": "rwd"},
+                "battery": {"pack_capacity_kwh_net": 60.0},
+                "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+                "charging": {"dc": {"max_power_kw": 170.0}},
+                "range": {"rated": [{"cycle": "wltp", "range_km": 450.0}]},
+                "sources": []
+            }))
+            .unwrap()
+        })
+        .collect();
+    let file = NamedTempFile::new().unwrap();
+    ev_etl::output::sqlite::generate(&vehicles, file.path()).unwrap();
+
+    let db = Arc::new(Database::new(file.path().to_str().unwrap()).unwrap());
+    (file, ev_server::api::vehicles::routes().with_state(db))
+}
+
+async fn get_json(app: axum::Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_search_vehicles_fuzzy_fallback() {
+    let (_file, app) = etl_app(&[
+        ("Tesla", "Model 3", "Long Range"),
+        ("Hyundai", "Ioniq 5", "Long Range"),
+    ]);
+
+    let (status, json) = get_json(app.clone(), "/vehicles/search?q=tesls%20modle%203").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["corrected_query"], "tesla model 3");
+    assert_eq!(json["pagination"]["total"], 1);
+    assert_eq!(
+        json["results"][0]["unique_code"],
+        "tesla:model_3:2024:long_range"
+    );
+
+    let (_, json) = get_json(app.clone(), "/vehicles/search?q=ionic%205").await;
+    assert_eq!(json["corrected_query"], "ioniq 5");
+    assert_eq!(
+        json["results"][0]["unique_code"],
+        "hyundai:ioniq_5:2024:long_range"
+    );
+
+    let (_, json) = get_json(app.clone(), "/vehicles/search?q=tesla").await;
+    assert!(json.get("corrected_query").is_none());
+
+    let (_, json) = get_json(app, "/vehicles/search?q=porsche").await;
+    assert!(json.get("corrected_query").is_none());
+    assert!(json["results"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_suggest_vehicles() {
+    let (_file, app) = etl_app(&[
+        ("Tesla", "Model 3", "Long Range"),
+        ("Tesla", "Model Y", "Performance"),
+    ]);
+
+    let (status, json) = get_json(app.clone(), "/vehicles/suggest?q=tesls%20modle%203").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["suggestions"][0]["kind"], "model");
+    assert_eq!(json["suggestions"][0]["label"], "Tesla Model 3");
+    assert_eq!(json["suggestions"][0]["model_slug"], "model_3");
+
+    let (_, json) = get_json(app.clone(), "/vehicles/suggest?q=tes&limit=2").await;
+    assert_eq!(json["suggestions"].as_array().unwrap().len(), 2);
+    assert_eq!(json["suggestions"][0]["kind"], "make");
+    assert_eq!(json["suggestions"][0]["label"], "Tesla");
+
+    let (status, json) = get_json(app, "/vehicles/suggest?q=t").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["detail"], "Search query must be at least 2 characters");
+}
+
+#[tokio::test]
+async fn test_compare_vehicles() {
+    let (_file, app) = etl_app(&[("Kia", "EV6", "GT"), ("Tesla", "Model 3", "Long Range")]);
+
+    let (status, json) = get_json(
+        app.clone(),
+        "/vehicles/compare?codes=tesla:model_3:2024:long_range,%20kia:ev6:2024:gt",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json["vehicles"][0]["unique_code"],
+        "tesla:model_3:2024:long_range"
+    );
+    assert_eq!(json["vehicles"][1]["unique_code"], "kia:ev6:2024:gt");
+
+    let range = json["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|metric| metric["key"] == "range_wltp_km")
+        .unwrap();
+    assert_eq!(range["better"], "higher");
+    assert_eq!(range["values"][0]["value"], 450.0);
+    assert_eq!(range["values"][1]["delta_to_baseline"], 0.0);
+
+    let make = json["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|field| field["path"] == "/make/name")
+        .unwrap();
+    assert_eq!(make["values"], json!(["Tesla", "Kia"]));
+    assert_eq!(make["differs"], true);
+}
+
+#[tokio::test]
+async fn test_compare_vehicles_errors() {
+    let (_file, app) = etl_app(&[("Kia", "EV6", "GT"), ("Tesla", "Model 3", "Long Range")]);
+
+    let cases = [
+        ("codes=kia:ev6:2024:gt", StatusCode::BAD_REQUEST),
+        (
+            "codes=kia:ev6:2024:gt,kia:ev6:2024:gt",
+            StatusCode::BAD_REQUEST,
+        ),
+        ("codes=kia:ev6:2024:gt,not-a-code", StatusCode::BAD_REQUEST),
+        (
+            "codes=kia:ev6:2024:gt,tesla:model_3:2024:long_range&currency=euro",
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            "codes=kia:ev6:2024:gt,tesla:model_y:2024:base",
+            StatusCode::NOT_FOUND,
+        ),
+    ];
+    for (query, expected) in cases {
+        let (status, json) = get_json(app.clone(), &format!("/vehicles/compare?{query}")).await;
+        assert_eq!(status, expected, "{query}: {json}");
+    }
+
+    let (_, json) = get_json(app, "/vehicles/compare?codes=kia:ev6:2024:gt").await;
+    assert_eq!(json["detail"], "Compare between 2 and 10 vehicles, got 1");
+}
+
+#[tokio::test]
+async fn test_diff_vehicles() {
+    let (_file, app) = etl_app(&[
+        ("Tesla", "Model 3", "Long Range"),
+        ("Tesla", "Model 3", "Performance"),
+    ]);
+
+    let (status, json) = get_json(
+        app.clone(),
+        "/vehicles/tesla:model_3:2024:long_range/diff/tesla:model_3:2024:performance",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["from"], "tesla:model_3:2024:long_range");
+    assert_eq!(json["to"], "tesla:model_3:2024:performance");
+    assert_eq!(
+        json["changes"],
+        json!([
+            {"path": "/trim/name", "kind": "changed", "old": "Long Range", "new": "Performance"},
+            {"path": "/trim/slug", "kind": "changed", "old": "long_range", "new": "performance"},
+            {
+                "path": "/unique_code",
+                "kind": "changed",
+                "old": "tesla:model_3:2024:long_range",
+                "new": "tesla:model_3:2024:performance"
+            }
+        ])
+    );
+
+    let (status, json) = get_json(
+        app.clone(),
+        "/vehicles/tesla:model_3:2024:long_range/diff/tesla:model_3:2024:long_range",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["changes"], json!([]));
+
+    let (status, json) = get_json(
+        app.clone(),
+        "/vehicles/tesla:model_3:02024:long_range/diff/tesla:model_3:2024:performance",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["from"], "tesla:model_3:2024:long_range");
+
+    let (status, _) = get_json(
+        app.clone(),
+        "/vehicles/tesla:model_3:2024:long_range/diff/tesla:model_y:2024:base",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = get_json(
+        app,
+        "/vehicles/not-a-code/diff/tesla:model_3:2024:long_range",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -1,4 +1,7 @@
-use ev_server::db::{ListParams, SqliteDatabase};
+use ev_core::VehicleType;
+use ev_server::db::{
+    Database, ListParams, PriceRange, SearchHighlight, SqliteDatabase, VehicleRepository,
+};
 use rusqlite::Connection;
 use tempfile::NamedTempFile;
 
@@ -11,6 +14,18 @@ fn test_sqlite_db_init() {
     // Also internal conn is private, so we test behavior via methods.
 }
 
+#[test]
+fn test_database_new_selects_sqlite_backend() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+
+    let db = Database::new(path).unwrap();
+    assert_eq!(db.backend_name(), "sqlite");
+
+    let db = Database::new(&format!("sqlite://{}", path)).unwrap();
+    assert_eq!(db.backend_name(), "sqlite");
+}
+
 #[test]
 fn test_get_vehicle_count() {
     let file = NamedTempFile::new().unwrap();
@@ -59,11 +74,11 @@ fn test_list_vehicles_filtering() {
 
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type, range_wltp_km)
-             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'bev', 513.0)", []
+             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'passenger_car', 513.0)", []
         ).unwrap();
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type, range_wltp_km)
-             VALUES ('t2', 'tesla', 'Tesla', 'model_y', 'Model Y', 2024, 'RWD', 'bev', 455.0)", []
+             VALUES ('t2', 'tesla', 'Tesla', 'model_y', 'Model Y', 2024, 'RWD', 'passenger_car', 455.0)", []
         ).unwrap();
     }
 
@@ -127,11 +142,11 @@ fn test_list_vehicles_year_filter() {
 
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
-             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'bev')", []
+             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'passenger_car')", []
         ).unwrap();
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
-             VALUES ('t2', 'tesla', 'Tesla', 'model_3', 'Model 3', 2023, 'RWD', 'bev')", []
+             VALUES ('t2', 'tesla', 'Tesla', 'model_3', 'Model 3', 2023, 'RWD', 'passenger_car')", []
         ).unwrap();
     }
 
@@ -176,7 +191,7 @@ fn test_list_vehicles_vehicle_type_filter() {
 
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
-             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'bev')", []
+             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'passenger_car')", []
         ).unwrap();
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
@@ -187,12 +202,12 @@ fn test_list_vehicles_vehicle_type_filter() {
     let db = SqliteDatabase::new(path).unwrap();
 
     let params = ListParams {
-        vehicle_type: Some("bev".to_string()),
+        vehicle_type: Some(VehicleType::PassengerCar),
         ..Default::default()
     };
     let (vehicles, total) = db.list_vehicles(&params).unwrap();
     assert_eq!(total, 1);
-    assert_eq!(vehicles[0].vehicle_type, "bev");
+    assert_eq!(vehicles[0].vehicle_type, "passenger_car");
 }
 
 #[test]
@@ -225,11 +240,11 @@ fn test_list_vehicles_max_range_filter() {
 
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type, range_wltp_km)
-             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'bev', 513.0)", []
+             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'passenger_car', 513.0)", []
         ).unwrap();
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type, range_wltp_km)
-             VALUES ('t2', 'byd', 'BYD', 'dolphin', 'Dolphin', 2024, 'Base', 'bev', 340.0)", []
+             VALUES ('t2', 'byd', 'BYD', 'dolphin', 'Dolphin', 2024, 'Base', 'passenger_car', 340.0)", []
         ).unwrap();
     }
 
@@ -274,11 +289,11 @@ fn test_list_vehicles_sorting() {
 
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type, range_wltp_km)
-             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'bev', 513.0)", []
+             VALUES ('t1', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'passenger_car', 513.0)", []
         ).unwrap();
         conn.execute(
             "INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type, range_wltp_km)
-             VALUES ('t2', 'byd', 'BYD', 'dolphin', 'Dolphin', 2024, 'Base', 'bev', 340.0)", []
+             VALUES ('t2', 'byd', 'BYD', 'dolphin', 'Dolphin', 2024, 'Base', 'passenger_car', 340.0)", []
         ).unwrap();
     }
 
@@ -333,7 +348,7 @@ fn test_list_vehicles_pagination() {
 
         for i in 1..=5 {
             conn.execute(
-                &format!("INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type) VALUES ('t{}', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'bev')", i), []
+                &format!("INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type) VALUES ('t{}', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'RWD', 'passenger_car')", i), []
             ).unwrap();
         }
     }
@@ -360,3 +375,368 @@ fn test_list_vehicles_pagination() {
     assert_eq!(total, 5);
     assert_eq!(vehicles.len(), 1);
 }
+
+fn filter_vehicle(make: &str, model: &str, extra: serde_json::Value) -> ev_core::Vehicle {
+    let mut vehicle = serde_json::json!({
+        "schema_version": "1.0.0",
+        "make": {"slug": make, "name": make.to_uppercase()},
+        "model": {"slug": model, "name": model.to_uppercase()},
+        "year": 2024,
+        "trim": {"slug": "base", "name": "Base"},
+        "unique_code": format!("{make}:{model}:2024:base"),
+        "vehicle_type": "passenger_car",
+        "powertrain": {"drivetrain": "rwd"},
+        "battery": {"pack_capacity_kwh_net": 60.0},
+        "charge_ports": [{"kind": "combo", "connector": "ccs2"}],
+        "charging": {"dc": {"max_power_kw": 170.0}, "ac": {"max_power_kw": 11.0}},
+        "range": {"rated": [{"cycle": "wltp", "range_km": 450.0}]},
+        "sources": []
+    });
+    for (key, value) in extra.as_object().unwrap() {
+        vehicle[key] = value.clone();
+    }
+    serde_json::from_value(vehicle).unwrap()
+}
+
+#[test]
+fn test_sqlite_list_vehicles_advanced_filters() {
+    let vehicles = vec![
+        filter_vehicle(
+            "kia",
+            "ev6",
+            serde_json::json!({
+                "powertrain": {"drivetrain": "awd"},
+                "battery": {"pack_capacity_kwh_net": 77.4, "heat_pump": true},
+                "charging": {"dc": {"max_power_kw": 258.0}, "ac": {"max_power_kw": 11.0}},
+                "performance": {"acceleration_0_100_kmh_s": 3.5},
+                "v2x": {"v2l": {"supported": true}, "v2g": {"supported": false}},
+                "markets": ["DE", "FR"],
+                "availability": {"status": "production"},
+                "pricing": {"msrp": [{"currency": "EUR", "amount": 69990.0}]}
+            }),
+        ),
+        filter_vehicle(
+            "nissan",
+            "leaf",
+            serde_json::json!({
+                "battery": {"pack_capacity_kwh_net": 39.0, "heat_pump": false},
+                "charge_ports": [
+                    {"kind": "ac_only", "connector": "type2"},
+                    {"kind": "dc_only", "connector": "chademo"}
+                ],
+                "charging": {"dc": {"max_power_kw": 50.0}, "ac": {"max_power_kw": 6.6}},
+                "v2x": {"v2g": {"supported": true}},
+                "markets": ["DE"],
+                "availability": {"status": "discontinued"},
+                "pricing": {"msrp": [
+                    {"currency": "EUR", "amount": 29990.0},
+                    {"currency": "USD", "amount": 28140.0}
+                ]}
+            }),
+        ),
+        filter_vehicle("tesla", "model_3", serde_json::json!({"markets": ["US"]})),
+    ];
+    let file = NamedTempFile::new().unwrap();
+    ev_etl::output::sqlite::generate(&vehicles, file.path()).unwrap();
+    let db = SqliteDatabase::new(file.path().to_str().unwrap()).unwrap();
+
+    let codes = |params: ListParams| -> Vec<String> {
+        db.list_vehicles(&params)
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|v| v.unique_code)
+            .collect()
+    };
+
+    assert_eq!(
+        codes(ListParams {
+            drivetrain: Some(ev_core::Drivetrain::Awd),
+            ..Default::default()
+        }),
+        vec!["kia:ev6:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            connector: Some(ev_core::ConnectorType::Chademo),
+            ..Default::default()
+        }),
+        vec!["nissan:leaf:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            min_dc_power_kw: Some(150.0),
+            min_ac_power_kw: Some(11.0),
+            ..Default::default()
+        }),
+        vec!["kia:ev6:2024:base", "tesla:model_3:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            min_battery_kwh: Some(40.0),
+            max_battery_kwh: Some(70.0),
+            ..Default::default()
+        }),
+        vec!["tesla:model_3:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            max_acceleration_0_100_s: Some(4.0),
+            ..Default::default()
+        }),
+        vec!["kia:ev6:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            v2g: Some(true),
+            ..Default::default()
+        }),
+        vec!["nissan:leaf:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            v2l: Some(true),
+            heat_pump: Some(true),
+            ..Default::default()
+        }),
+        vec!["kia:ev6:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            market: Some("DE".to_string()),
+            availability: Some(ev_core::AvailabilityStatus::Discontinued),
+            ..Default::default()
+        }),
+        vec!["nissan:leaf:2024:base"]
+    );
+    assert_eq!(
+        codes(ListParams {
+            price: Some(PriceRange {
+                currency: "EUR".to_string(),
+                min: None,
+                max: Some(50000.0),
+            }),
+            ..Default::default()
+        }),
+        vec!["nissan:leaf:2024:base"]
+    );
+    assert!(
+        codes(ListParams {
+            price: Some(PriceRange {
+                currency: "USD".to_string(),
+                min: Some(30000.0),
+                max: None,
+            }),
+            ..Default::default()
+        })
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_sqlite_list_vehicles_with_ports() {
+    let (_file, db) = search_database(&[
+        filter_vehicle(
+            "nissan",
+            "leaf",
+            serde_json::json!({"charge_ports": [
+                {"kind": "ac_only", "connector": "type2"},
+                {"kind": "dc_only", "connector": "chademo"}
+            ]}),
+        ),
+        filter_vehicle(
+            "tesla",
+            "model_3",
+            serde_json::json!({"charge_ports": [{"kind": "combo", "connector": "nacs"}]}),
+        ),
+        filter_vehicle("kia", "ev6", serde_json::json!({})),
+    ]);
+
+    let codes = |connectors: &[ev_core::ConnectorType]| -> Vec<String> {
+        db.list_vehicles_with_ports(connectors)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.unique_code.unwrap())
+            .collect()
+    };
+
+    assert_eq!(
+        codes(&[
+            ev_core::ConnectorType::Chademo,
+            ev_core::ConnectorType::Nacs
+        ]),
+        vec!["nissan:leaf:2024:base", "tesla:model_3:2024:base"]
+    );
+    assert_eq!(
+        codes(&[ev_core::ConnectorType::Ccs2]),
+        vec!["kia:ev6:2024:base"]
+    );
+    assert!(codes(&[ev_core::ConnectorType::GbTDc]).is_empty());
+}
+
+#[tokio::test]
+async fn test_database_vehicle_names_are_read_once() {
+    let file = NamedTempFile::new().unwrap();
+    ev_etl::output::sqlite::generate(
+        &[filter_vehicle("kia", "ev6", serde_json::json!({}))],
+        file.path(),
+    )
+    .unwrap();
+    let db = Database::new(file.path().to_str().unwrap()).unwrap();
+    assert_eq!(db.vehicle_names().await.unwrap().len(), 1);
+
+    ev_etl::output::sqlite::refresh(
+        &[
+            filter_vehicle("kia", "ev6", serde_json::json!({})),
+            filter_vehicle("byd", "dolphin", serde_json::json!({})),
+        ],
+        file.path(),
+    )
+    .unwrap();
+
+    assert_eq!(db.get_vehicle_count().await.unwrap(), 2);
+    assert_eq!(db.vehicle_names().await.unwrap().len(), 1);
+}
+
+fn search_database(vehicles: &[ev_core::Vehicle]) -> (NamedTempFile, SqliteDatabase) {
+    let file = NamedTempFile::new().unwrap();
+    ev_etl::output::sqlite::generate(vehicles, file.path()).unwrap();
+    let db = SqliteDatabase::new(file.path().to_str().unwrap()).unwrap();
+    (file, db)
+}
+
+#[test]
+fn test_sqlite_search_matches_indexed_fields() {
+    let (_file, db) = search_database(&[
+        filter_vehicle(
+            "kia",
+            "ev6",
+            serde_json::json!({
+                "body": {"style": "crossover", "platform": "E-GMP"},
+                "battery": {"pack_capacity_kwh_net": 77.4, "chemistry": "NMC"},
+                "markets": ["DE", "FR"]
+            }),
+        ),
+        filter_vehicle(
+            "byd",
+            "dolphin",
+            serde_json::json!({
+                "model": {"slug": "dolphin", "name": "Dolphin"},
+                "battery": {"pack_capacity_kwh_net": 60.4, "chemistry": "LFP"},
+                "markets": ["DE"]
+            }),
+        ),
+    ]);
+
+    let codes = |query: &str| -> Vec<String> {
+        db.search(query, 1, 20)
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|hit| hit.vehicle.unique_code)
+            .collect()
+    };
+
+    assert_eq!(codes("dolph"), vec!["byd:dolphin:2024:base"]);
+    assert_eq!(codes("gmp"), vec!["kia:ev6:2024:base"]);
+    assert_eq!(codes("lfp"), vec!["byd:dolphin:2024:base"]);
+    assert_eq!(codes("fr"), vec!["kia:ev6:2024:base"]);
+    assert_eq!(codes("crossover de"), vec!["kia:ev6:2024:base"]);
+    assert_eq!(codes("de").len(), 2);
+    assert!(codes("dolphin fr").is_empty());
+    assert_eq!(codes("\"kia"), vec!["kia:ev6:2024:base"]);
+    assert!(codes("--").is_empty());
+
+    let (hits, total) = db.search("dolph", 1, 20).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(
+        hits[0].highlights,
+        vec![SearchHighlight {
+            field: "model_name".to_string(),
+            snippet: "<mark>Dolphin</mark>".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_sqlite_search_escapes_highlighted_text() {
+    let (_file, db) = search_database(&[filter_vehicle(
+        "kia",
+        "ev6",
+        serde_json::json!({"trim": {"slug": "gt", "name": "GT <b>\"R&D\"</b>"}}),
+    )]);
+
+    let (hits, _) = db.search("gt", 1, 20).unwrap();
+    assert_eq!(
+        hits[0].highlights,
+        vec![SearchHighlight {
+            field: "trim_name".to_string(),
+            snippet: "<mark>GT</mark> &lt;b&gt;&quot;R&amp;D&quot;&lt;/b&gt;".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_sqlite_search_ranks_identity_fields_first() {
+    let (_file, db) = search_database(&[
+        filter_vehicle(
+            "audi",
+            "a6",
+            serde_json::json!({"body": {"style": "sedan"}}),
+        ),
+        filter_vehicle("zeekr", "sedan", serde_json::json!({})),
+    ]);
+
+    let (hits, total) = db.search("sedan", 1, 20).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(hits[0].vehicle.unique_code, "zeekr:sedan:2024:base");
+    assert_eq!(hits[1].vehicle.unique_code, "audi:a6:2024:base");
+    assert_eq!(hits[1].highlights[0].field, "body_style");
+
+    let (page, total) = db.search("sedan", 2, 1).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(page[0].vehicle.unique_code, "audi:a6:2024:base");
+}
+
+#[test]
+fn test_sqlite_search_without_fts_table() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+
+    {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE vehicles (
+                id INTEGER PRIMARY KEY,
+                unique_code TEXT NOT NULL,
+                make_slug TEXT NOT NULL,
+                make_name TEXT NOT NULL,
+                model_slug TEXT NOT NULL,
+                model_name TEXT NOT NULL,
+                year INTEGER NOT NULL,
+                trim_name TEXT NOT NULL,
+                variant_name TEXT,
+                vehicle_type TEXT NOT NULL,
+                battery_capacity_net_kwh REAL,
+                range_wltp_km REAL,
+                range_epa_km REAL,
+                dc_max_power_kw REAL
+            );
+            INSERT INTO vehicles (unique_code, make_slug, make_name, model_slug, model_name, year, trim_name, vehicle_type)
+            VALUES ('tesla:model_3:2024:base', 'tesla', 'Tesla', 'model_3', 'Model 3', 2024, 'Base', 'passenger_car');",
+        )
+        .unwrap();
+    }
+
+    let db = SqliteDatabase::new(path).unwrap();
+    let (hits, total) = db.search("odel", 1, 20).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(hits[0].vehicle.unique_code, "tesla:model_3:2024:base");
+    assert!(hits[0].highlights.is_empty());
+
+    for wildcard in ["__", "%", "\\"] {
+        let (hits, total) = db.search(wildcard, 1, 20).unwrap();
+        assert_eq!(total, 0, "{wildcard} must match literally");
+        assert!(hits.is_empty());
+    }
+}
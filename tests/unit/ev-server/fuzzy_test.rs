@@ -0,0 +1,135 @@
+use ev_server::db::VehicleName;
+use ev_server::fuzzy::{SuggestionKind, correct_query, edit_distance, suggest};
+
+fn name(code: &str, make: &str, model: &str, year: u16, trim: &str) -> VehicleName {
+    let slug = |name: &str| name.to_lowercase().replace(' ', "_");
+    VehicleName {
+        unique_code: code.to_string(),
+        make_slug: slug(make),
+        make_name: make.to_string(),
+        model_slug: slug(model),
+        model_name: model.to_string(),
+        year,
+        trim_name: trim.to_string(),
+    }
+}
+
+fn names() -> Vec<VehicleName> {
+    vec![
+        name(
+            "hyundai:ioniq_5:2024:long_range",
+            "Hyundai",
+            "Ioniq 5",
+            2024,
+            "Long Range",
+        ),
+        name(
+            "tesla:model_3:2023:long_range",
+            "Tesla",
+            "Model 3",
+            2023,
+            "Long Range",
+        ),
+        name(
+            "tesla:model_3:2024:long_range",
+            "Tesla",
+            "Model 3",
+            2024,
+            "Long Range",
+        ),
+        name(
+            "tesla:model_3:2024:long_range:awd",
+            "Tesla",
+            "Model 3",
+            2024,
+            "Long Range",
+        ),
+        name(
+            "tesla:model_y:2024:performance",
+            "Tesla",
+            "Model Y",
+            2024,
+            "Performance",
+        ),
+    ]
+}
+
+#[test]
+fn test_edit_distance_counts_edits() {
+    assert_eq!(edit_distance("tesla", "tesla"), 0);
+    assert_eq!(edit_distance("tesls", "tesla"), 1);
+    assert_eq!(edit_distance("modle", "model"), 1);
+    assert_eq!(edit_distance("ionic", "ioniq"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+}
+
+#[test]
+fn test_suggest_tolerates_typos() {
+    let suggestions = suggest(&names(), "tesls modle 3", 10);
+
+    assert_eq!(suggestions[0].kind, SuggestionKind::Model);
+    assert_eq!(suggestions[0].label, "Tesla Model 3");
+    assert_eq!(suggestions[0].model_slug.as_deref(), Some("model_3"));
+    assert!(suggestions.iter().all(|s| s.label.contains("Model 3")));
+
+    let suggestions = suggest(&names(), "ionic 5", 10);
+    assert_eq!(suggestions[0].label, "Hyundai Ioniq 5");
+}
+
+#[test]
+fn test_suggest_completes_prefixes() {
+    let suggestions = suggest(&names(), "tes", 10);
+
+    assert_eq!(suggestions[0].kind, SuggestionKind::Make);
+    assert_eq!(suggestions[0].label, "Tesla");
+    assert_eq!(suggestions[0].model_slug, None);
+    assert!(suggestions.windows(2).all(|w| w[0].score >= w[1].score));
+
+    let labels: Vec<_> = suggest(&names(), "tesla model y perf", 10)
+        .into_iter()
+        .map(|s| s.label)
+        .collect();
+    assert_eq!(labels, vec!["Tesla Model Y Performance"]);
+
+    let labels: Vec<_> = suggest(&names(), "tesla model y perfro", 10)
+        .into_iter()
+        .map(|s| s.label)
+        .collect();
+    assert_eq!(labels, vec!["Tesla Model Y Performance"]);
+}
+
+#[test]
+fn test_suggest_trim_points_at_newest_vehicle() {
+    let trim = suggest(&names(), "model 3 long range", 10)
+        .into_iter()
+        .find(|s| s.kind == SuggestionKind::Trim)
+        .unwrap();
+
+    assert_eq!(trim.label, "Tesla Model 3 Long Range");
+    assert_eq!(
+        trim.unique_code.as_deref(),
+        Some("tesla:model_3:2024:long_range")
+    );
+}
+
+#[test]
+fn test_suggest_limit_and_no_match() {
+    assert_eq!(suggest(&names(), "tesla", 2).len(), 2);
+    assert!(suggest(&names(), "porsche", 10).is_empty());
+    assert!(suggest(&names(), "--", 10).is_empty());
+}
+
+#[test]
+fn test_correct_query_replaces_unknown_words() {
+    assert_eq!(
+        correct_query(&names(), "tesls modle 3").as_deref(),
+        Some("tesla model 3")
+    );
+    assert_eq!(
+        correct_query(&names(), "Ionic 5").as_deref(),
+        Some("ioniq 5")
+    );
+    assert_eq!(correct_query(&names(), "tesla mod"), None);
+    assert_eq!(correct_query(&names(), "porsche"), None);
+}
@@ -2,4 +2,5 @@ mod api;
 mod config_test;
 mod db;
 mod error_test;
+mod fuzzy_test;
 mod models_test;